@@ -0,0 +1,298 @@
+//! A quick check of a template's syntax, to catch the mistakes we can report
+//! against the template file before handing it to the `html!` macro.
+
+type Position = (usize, usize);
+type CheckError = (usize, usize, String);
+
+#[derive(PartialEq, Debug)]
+enum Token {
+    Ident(String),
+    Punct(char),
+    Literal,
+    Open(char),
+    Close(char),
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Lexer {
+            chars: source.chars().peekable(),
+            line: 1,
+            column: 1,
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.chars.peek() == Some(&c) {
+            self.bump();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error<A>(&self, position: Position, message: &str) -> Result<A, CheckError> {
+        Err((position.0, position.1, message.to_string()))
+    }
+
+    fn string(&mut self, start: Position) -> Result<Token, CheckError> {
+        loop {
+            match self.bump() {
+                None => return self.error(start, "unterminated string"),
+                Some('"') => return Ok(Token::Literal),
+                Some('\\') => {
+                    self.bump();
+                }
+                Some(_) => (),
+            }
+        }
+    }
+
+    fn raw_string(&mut self, start: Position) -> Result<Token, CheckError> {
+        let mut hashes = 0;
+        while self.eat('#') {
+            hashes += 1;
+        }
+        if !self.eat('"') {
+            // A raw identifier, like `r#type`.
+            let mut ident = String::new();
+            while let Some(&c) = self.chars.peek() {
+                if !is_ident_char(c) {
+                    break;
+                }
+                ident.push(c);
+                self.bump();
+            }
+            if hashes != 1 || ident.is_empty() {
+                return self.error(start, "invalid raw string");
+            }
+            return Ok(Token::Ident(ident));
+        }
+        loop {
+            match self.bump() {
+                None => return self.error(start, "unterminated string"),
+                Some('"') => {
+                    let mut closing = 0;
+                    while closing < hashes && self.eat('#') {
+                        closing += 1;
+                    }
+                    if closing == hashes {
+                        return Ok(Token::Literal);
+                    }
+                }
+                Some(_) => (),
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Option<Result<(Token, Position), CheckError>> {
+        loop {
+            let start = (self.line, self.column);
+            let c = self.bump()?;
+            let token = match c {
+                c if c.is_whitespace() => continue,
+                '/' if self.eat('/') => {
+                    while self.chars.peek().is_some_and(|c| *c != '\n') {
+                        self.bump();
+                    }
+                    continue;
+                }
+                '/' if self.eat('*') => {
+                    let mut depth = 1;
+                    while depth > 0 {
+                        match self.bump() {
+                            None => return Some(self.error(start, "unterminated comment")),
+                            Some('*') if self.eat('/') => depth -= 1,
+                            Some('/') if self.eat('*') => depth += 1,
+                            Some(_) => (),
+                        }
+                    }
+                    continue;
+                }
+                '"' => self.string(start),
+                'r' if matches!(self.chars.peek(), Some('"') | Some('#')) => self.raw_string(start),
+                'b' if self.eat('"') => self.string(start),
+                '\'' => {
+                    // Either a char literal or a lifetime.
+                    let first = self.bump();
+                    if first == Some('\\') {
+                        self.bump();
+                        while self.chars.peek().is_some_and(|c| *c != '\'') {
+                            self.bump();
+                        }
+                    }
+                    if self.eat('\'') {
+                        Ok(Token::Literal)
+                    } else {
+                        while self.chars.peek().is_some_and(|c| is_ident_char(*c)) {
+                            self.bump();
+                        }
+                        Ok(Token::Literal)
+                    }
+                }
+                '(' | '[' | '{' => Ok(Token::Open(c)),
+                ')' | ']' | '}' => Ok(Token::Close(c)),
+                c if is_ident_char(c) => {
+                    let mut ident = c.to_string();
+                    while let Some(&c) = self.chars.peek() {
+                        if !is_ident_char(c) {
+                            break;
+                        }
+                        ident.push(c);
+                        self.bump();
+                    }
+                    Ok(Token::Ident(ident))
+                }
+                c => Ok(Token::Punct(c)),
+            };
+            return Some(token.map(|token| (token, start)));
+        }
+    }
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn closing(open: char) -> char {
+    match open {
+        '(' => ')',
+        '[' => ']',
+        _ => '}',
+    }
+}
+
+/// Check that a template's delimiters are balanced, its strings and comments
+/// are terminated, and its tags match.
+pub fn check(source: &str) -> Result<(), CheckError> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+    let mut groups: Vec<(char, Position)> = Vec::new();
+    while let Some(token) = lexer.next_token() {
+        let (token, position) = token?;
+        match token {
+            Token::Open(c) => groups.push((c, position)),
+            Token::Close(c) => match groups.pop() {
+                Some((open, _)) if closing(open) == c => (),
+                Some((open, _)) => {
+                    return lexer.error(
+                        position,
+                        &format!("expected {:?}, found {:?}", closing(open), c),
+                    )
+                }
+                None => return lexer.error(position, &format!("unexpected {:?}", c)),
+            },
+            // Only check tags outside of Rust code.
+            token if groups.is_empty() => tokens.push((token, position)),
+            _ => (),
+        }
+    }
+    if let Some((open, position)) = groups.pop() {
+        return lexer.error(position, &format!("unclosed {:?}", open));
+    }
+    check_tags(&tokens).map_err(|(position, message)| (position.0, position.1, message))
+}
+
+fn check_tags(tokens: &[(Token, Position)]) -> Result<(), (Position, String)> {
+    let mut open: Vec<(&str, Position)> = Vec::new();
+    let mut tokens = tokens.iter().peekable();
+    while let Some((token, position)) = tokens.next() {
+        // A `let` statement between children is Rust, which can have `<` and
+        // `>` in it, up to its `;`.
+        if *token == Token::Ident("let".to_string()) {
+            while tokens
+                .next()
+                .is_some_and(|(token, _)| *token != Token::Punct(';'))
+            {}
+            continue;
+        }
+        if *token != Token::Punct('<') {
+            continue;
+        }
+        let closing = tokens.peek().map(|(token, _)| token) == Some(&Token::Punct('/'));
+        if closing {
+            tokens.next();
+        }
+        let name = match tokens.next() {
+            Some((Token::Ident(name), _)) => name.as_str(),
+            _ => return Err((*position, "expected a tag name".to_string())),
+        };
+        if closing {
+            match open.pop() {
+                Some((expected, _)) if expected == name => (),
+                Some((expected, _)) => {
+                    return Err((
+                        *position,
+                        format!("expected </{}>, found </{}>", expected, name),
+                    ))
+                }
+                None => return Err((*position, format!("unexpected </{}>", name))),
+            }
+        }
+        let mut self_closing = false;
+        loop {
+            match tokens.next() {
+                Some((Token::Punct('>'), _)) => break,
+                Some((Token::Punct('/'), _)) if !closing => self_closing = true,
+                Some(_) if !closing => self_closing = false,
+                _ => return Err((*position, format!("unterminated tag <{}", name))),
+            }
+        }
+        if !closing && !self_closing {
+            open.push((name, *position));
+        }
+    }
+    match open.pop() {
+        Some((name, position)) => Err((position, format!("unclosed tag <{}>", name))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check() {
+        assert_eq!(
+            Ok(()),
+            check("<input r#type=\"text\"/><div class=r\"a>b\">{ if x < 1 { \"}\" } else { 'a' }}</div>")
+        );
+        assert_eq!(
+            Err((2, 5, "unclosed tag <p>".to_string())),
+            check("<div></div>\n    <p>\"Hi\"")
+        );
+        assert_eq!(
+            Err((1, 6, "unterminated string".to_string())),
+            check("<p>{ \"oops }</p>")
+        );
+        assert_eq!(
+            Err((1, 12, "expected ')', found '}'".to_string())),
+            check("<p>{ foo(1 }</p>")
+        );
+        assert_eq!(
+            Ok(()),
+            check(
+                "<div>let v = Vec::<u8>::new(); let few = n < 3;\
+                 <p>{ text!(\"{} {}\", v.len(), few) }</p></div>"
+            )
+        );
+    }
+}
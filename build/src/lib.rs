@@ -0,0 +1,360 @@
+//! Compile a directory of HTML templates into [typed-html] functions from
+//! `build.rs`.
+//!
+//! Each `.html` file in the directory is the body of an `html!` macro, and
+//! becomes a function returning a `DOMTree<String>`, named after the file:
+//! `templates/blog/post.html` becomes `blog_post()`. A template can take
+//! arguments, which you declare in a comment at the top of the file:
+//!
+//! ```html
+//! <!-- args: title: &str, items: &[String] -->
+//! <div>
+//!     <h1>{ text!(title) }</h1>
+//!     <ul>{ items.iter().map(|item| html!(<li>{ text!(item) }</li>)) }</ul>
+//! </div>
+//! ```
+//!
+//! In the `main()` of your `build.rs`:
+//!
+//! ```no_run
+//! typed_html_build::Templates::new("templates")
+//!     .compile()
+//!     .unwrap_or_else(|err| panic!("{}", err));
+//! ```
+//!
+//! And in your crate, with `typed-html` as a dependency:
+//!
+//! ```ignore
+//! use typed_html::{html, text};
+//!
+//! include!(concat!(env!("OUT_DIR"), "/templates.rs"));
+//! ```
+//!
+//! Templates are checked for unbalanced delimiters, unterminated strings and
+//! mismatched tags before anything is generated, and those errors are
+//! reported against the template file. Everything else, like unknown
+//! elements or invalid attribute values, is checked by the `html!` macro when
+//! your crate is compiled. Each template's function is written to a file of
+//! its own, named after the template, with the template on the same lines as
+//! in the `.html` file, so an error in
+//! `$OUT_DIR/templates/blog/post.html.rs:12:9` is on line 12, column 9 of
+//! `templates/blog/post.html`. Only columns on the first line are off.
+//!
+//! [typed-html]: https://docs.rs/typed-html/
+
+use std::env;
+use std::error;
+use std::fmt::{Display, Error as FmtError, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+mod check;
+
+/// An error from compiling templates.
+#[derive(Debug)]
+pub enum Error {
+    /// A template couldn't be read, or the output couldn't be written.
+    Io { path: PathBuf, error: io::Error },
+    /// A template has a syntax error.
+    Syntax {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// A template's file name can't be made into a function name, or two
+    /// templates have the same function name.
+    InvalidName { path: PathBuf, message: String },
+    /// No output path was given, and `OUT_DIR` isn't set because we're not
+    /// running in a build script.
+    NoOutDir,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        match self {
+            Error::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            Error::Syntax {
+                path,
+                line,
+                column,
+                message,
+            } => write!(f, "{}:{}:{}: {}", path.display(), line, column, message),
+            Error::InvalidName { path, message } => write!(f, "{}: {}", path.display(), message),
+            Error::NoOutDir => write!(f, "OUT_DIR is not set, so an output path must be given"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// A directory of templates to compile.
+pub struct Templates {
+    dir: PathBuf,
+    output: Option<PathBuf>,
+}
+
+impl Templates {
+    /// Compile the templates in the given directory and its subdirectories.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Templates {
+            dir: dir.as_ref().to_path_buf(),
+            output: None,
+        }
+    }
+
+    /// Write the generated code to this path, instead of `templates.rs` in
+    /// `OUT_DIR`.
+    ///
+    /// The functions themselves go in a directory next to it, named after
+    /// it without the extension, which this file includes.
+    pub fn output<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.output = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Check the templates and write the generated functions.
+    ///
+    /// This also tells Cargo to rerun the build script when a template
+    /// changes.
+    pub fn compile(&self) -> Result<(), Error> {
+        let output = match &self.output {
+            Some(output) => output.clone(),
+            None => match env::var_os("OUT_DIR") {
+                Some(dir) => Path::new(&dir).join("templates.rs"),
+                None => return Err(Error::NoOutDir),
+            },
+        };
+        println!("cargo:rerun-if-changed={}", self.dir.display());
+        for path in self.paths()? {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+        let functions_dir = output.with_extension("");
+        let dir_name = functions_dir
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy();
+        let mut code = String::new();
+        for (relative, function) in self.functions()? {
+            write_file(&functions_dir.join(format!("{}.rs", relative)), &function)?;
+            let include = format!("{}/{}.rs", dir_name, relative);
+            code.push_str(&format!("include!({:?});\n", include));
+        }
+        write_file(&output, &code)
+    }
+
+    /// Check the templates and return the generated functions.
+    ///
+    /// The functions are all in the one string, so unlike the files
+    /// [`compile()`][compile] writes, the lines in it don't match the
+    /// templates'.
+    ///
+    /// [compile]: #method.compile
+    pub fn generate(&self) -> Result<String, Error> {
+        Ok(self
+            .functions()?
+            .into_iter()
+            .map(|(_, function)| function)
+            .collect())
+    }
+
+    /// Check the templates and generate their functions, with the path of
+    /// each template relative to the template directory.
+    fn functions(&self) -> Result<Vec<(String, String)>, Error> {
+        let mut names = Vec::new();
+        let mut functions = Vec::new();
+        for path in self.paths()? {
+            let name = function_name(&self.dir, &path)?;
+            if names.contains(&name) {
+                return Err(Error::InvalidName {
+                    path,
+                    message: format!("more than one template is named {}", name),
+                });
+            }
+            let source = fs::read_to_string(&path).map_err(|error| Error::Io {
+                path: path.clone(),
+                error,
+            })?;
+            let relative = path.strip_prefix(&self.dir).unwrap_or(&path);
+            let relative = relative
+                .components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            functions.push((relative, compile_template(&path, &name, &source)?));
+            names.push(name);
+        }
+        Ok(functions)
+    }
+
+    fn paths(&self) -> Result<Vec<PathBuf>, Error> {
+        let mut paths = Vec::new();
+        find_templates(&self.dir, &mut paths)?;
+        paths.sort();
+        Ok(paths)
+    }
+}
+
+/// Check a single template and return the generated function.
+///
+/// The template starts on the function's first line and keeps its line
+/// numbers. The path is only used for error messages and the function's doc
+/// comment.
+pub fn compile_template(path: &Path, name: &str, source: &str) -> Result<String, Error> {
+    let (args, body) = split_args(source);
+    check::check(&body).map_err(|(line, column, message)| Error::Syntax {
+        path: path.to_path_buf(),
+        line,
+        column,
+        message,
+    })?;
+    Ok(format!(
+        "#[doc = {doc:?}] #[allow(dead_code)] \
+         pub fn {name}({args}) -> ::typed_html::dom::DOMTree<String> {{ \
+         ::typed_html::html!({body}\n) }}\n",
+        doc = format!("Compiled from `{}`.", path.display()),
+        name = name,
+        args = args,
+        body = body,
+    ))
+}
+
+fn write_file(path: &Path, contents: &str) -> Result<(), Error> {
+    let io_error = |error| Error::Io {
+        path: path.to_path_buf(),
+        error,
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(path, contents).map_err(io_error)
+}
+
+/// Split the arguments comment off a template.
+///
+/// The comment is replaced by whitespace in the body, so line and column
+/// numbers still match the template file.
+fn split_args(source: &str) -> (&str, String) {
+    let trimmed = source.trim_start();
+    if let Some(header) = trimmed.strip_prefix("<!--") {
+        if let Some(end) = header.find("-->") {
+            if let Some(args) = header[..end].trim().strip_prefix("args:") {
+                let header_len = source.len() - trimmed.len() + 4 + end + 3;
+                let blank: String = source[..header_len]
+                    .chars()
+                    .map(|c| if c == '\n' { '\n' } else { ' ' })
+                    .collect();
+                return (args.trim(), blank + &source[header_len..]);
+            }
+        }
+    }
+    ("", source.to_string())
+}
+
+fn find_templates(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), Error> {
+    let io_error = |error| Error::Io {
+        path: dir.to_path_buf(),
+        error,
+    };
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        let path = entry.map_err(io_error)?.path();
+        if path.is_dir() {
+            find_templates(&path, paths)?;
+        } else if path.extension().is_some_and(|ext| ext == "html") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Make a function name from a template's path relative to the template
+/// directory.
+fn function_name(dir: &Path, path: &Path) -> Result<String, Error> {
+    let relative = path.strip_prefix(dir).unwrap_or(path).with_extension("");
+    let name: String = relative
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(Error::InvalidName {
+            path: path.to_path_buf(),
+            message: format!("{:?} is not a valid function name", name),
+        });
+    }
+    Ok(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compile_template() {
+        let path = Path::new("templates/hello.html");
+        let code = compile_template(
+            path,
+            "hello",
+            "<!-- args: name: &str -->\n<p>\"Hello \"{ text!(name) }</p>\n",
+        )
+        .unwrap();
+        assert!(code.contains("pub fn hello(name: &str) -> ::typed_html::dom::DOMTree<String>"));
+        assert_eq!(
+            Some("<p>\"Hello \"{ text!(name) }</p>"),
+            code.lines().nth(1),
+            "the template should keep its line numbers"
+        );
+
+        let err = compile_template(path, "hello", "<!-- args: -->\n<div>\n  <p>\"Hi\"</div>")
+            .unwrap_err();
+        assert_eq!(
+            "templates/hello.html:3:10: expected </p>, found </div>",
+            err.to_string()
+        );
+    }
+
+    #[test]
+    fn test_compile() {
+        let dir = env::temp_dir().join(format!("typed-html-build-{}", std::process::id()));
+        let templates = dir.join("templates");
+        fs::create_dir_all(templates.join("blog")).unwrap();
+        fs::write(
+            templates.join("blog/post.html"),
+            "<!-- args: title: &str -->\n<article>\n    <h1>{ text!(title) }</h1>\n</article>\n",
+        )
+        .unwrap();
+        Templates::new(&templates)
+            .output(dir.join("templates.rs"))
+            .compile()
+            .unwrap();
+
+        assert_eq!(
+            "include!(\"templates/blog/post.html.rs\");\n",
+            fs::read_to_string(dir.join("templates.rs")).unwrap()
+        );
+        let function = fs::read_to_string(dir.join("templates/blog/post.html.rs")).unwrap();
+        assert!(function.contains("pub fn blog_post(title: &str)"));
+        assert_eq!(
+            Some("    <h1>{ text!(title) }</h1>"),
+            function.lines().nth(2)
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_function_name() {
+        let dir = Path::new("templates");
+        assert_eq!(
+            "blog_post_list",
+            function_name(dir, Path::new("templates/blog/post-list.html")).unwrap()
+        );
+        assert!(function_name(dir, Path::new("templates/404.html")).is_err());
+    }
+}
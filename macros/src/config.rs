@@ -1,4 +1,4 @@
-use proc_macro2::{Ident, Span, TokenStream};
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
 
 use crate::map::StringyMap;
 
@@ -10,6 +10,76 @@ pub fn required_children(element: &str) -> &[&str] {
     }
 }
 
+/// Find the replacement to suggest for an obsolete element, if it is one.
+pub fn obsolete_element(element: &str) -> Option<&'static str> {
+    match element {
+        "blink" | "marquee" => Some("use CSS animations instead"),
+        "center" => Some("use the CSS `text-align` property instead"),
+        "font" => Some("use CSS to style the text instead"),
+        "param" => Some("pass parameters in the URL of the `<object>`'s `data` attribute instead"),
+        _ => None,
+    }
+}
+
+/// Find the replacement to suggest for a deprecated attribute, if it is one.
+pub fn deprecated_attribute(element: &str, attr: &str) -> Option<&'static str> {
+    match (element, attr) {
+        (_, "align") => Some("use the CSS `text-align` or `float` property instead"),
+        (_, "bgcolor") => Some("use the CSS `background-color` property instead"),
+        ("iframe", "allowpaymentrequest") => Some("use `allow=\"payment\"` instead"),
+        ("marquee", "hspace") | ("marquee", "vspace") => {
+            Some("use the CSS `margin` property instead")
+        }
+        _ => None,
+    }
+}
+
+/// Find the `const fn` which checks string literal values for an attribute
+/// at compile time, if there is one.
+pub fn literal_validator(
+    element: &str,
+    attr: &str,
+    attrs: &StringyMap<Ident, TokenTree>,
+) -> Option<&'static str> {
+    let name = attrs.get("name").map(ToString::to_string);
+    match (element, attr) {
+        ("marquee", "bgcolor") => Some("typed_html::types::Color::validate"),
+        ("meta", "content") if name.as_deref() == Some("\"theme-color\"") => {
+            Some("typed_html::types::Color::validate")
+        }
+        ("canvas", "height")
+        | ("canvas", "width")
+        | ("embed", "height")
+        | ("embed", "width")
+        | ("iframe", "height")
+        | ("iframe", "width")
+        | ("img", "height")
+        | ("img", "width")
+        | ("input", "height")
+        | ("input", "width")
+        | ("object", "height")
+        | ("object", "width")
+        | ("video", "height")
+        | ("video", "width") => Some("typed_html::types::Dimension::validate_pixels"),
+        ("marquee", "height")
+        | ("marquee", "hspace")
+        | ("marquee", "vspace")
+        | ("marquee", "width") => Some("typed_html::types::Dimension::validate"),
+        (_, "lang") | (_, "hreflang") | (_, "srclang") => {
+            Some("typed_html::types::validate_language_tag")
+        }
+        (_, "is") => Some("typed_html::types::CustomElementName::validate"),
+        ("script", "type") => Some("typed_html::types::ScriptType::validate"),
+        ("a", "type")
+        | ("embed", "type")
+        | ("link", "type")
+        | ("object", "type")
+        | ("source", "type")
+        | ("style", "type") => Some("typed_html::types::validate_mime"),
+        _ => None,
+    }
+}
+
 pub fn global_attrs(span: Span) -> StringyMap<Ident, TokenStream> {
     let mut attrs = StringyMap::new();
     {
@@ -20,19 +90,34 @@ pub fn global_attrs(span: Span) -> StringyMap<Ident, TokenStream> {
         insert("class", "crate::types::ClassList");
 
         insert("accesskey", "String");
-        insert("autocapitalize", "String");
+        insert("autocapitalize", "crate::types::Autocapitalize");
+        insert("autofocus", "crate::types::Bool");
         insert("contenteditable", "crate::types::Bool");
         insert("contextmenu", "crate::types::Id");
         insert("dir", "crate::types::TextDirection");
         insert("draggable", "crate::types::Bool");
+        insert("enterkeyhint", "crate::types::EnterKeyHint");
         insert("hidden", "crate::types::Bool");
-        insert("is", "String");
+        insert("inert", "crate::types::Bool");
+        insert("inputmode", "crate::types::InputMode");
+        insert("is", "crate::types::CustomElementName");
+        insert("itemid", "crate::types::Uri");
+        insert("itemprop", "crate::types::SpacedList<String>");
+        insert("itemref", "crate::types::SpacedSet<crate::types::Id>");
+        insert("itemscope", "crate::types::Bool");
+        insert("itemtype", "crate::types::SpacedList<crate::types::Uri>");
         insert("lang", "crate::types::LanguageTag");
+        insert("nonce", "crate::types::Nonce");
+        insert("popover", "crate::types::Popover");
+        insert("role", "crate::types::Role");
+        insert("slot", "String");
+        insert("spellcheck", "crate::types::Bool");
         insert("style", "String");
         insert("tabindex", "isize");
         insert("title", "String");
+        insert("translate", "crate::types::Translate");
 
-        // FIXME ARIA and XML attrs missing
+        // FIXME XML attrs missing
     }
     attrs
 }
@@ -55,3 +140,152 @@ pub static SELF_CLOSING: &[&str] = &[
     "track",
     "wbr",
 ];
+
+/// Attributes which are rendered without a value when their value is empty,
+/// where other attributes are left out entirely.
+pub static BARE_WHEN_EMPTY: &[&str] = &["sandbox"];
+
+/// The roles which can be given in the `role` attribute.
+pub static ARIA_ROLES: &[&str] = &[
+    "alert", "alertdialog", "application", "article", "banner", "blockquote", "button", "caption",
+    "cell", "checkbox", "code", "columnheader", "combobox", "complementary", "contentinfo",
+    "definition", "deletion", "dialog", "document", "emphasis", "feed", "figure", "form",
+    "generic", "grid", "gridcell", "group", "heading", "img", "insertion", "link", "list",
+    "listbox", "listitem", "log", "main", "marquee", "math", "menu", "menubar", "menuitem",
+    "menuitemcheckbox", "menuitemradio", "meter", "navigation", "none", "note", "option",
+    "paragraph", "presentation", "progressbar", "radio", "radiogroup", "region", "row",
+    "rowgroup", "rowheader", "scrollbar", "search", "searchbox", "separator", "slider",
+    "spinbutton", "status", "strong", "subscript", "superscript", "switch", "tab", "table",
+    "tablist", "tabpanel", "term", "textbox", "time", "timer", "toolbar", "tooltip", "tree",
+    "treegrid", "treeitem",
+];
+
+/// Where an `aria-*` attribute can be used.
+pub enum AriaAttribute {
+    /// On any element, whatever its role.
+    Global,
+    /// Only on elements with one of these roles.
+    Roles(&'static [&'static str]),
+}
+
+/// Find out where an `aria-*` attribute can be used, if it exists.
+pub fn aria_attribute(attr: &str) -> Option<AriaAttribute> {
+    use self::AriaAttribute::*;
+    const RANGE: &[&str] = &["meter", "progressbar", "scrollbar", "separator", "slider", "spinbutton"];
+    const SET_ITEM: &[&str] = &[
+        "article", "listitem", "menuitem", "menuitemcheckbox", "menuitemradio", "option",
+        "radio", "row", "tab", "treeitem",
+    ];
+    const TABULAR: &[&str] = &["grid", "table", "treegrid"];
+    const CELL: &[&str] = &["cell", "columnheader", "gridcell", "rowheader"];
+    const TEXT: &[&str] = &["searchbox", "textbox"];
+    Some(match attr {
+        "aria-atomic" | "aria-braillelabel" | "aria-brailleroledescription" | "aria-busy"
+        | "aria-controls" | "aria-current" | "aria-describedby" | "aria-description"
+        | "aria-details" | "aria-disabled" | "aria-dropeffect" | "aria-errormessage"
+        | "aria-flowto" | "aria-grabbed" | "aria-haspopup" | "aria-hidden" | "aria-invalid"
+        | "aria-keyshortcuts" | "aria-label" | "aria-labelledby" | "aria-live" | "aria-owns"
+        | "aria-relevant" | "aria-roledescription" => Global,
+        "aria-activedescendant" => Roles(&[
+            "application", "combobox", "grid", "group", "listbox", "menu", "menubar",
+            "radiogroup", "row", "searchbox", "spinbutton", "tablist", "textbox", "toolbar",
+            "tree", "treegrid",
+        ]),
+        "aria-autocomplete" => Roles(&["combobox", "searchbox", "textbox"]),
+        "aria-checked" => Roles(&[
+            "checkbox", "menuitemcheckbox", "menuitemradio", "option", "radio", "switch",
+            "treeitem",
+        ]),
+        "aria-colcount" | "aria-rowcount" => Roles(TABULAR),
+        "aria-colindex" | "aria-rowindex" => Roles(&[
+            "cell", "columnheader", "gridcell", "row", "rowheader",
+        ]),
+        "aria-colspan" | "aria-rowspan" => Roles(CELL),
+        "aria-expanded" => Roles(&[
+            "application", "button", "checkbox", "columnheader", "combobox", "gridcell", "link",
+            "listbox", "menuitem", "menuitemcheckbox", "menuitemradio", "row", "rowheader",
+            "switch", "tab", "treeitem",
+        ]),
+        "aria-level" => Roles(&["heading", "listitem", "row", "treeitem"]),
+        "aria-modal" => Roles(&["alertdialog", "dialog"]),
+        "aria-multiline" | "aria-placeholder" => Roles(TEXT),
+        "aria-multiselectable" => Roles(&["grid", "listbox", "tablist", "tree", "treegrid"]),
+        "aria-orientation" => Roles(&[
+            "listbox", "menu", "menubar", "radiogroup", "scrollbar", "separator", "slider",
+            "tablist", "toolbar", "tree", "treegrid",
+        ]),
+        "aria-posinset" | "aria-setsize" => Roles(SET_ITEM),
+        "aria-pressed" => Roles(&["button"]),
+        "aria-readonly" => Roles(&[
+            "checkbox", "columnheader", "combobox", "grid", "gridcell", "listbox",
+            "menuitemcheckbox", "menuitemradio", "radiogroup", "rowheader", "searchbox",
+            "slider", "spinbutton", "switch", "textbox", "treegrid",
+        ]),
+        "aria-required" => Roles(&[
+            "checkbox", "columnheader", "combobox", "gridcell", "listbox", "radiogroup",
+            "rowheader", "searchbox", "spinbutton", "switch", "textbox", "tree", "treegrid",
+        ]),
+        "aria-selected" => Roles(&[
+            "columnheader", "gridcell", "option", "row", "rowheader", "tab", "treeitem",
+        ]),
+        "aria-sort" => Roles(&["columnheader", "rowheader"]),
+        "aria-valuemax" | "aria-valuemin" | "aria-valuenow" | "aria-valuetext" => Roles(RANGE),
+        _ => return None,
+    })
+}
+
+/// The `aria-*` attributes an element with the given role has to have.
+pub fn required_aria_attributes(role: &str) -> &'static [&'static str] {
+    match role {
+        "checkbox" | "menuitemcheckbox" | "menuitemradio" | "radio" | "switch" => {
+            &["aria-checked"]
+        }
+        "combobox" => &["aria-expanded"],
+        "heading" => &["aria-level"],
+        "meter" | "slider" => &["aria-valuenow"],
+        "scrollbar" => &["aria-controls", "aria-valuenow"],
+        _ => &[],
+    }
+}
+
+/// The role an element has without a `role` attribute, if we know it.
+pub fn implicit_role(element: &str, attrs: &StringyMap<Ident, TokenTree>) -> Option<&'static str> {
+    let input_type = attrs.get("type").map(ToString::to_string);
+    Some(match element {
+        "a" | "area" if attrs.get("href").is_some() => "link",
+        "article" => "article",
+        "aside" => "complementary",
+        "button" => "button",
+        "dialog" => "dialog",
+        "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => "heading",
+        "hr" => "separator",
+        "img" => "img",
+        "input" => match input_type.as_deref() {
+            None | Some("\"text\"") | Some("\"email\"") | Some("\"tel\"") | Some("\"url\"") => {
+                "textbox"
+            }
+            Some("\"checkbox\"") => "checkbox",
+            Some("\"radio\"") => "radio",
+            Some("\"range\"") => "slider",
+            Some("\"number\"") => "spinbutton",
+            Some("\"search\"") => "searchbox",
+            Some("\"button\"") | Some("\"image\"") | Some("\"reset\"") | Some("\"submit\"") => {
+                "button"
+            }
+            _ => return None,
+        },
+        "li" => "listitem",
+        "main" => "main",
+        "meter" => "meter",
+        "nav" => "navigation",
+        "ol" | "ul" | "menu" => "list",
+        "option" => "option",
+        "progress" => "progressbar",
+        "table" => "table",
+        // The roles of <td>, <th> and <tr> depend on the role of the table
+        // they're in, which we can't see from here: in a grid, a <td> is a
+        // gridcell rather than a cell.
+        "textarea" => "textbox",
+        _ => return None,
+    })
+}
@@ -1,7 +1,7 @@
 use proc_macro2::{Ident, Literal, TokenStream, TokenTree};
 use quote::quote;
 
-use crate::config::{global_attrs, SELF_CLOSING};
+use crate::config::{global_attrs, BARE_WHEN_EMPTY, SELF_CLOSING};
 use crate::error::ParseError;
 use crate::ident;
 use crate::lexer::{Lexer, Token};
@@ -16,6 +16,7 @@ pub struct Declare {
     pub req_children: Vec<Ident>,
     pub opt_children: Option<TokenStream>,
     pub traits: Vec<TokenStream>,
+    pub krate: TokenStream,
 }
 
 impl Declare {
@@ -25,12 +26,31 @@ impl Declare {
             req_children: Vec::new(),
             opt_children: None,
             traits: Vec::new(),
+            krate: quote!(crate),
             name,
         }
     }
 
+    /// Declare the element outside of `typed_html`, which is found at the
+    /// path `krate`.
+    pub fn set_crate(&mut self, krate: TokenStream) {
+        let defaults = global_attrs(self.name.span());
+        for (key, default) in defaults.iter() {
+            let key = key.to_string();
+            let value = match self.attrs.get(&key) {
+                Some(value) => value,
+                None => continue,
+            };
+            if value.to_string() == default.to_string() {
+                let value = replace_crate(value, &krate);
+                self.attrs.insert(Ident::new(&key, self.name.span()), value);
+            }
+        }
+        self.krate = krate;
+    }
+
     fn elem_name(&self) -> TokenTree {
-        Ident::new(&self.name.to_string(), self.name.span()).into()
+        ident::new_raw(&self.name.to_string(), self.name.span()).into()
     }
 
     fn attr_type_name(&self) -> TokenTree {
@@ -67,8 +87,10 @@ impl Declare {
         stream.extend(self.impl_());
         stream.extend(self.impl_node());
         stream.extend(self.impl_element());
+        stream.extend(self.impl_untyped_element());
         stream.extend(self.impl_marker_traits());
         stream.extend(self.impl_display());
+        stream.extend(self.impl_debug());
         stream.extend(self.impl_into_iter());
         stream
     }
@@ -79,15 +101,69 @@ impl Declare {
             body.extend(quote!( pub #attr_name: Option<#attr_type>, ));
         }
 
+        let krate = &self.krate;
+        let count = Literal::usize_unsuffixed(self.attrs.len());
+        let mut names = TokenStream::new();
+        let mut kinds = TokenStream::new();
+        let mut values = TokenStream::new();
+        let mut set = TokenStream::new();
+        let mut take = TokenStream::new();
+        for (index, ((attr_name, attr_type, attr_str), key)) in
+            self.attrs().zip(self.attrs.keys()).enumerate()
+        {
+            let kind = if BARE_WHEN_EMPTY.contains(&key.to_string().as_str()) {
+                quote!(BareWhenEmpty)
+            } else if is_uri_type(&attr_type) {
+                quote!(Url)
+            } else {
+                quote!(Text)
+            };
+            let index = Literal::usize_unsuffixed(index);
+            names.extend(quote!( #attr_str, ));
+            kinds.extend(quote!( #krate::dom::AttributeKind::#kind, ));
+            values.extend(quote!( #krate::dom::attribute_value(&self.#attr_name), ));
+            set.extend(quote!(
+                #index => #krate::dom::parse_attribute(&mut self.#attr_name, value),
+            ));
+            take.extend(quote!(
+                #index => #krate::dom::take_attribute(&mut self.#attr_name),
+            ));
+        }
+
         let attr_type_name = self.attr_type_name();
         quote!(
+            #[allow(non_camel_case_types)]
             pub struct #attr_type_name {
                 #body
             }
+
+            impl #attr_type_name {
+                const NAMES: &'static [&'static str] = &[ #names ];
+                const KINDS: &'static [#krate::dom::AttributeKind] = &[ #kinds ];
+
+                fn values(&self) -> [Option<&dyn std::fmt::Display>; #count] {
+                    [ #values ]
+                }
+
+                fn set(&mut self, index: usize, value: &str) -> Result<(), String> {
+                    match index {
+                        #set
+                        _ => unreachable!(),
+                    }
+                }
+
+                fn take(&mut self, index: usize) -> Option<String> {
+                    match index {
+                        #take
+                        _ => unreachable!(),
+                    }
+                }
+            }
         )
     }
 
     fn struct_(&self) -> TokenStream {
+        let krate = &self.krate;
         let elem_name = self.elem_name();
         let attr_type_name = self.attr_type_name();
 
@@ -103,16 +179,20 @@ impl Declare {
         }
 
         quote!(
-            pub struct #elem_name<T> where T: crate::OutputType {
+            #[allow(non_camel_case_types)]
+            pub struct #elem_name<T> where T: #krate::OutputType {
                 pub attrs: #attr_type_name,
                 pub data_attributes: Vec<(&'static str, String)>,
+                pub extra_attributes: Vec<(&'static str, String)>,
                 pub events: T::Events,
+                pub source: Option<&'static #krate::dom::Source>,
                 #body
             }
         )
     }
 
     fn impl_(&self) -> TokenStream {
+        let krate = &self.krate;
         let elem_name = self.elem_name();
         let attr_type_name = self.attr_type_name();
 
@@ -131,6 +211,7 @@ impl Declare {
             attrs: #attr_type_name { #attrs },
         ));
         body.extend(quote!(data_attributes: Vec::new(),));
+        body.extend(quote!(extra_attributes: Vec::new(),));
 
         for (child_name, _, _) in self.req_children() {
             body.extend(quote!( #child_name, ));
@@ -140,10 +221,11 @@ impl Declare {
         }
 
         quote!(
-            impl<T> #elem_name<T> where T: crate::OutputType {
+            impl<T> #elem_name<T> where T: #krate::OutputType {
                 pub fn new(#args) -> Self {
                     #elem_name {
                         events: T::Events::default(),
+                        source: None,
                         #body
                     }
                 }
@@ -152,6 +234,7 @@ impl Declare {
     }
 
     fn impl_vnode(&self) -> TokenStream {
+        let krate = &self.krate;
         let elem_name = TokenTree::Literal(Literal::string(self.name.to_string().as_str()));
         let mut req_children = TokenStream::new();
         for (child_name, _, _) in self.req_children() {
@@ -166,25 +249,18 @@ impl Declare {
             }));
         }
 
-        let mut push_attrs = TokenStream::new();
-        for (attr_name, _, attr_str) in self.attrs() {
-            push_attrs.extend(quote!(
-                if let Some(ref value) = self.attrs.#attr_name {
-                    attributes.push((#attr_str, value.to_string()));
-                }
-            ));
-        }
-
+        let attr_type_name = self.attr_type_name();
         quote!(
-            let mut attributes = Vec::new();
-            #push_attrs
+            let mut attributes =
+                #krate::dom::attribute_strings(#attr_type_name::NAMES, &self.attrs.values());
+            attributes.extend(self.extra_attributes.clone());
             attributes.extend(self.data_attributes.clone());
 
             let mut children = Vec::new();
             #req_children
             #opt_children
 
-            crate::dom::VNode::Element(crate::dom::VElement {
+            #krate::dom::VNode::Element(#krate::dom::VElement {
                 name: #elem_name,
                 attributes,
                 events: &mut self.events,
@@ -193,54 +269,202 @@ impl Declare {
         )
     }
 
+    fn impl_visit(&self) -> TokenStream {
+        let krate = &self.krate;
+        let elem_name = TokenTree::Literal(Literal::string(self.name.to_string().as_str()));
+        let mut req_children = TokenStream::new();
+        for (child_name, _, _) in self.req_children() {
+            req_children.extend(quote!(
+                self.#child_name.visit(visitor);
+            ));
+        }
+        let mut opt_children = TokenStream::new();
+        if self.opt_children.is_some() {
+            opt_children.extend(quote!(for child in &self.children {
+                child.visit(visitor);
+            }));
+        }
+
+        quote!(
+            visitor.enter_element(#elem_name, #krate::dom::Element::attributes(self));
+            #req_children
+            #opt_children
+            visitor.leave_element();
+        )
+    }
+
+    fn impl_apply(&self) -> TokenStream {
+        let mut req_children = TokenStream::new();
+        for (child_name, _, _) in self.req_children() {
+            req_children.extend(quote!(
+                self.#child_name.apply(transform);
+            ));
+        }
+        let mut opt_children = TokenStream::new();
+        if self.opt_children.is_some() {
+            opt_children.extend(quote!(
+                for mut child in std::mem::replace(&mut self.children, Vec::new()) {
+                    if child.apply(transform) {
+                        self.children.push(child);
+                    }
+                }
+            ));
+        }
+
+        quote!(
+            if !transform.element(self) {
+                return false;
+            }
+            #req_children
+            #opt_children
+            true
+        )
+    }
+
     fn impl_node(&self) -> TokenStream {
+        let krate = &self.krate;
         let elem_name = self.elem_name();
         let vnode = self.impl_vnode();
+        let apply = self.impl_apply();
+        let write_html = self.impl_write_html();
+        let visit = self.impl_visit();
         quote!(
-            impl<T> crate::dom::Node<T> for #elem_name<T> where T: crate::OutputType {
-                fn vnode(&'_ mut self) -> crate::dom::VNode<'_, T> {
+            impl<T> #krate::dom::Node<T> for #elem_name<T> where T: #krate::OutputType {
+                fn vnode(&'_ mut self) -> #krate::dom::VNode<'_, T> {
                     #vnode
                 }
+
+                fn apply(&mut self, transform: &mut dyn #krate::transform::Transform) -> bool {
+                    #apply
+                }
+
+                fn write_html(
+                    &self,
+                    f: &mut dyn std::fmt::Write,
+                    escape: &dyn #krate::escape::Escape,
+                ) -> std::fmt::Result {
+                    #write_html
+                }
+
+                fn visit(&self, visitor: &mut dyn #krate::dom::Visit) {
+                    #visit
+                }
             }
         )
     }
 
-    fn impl_element(&self) -> TokenStream {
+    fn impl_untyped_element(&self) -> TokenStream {
+        let krate = &self.krate;
         let name: TokenTree = Literal::string(&self.name.to_string()).into();
         let elem_name = self.elem_name();
+        let attr_type_name = self.attr_type_name();
+
+        quote!(
+            impl<T> #krate::dom::UntypedElement for #elem_name<T> where T: #krate::OutputType {
+                fn name(&self) -> &'static str {
+                    #name
+                }
+
+                fn attribute(&self, name: &str) -> Option<String> {
+                    let index = #attr_type_name::NAMES.iter().position(|attr| *attr == name)?;
+                    self.attrs.values()[index].map(|value| value.to_string())
+                }
+
+                fn set_attribute(&mut self, name: &str, value: &str) -> Result<(), String> {
+                    match #attr_type_name::NAMES.iter().position(|attr| *attr == name) {
+                        Some(index) => self.attrs.set(index, value).map_err(|err| {
+                            format!(
+                                "<{} {}={:?}> failed to parse attribute value: {}",
+                                #name, name, value, err
+                            )
+                        }),
+                        None => Err(format!("<{}> has no attribute {:?}", #name, name)),
+                    }
+                }
+
+                fn remove_attribute(&mut self, name: &str) -> Option<String> {
+                    let index = #attr_type_name::NAMES.iter().position(|attr| *attr == name)?;
+                    self.attrs.take(index)
+                }
+
+                fn data_attributes_mut(&mut self) -> &mut Vec<(&'static str, String)> {
+                    &mut self.data_attributes
+                }
+
+                fn extra_attributes_mut(&mut self) -> &mut Vec<(&'static str, String)> {
+                    &mut self.extra_attributes
+                }
+            }
+        )
+    }
 
-        let attrs: TokenStream = self.attrs().map(|(_, _, name)| quote!( #name, )).collect();
+    /// The element's content model, as a `ContentModel` for the runtime
+    /// validator.
+    pub fn content_model(&self) -> TokenStream {
+        let krate = &self.krate;
+        let name: TokenTree = Literal::string(&self.name.to_string()).into();
+        let groups: TokenStream = self
+            .traits
+            .iter()
+            .map(|group| {
+                quote!( &<dyn #group<std::string::String> as #krate::validate::Group>::GROUP, )
+            })
+            .collect();
         let reqs: TokenStream = self
             .req_children()
             .map(|(_, _, name)| quote!( #name, ))
             .collect();
+        let children = match &self.opt_children {
+            Some(children) => {
+                let children = Literal::string(&last_ident(children));
+                quote!(Some(#children))
+            }
+            None => quote!(None),
+        };
+        quote!(
+            #krate::validate::ContentModel {
+                name: #name,
+                groups: &[ #groups ],
+                required_children: &[ #reqs ],
+                children: #children,
+            }
+        )
+    }
 
-        let mut push_attrs = TokenStream::new();
-        for (attr_name, _, attr_str) in self.attrs() {
-            push_attrs.extend(quote!(
-                if let Some(ref value) = self.attrs.#attr_name {
-                    out.push((#attr_str, value.to_string()));
-                }
-            ));
-        }
+    fn impl_element(&self) -> TokenStream {
+        let krate = &self.krate;
+        let name: TokenTree = Literal::string(&self.name.to_string()).into();
+        let elem_name = self.elem_name();
+        let attr_type_name = self.attr_type_name();
+        let content_model = self.content_model();
+
+        let reqs: TokenStream = self
+            .req_children()
+            .map(|(_, _, name)| quote!( #name, ))
+            .collect();
 
         quote!(
-            impl<T> crate::dom::Element<T> for #elem_name<T> where T: crate::OutputType {
+            impl<T> #krate::dom::Element<T> for #elem_name<T> where T: #krate::OutputType {
                 fn name() -> &'static str {
                     #name
                 }
 
                 fn attribute_names() -> &'static [&'static str] {
-                    &[ #attrs ]
+                    #attr_type_name::NAMES
                 }
 
                 fn required_children() -> &'static [&'static str] {
                     &[ #reqs ]
                 }
 
+                fn content_model() -> &'static #krate::validate::ContentModel {
+                    &#content_model
+                }
+
                 fn attributes(&self) -> Vec<(&'static str, String)> {
-                    let mut out = Vec::new();
-                    #push_attrs
+                    let mut out =
+                        #krate::dom::attribute_strings(#attr_type_name::NAMES, &self.attrs.values());
+                    out.extend(self.extra_attributes.iter().cloned());
                     for (key, value) in &self.data_attributes {
                         out.push((key, value.to_string()));
                     }
@@ -251,21 +475,23 @@ impl Declare {
     }
 
     fn impl_marker_traits(&self) -> TokenStream {
+        let krate = &self.krate;
         let elem_name = self.elem_name();
         let mut body = TokenStream::new();
         for t in &self.traits {
             let name = t.clone();
             body.extend(quote!(
-                impl<T> #name<T> for #elem_name<T> where T: crate::OutputType {}
+                impl<T> #name<T> for #elem_name<T> where T: #krate::OutputType {}
             ));
         }
         body
     }
 
     fn impl_into_iter(&self) -> TokenStream {
+        let krate = &self.krate;
         let elem_name = self.elem_name();
         quote!(
-            impl<T> IntoIterator for #elem_name<T> where T: crate::OutputType {
+            impl<T> IntoIterator for #elem_name<T> where T: #krate::OutputType {
                 type Item = #elem_name<T>;
                 type IntoIter = std::vec::IntoIter<#elem_name<T>>;
                 fn into_iter(self) -> Self::IntoIter {
@@ -273,7 +499,7 @@ impl Declare {
                 }
             }
 
-            impl<T> IntoIterator for Box<#elem_name<T>> where T: crate::OutputType {
+            impl<T> IntoIterator for Box<#elem_name<T>> where T: #krate::OutputType {
                 type Item = Box<#elem_name<T>>;
                 type IntoIter = std::vec::IntoIter<Box<#elem_name<T>>>;
                 fn into_iter(self) -> Self::IntoIter {
@@ -283,88 +509,176 @@ impl Declare {
         )
     }
 
-    fn impl_display(&self) -> TokenStream {
-        let elem_name = self.elem_name();
+    fn impl_write_html(&self) -> TokenStream {
+        let krate = &self.krate;
         let name: TokenTree = Literal::string(&self.name.to_string()).into();
+        let attr_type_name = self.attr_type_name();
 
-        let print_opt_children = if self.opt_children.is_some() {
-            quote!(for child in &self.children {
-                child.fmt(f)?;
-            })
-        } else {
-            TokenStream::new()
-        };
-
-        let mut print_req_children = TokenStream::new();
+        let mut print_children = TokenStream::new();
         for (child_name, _, _) in self.req_children() {
-            print_req_children.extend(quote!(
-                self.#child_name.fmt(f)?;
+            print_children.extend(quote!(
+                self.#child_name.write_html(f, escape)?;
             ));
         }
+        if self.opt_children.is_some() {
+            print_children.extend(quote!(for child in &self.children {
+                child.write_html(f, escape)?;
+            }));
+        }
 
-        let print_children = if self.req_children.is_empty() {
-            if self.opt_children.is_some() {
-                if !SELF_CLOSING.contains(&elem_name.to_string().as_str()) {
-                    quote!(
-                        write!(f, ">")?;
-                        #print_opt_children
-                        write!(f, "</{}>", #name)
-                    )
-                } else {
-                    quote!(if self.children.is_empty() {
-                        write!(f, " />")
-                    } else {
-                        write!(f, ">")?;
-                        #print_opt_children
-                        write!(f, "</{}>", #name)
-                    })
-                }
-            } else if !SELF_CLOSING.contains(&elem_name.to_string().as_str()) {
-                quote!(write!(f, "></{}>", #name))
+        let self_closing = SELF_CLOSING.contains(&self.name.to_string().as_str());
+        let closing = if !self.req_children.is_empty() || !self_closing {
+            quote!(#krate::dom::Closing::Tag)
+        } else if self.opt_children.is_some() {
+            quote!(if self.children.is_empty() {
+                #krate::dom::Closing::SpacedVoid
             } else {
-                quote!(write!(f, "/>"))
-            }
+                #krate::dom::Closing::Tag
+            })
         } else {
-            quote!(
-                write!(f, ">")?;
-                #print_req_children
-                #print_opt_children
-                write!(f, "</{}>", #name)
-            )
+            quote!(#krate::dom::Closing::Void)
         };
 
-        let mut print_attrs = TokenStream::new();
-        for (attr_name, _, attr_str) in self.attrs() {
-            print_attrs.extend(quote!(
-                if let Some(ref value) = self.attrs.#attr_name {
-                    let value = ::htmlescape::encode_attribute(&value.to_string());
-                    if !value.is_empty() {
-                        write!(f, " {}=\"{}\"", #attr_str, value)?;
-                    }
+        quote!(
+            #krate::dom::ElementHtml {
+                name: #name,
+                attribute_names: #attr_type_name::NAMES,
+                attribute_kinds: #attr_type_name::KINDS,
+                attribute_values: &self.attrs.values(),
+                extra_attributes: &self.extra_attributes,
+                data_attributes: &self.data_attributes,
+                events: &self.events,
+                source: self.source,
+                closing: #closing,
+                children: &|f: &mut dyn std::fmt::Write,
+                            escape: &dyn #krate::escape::Escape|
+                 -> std::fmt::Result {
+                    #print_children
+                    Ok(())
+                },
+            }
+            .write(f, escape)
+        )
+    }
+
+    fn impl_display(&self) -> TokenStream {
+        let krate = &self.krate;
+        let elem_name = self.elem_name();
+        quote!(
+            impl<T> std::fmt::Display for #elem_name<T>
+            where
+                T: #krate::OutputType,
+            {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+                    #krate::dom::Node::<T>::write_html(self, f, &#krate::escape::Html)
+                }
+            }
+        )
+    }
+
+    fn impl_debug(&self) -> TokenStream {
+        let krate = &self.krate;
+        let elem_name = self.elem_name();
+        let name: TokenTree = Literal::string(&self.name.to_string()).into();
+
+        let attr_type_name = self.attr_type_name();
+        let debug_attrs = quote!(
+            let values = self.attrs.values();
+            for (key, value) in #attr_type_name::NAMES.iter().zip(&values) {
+                if let Some(value) = value {
+                    out.field(key, &value.to_string());
+                }
+            }
+        );
+
+        let mut push_children = TokenStream::new();
+        for (child_name, _, _) in self.req_children() {
+            push_children.extend(quote!(
+                children.push(&self.#child_name);
+            ));
+        }
+        if self.opt_children.is_some() {
+            push_children.extend(quote!(
+                for child in &self.children {
+                    children.push(child);
                 }
             ));
         }
 
         quote!(
-            impl<T> std::fmt::Display for #elem_name<T>
+            impl<T> std::fmt::Debug for #elem_name<T>
             where
-                T: crate::OutputType,
+                T: #krate::OutputType,
             {
                 fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-                    write!(f, "<{}", #name)?;
-                    #print_attrs
+                    let mut out = f.debug_struct(#name);
+                    #debug_attrs
+                    for (key, value) in &self.extra_attributes {
+                        out.field(key, value);
+                    }
                     for (key, value) in &self.data_attributes {
-                        write!(f, " data-{}=\"{}\"", key,
-                               ::htmlescape::encode_attribute(&value))?;
+                        out.field(&format!("data-{}", key), value);
                     }
-                    write!(f, "{}", self.events)?;
-                    #print_children
+                    let mut children: Vec<&dyn std::fmt::Debug> = Vec::new();
+                    #push_children
+                    if !children.is_empty() {
+                        out.field("children", &children);
+                    }
+                    out.finish()
                 }
             }
         )
     }
 }
 
+/// Split a leading `$crate;` off the input to `declare_elements!`.
+pub fn split_crate(input: TokenStream) -> (Option<TokenStream>, TokenStream) {
+    let tokens: Vec<TokenTree> = input.clone().into_iter().collect();
+    match (tokens.first(), tokens.get(1)) {
+        (Some(TokenTree::Ident(krate)), Some(TokenTree::Punct(punct)))
+            if krate == "$crate" && punct.as_char() == ';' =>
+        {
+            let krate = quote!(#krate);
+            (Some(krate), tokens.into_iter().skip(2).collect())
+        }
+        _ => (None, input),
+    }
+}
+
 pub fn expand_declare(input: &[Token]) -> Result<Vec<Declare>, ParseError> {
     parser::grammar::DeclarationsParser::new().parse(Lexer::new(input))
 }
+
+/// Replace `crate` in a type's paths with `krate`.
+fn replace_crate(path: &TokenStream, krate: &TokenStream) -> TokenStream {
+    let mut out = TokenStream::new();
+    for token in path.clone() {
+        match token {
+            TokenTree::Ident(ref ident) if ident == "crate" => out.extend(krate.clone()),
+            token => out.extend(Some(token)),
+        }
+    }
+    out
+}
+
+/// The last identifier in a path, like `FlowContent` in
+/// `typed_html::elements::FlowContent`.
+fn last_ident(path: &TokenStream) -> String {
+    path.clone()
+        .into_iter()
+        .filter_map(|token| match token {
+            TokenTree::Ident(ident) => Some(ident.to_string()),
+            _ => None,
+        })
+        .last()
+        .unwrap_or_default()
+}
+
+/// Test whether an attribute type is `Uri`, so its value should be escaped as
+/// a URL.
+fn is_uri_type(attr_type: &TokenStream) -> bool {
+    match attr_type.clone().into_iter().last() {
+        Some(TokenTree::Ident(ident)) => ident == "Uri",
+        _ => false,
+    }
+}
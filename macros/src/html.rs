@@ -1,12 +1,18 @@
 use proc_macro2::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
 use quote::{quote, quote_spanned};
 
-use crate::config::required_children;
+#[cfg(feature = "dodrio")]
+use crate::config::BARE_WHEN_EMPTY;
+use crate::config::{
+    aria_attribute, deprecated_attribute, implicit_role, literal_validator, obsolete_element,
+    required_aria_attributes, required_children, AriaAttribute, ARIA_ROLES,
+};
 use crate::error::ParseError;
 use crate::ident;
 use crate::lexer::{to_stream, Lexer, Token};
 use crate::map::StringyMap;
 use crate::parser::grammar;
+use crate::text::decode_text;
 
 use std::iter::FromIterator;
 
@@ -15,6 +21,24 @@ pub enum Node {
     Element(Element),
     Text(Literal),
     Block(Group),
+    Let(TokenStream),
+    Wrapped(Vec<(TokenStream, Vec<Node>)>),
+}
+
+fn let_error(stmt: TokenStream) -> TokenStream {
+    let span = stmt.into_iter().next().unwrap().span();
+    let error = "you cannot use a let binding as a top level element or a required child element";
+    quote_spanned! { span=>
+        compile_error! { #error }
+    }
+}
+
+fn wrapped_error(parts: &[(TokenStream, Vec<Node>)]) -> TokenStream {
+    let span = parts[0].0.clone().into_iter().next().unwrap().span();
+    let error = "you cannot use a control flow block as a top level element or a required child element";
+    quote_spanned! { span=>
+        compile_error! { #error }
+    }
 }
 
 impl Node {
@@ -22,7 +46,7 @@ impl Node {
         match self {
             Node::Element(el) => el.into_token_stream(ty),
             Node::Text(text) => {
-                let text = TokenTree::Literal(text);
+                let text = decode_text(text);
                 Ok(quote!(Box::new(typed_html::dom::TextNode::new(#text.to_string()))))
             }
             Node::Block(group) => {
@@ -33,9 +57,37 @@ impl Node {
                     compile_error! { #error }
                 })
             }
+            Node::Let(stmt) => Err(let_error(stmt)),
+            Node::Wrapped(parts) => Err(wrapped_error(&parts)),
         }
     }
 
+    /// Generate the root node of a macro invocation, recording where the
+    /// invocation is if it's an element.
+    pub fn into_root_token_stream(
+        self,
+        ty: &Option<Vec<Token>>,
+    ) -> Result<TokenStream, TokenStream> {
+        check_duplicate_ids(&self, &mut Vec::new())?;
+        let is_element = matches!(self, Node::Element(_));
+        let node = self.into_token_stream(ty)?;
+        if !is_element {
+            return Ok(node);
+        }
+        Ok(quote!(
+            {
+                let mut root = #node;
+                root.source = Some(&typed_html::dom::Source {
+                    file: file!(),
+                    line: line!(),
+                    column: column!(),
+                    module: module_path!(),
+                });
+                root
+            }
+        ))
+    }
+
     fn into_child_stream(self, ty: &Option<Vec<Token>>) -> Result<TokenStream, TokenStream> {
         match self {
             Node::Element(el) => {
@@ -58,6 +110,18 @@ impl Node {
                     }
                 ))
             }
+            Node::Let(stmt) => Ok(stmt),
+            Node::Wrapped(parts) => {
+                let mut stream = TokenStream::new();
+                for (head, children) in parts {
+                    let mut body = TokenStream::new();
+                    for child in children {
+                        body.extend(child.into_child_stream(ty)?);
+                    }
+                    stream.extend(quote!(#head { #body }));
+                }
+                Ok(stream)
+            }
         }
     }
 
@@ -78,20 +142,60 @@ impl Node {
                     compile_error! { #error }
                 })
             }
+            Node::Let(stmt) => Err(let_error(stmt)),
+            Node::Wrapped(parts) => Err(wrapped_error(&parts)),
         }
     }
 }
 
+/// Fail if two elements in the tree are given the same literal `id`, with
+/// an error at each of them.
+///
+/// Elements inside control flow blocks are left out, since they might not
+/// both be built.
+fn check_duplicate_ids(node: &Node, seen: &mut Vec<Literal>) -> Result<(), TokenStream> {
+    let el = match node {
+        Node::Element(el) => el,
+        _ => return Ok(()),
+    };
+    let id = el.attributes.iter().find_map(|(key, value)| match value {
+        TokenTree::Literal(lit) if key == "id" && is_string_literal(lit) => Some(lit),
+        _ => None,
+    });
+    if let Some(id) = id {
+        let value = id.to_string();
+        if let Some(first) = seen.iter().find(|seen| seen.to_string() == value) {
+            let (first_span, span) = (first.span(), id.span());
+            let error = format!("duplicate id {}", value);
+            let note = format!("id {} first used here", value);
+            let mut errors = quote_spanned! { span=>
+                compile_error! { #error }
+            };
+            errors.extend(quote_spanned! { first_span=>
+                compile_error! { #note }
+            });
+            return Err(errors);
+        }
+        seen.push(id.clone());
+    }
+    for child in &el.children {
+        check_duplicate_ids(child, seen)?;
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct Element {
     pub name: Ident,
     pub attributes: StringyMap<Ident, TokenTree>,
+    pub mixins: Vec<Group>,
+    pub vendor_attributes: Vec<(Ident, Ident, TokenTree)>,
     pub children: Vec<Node>,
 }
 
 #[cfg(feature = "dodrio")]
 fn dodrio_text_node(text: Literal) -> TokenStream {
-    let text = TokenTree::Literal(text);
+    let text = decode_text(text);
     quote!(dodrio::builder::text(#text))
 }
 
@@ -109,6 +213,20 @@ fn extract_data_attrs(attrs: &mut StringyMap<Ident, TokenTree>) -> StringyMap<St
     data
 }
 
+/// Take out the `aria-*` attributes, keyed by their full names.
+fn extract_aria_attrs(attrs: &mut StringyMap<Ident, TokenTree>) -> StringyMap<String, TokenTree> {
+    let mut aria = StringyMap::new();
+    let keys: Vec<Ident> = attrs.keys().cloned().collect();
+    for key in keys {
+        let key_name = key.to_string();
+        if key_name.starts_with("aria_") {
+            let value = attrs.remove(&key).unwrap();
+            aria.insert(key_name.replace('_', "-"), value);
+        }
+    }
+    aria
+}
+
 fn extract_event_handlers(
     attrs: &mut StringyMap<Ident, TokenTree>,
 ) -> StringyMap<Ident, TokenTree> {
@@ -117,7 +235,7 @@ fn extract_event_handlers(
     for key in keys {
         let key_name = key.to_string();
         let prefix = "on";
-        if key_name.starts_with(prefix) {
+        if key_name.starts_with(prefix) && key_name != prefix {
             let event_name = &key_name[prefix.len()..];
             let value = attrs.remove(&key).unwrap();
             events.insert(ident::new_raw(event_name, key.span()), value);
@@ -126,6 +244,60 @@ fn extract_event_handlers(
     events
 }
 
+/// Take out the `on={events}` attribute, which sets all of an element's event
+/// handlers at once.
+fn extract_events_value(attrs: &mut StringyMap<Ident, TokenTree>) -> Option<(Ident, TokenTree)> {
+    let key = attrs.keys().find(|key| *key == "on").cloned()?;
+    let value = attrs.remove(&key).unwrap();
+    Some((key, value))
+}
+
+/// Check the names in a `raw_attrs` value which are written as string
+/// literals, the first items of tuples like `("@click", handler)`, at
+/// compile time. Other names are checked when the element is built.
+fn check_raw_attribute_names(value: &TokenTree) -> TokenStream {
+    let mut checks = TokenStream::new();
+    let group = match value {
+        TokenTree::Group(group) => group,
+        _ => return checks,
+    };
+    let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+    if group.delimiter() == Delimiter::Parenthesis {
+        if let [TokenTree::Literal(name), TokenTree::Punct(comma), ..] = tokens.as_slice() {
+            if is_string_literal(name) && comma.as_char() == ',' {
+                let span = name.span();
+                checks.extend(quote_spanned!(span=>
+                    const _: () = typed_html::types::check_literal(
+                        typed_html::dom::validate_raw_attribute_name(#name)
+                    );
+                ));
+            }
+        }
+    }
+    for token in &tokens {
+        checks.extend(check_raw_attribute_names(token));
+    }
+    checks
+}
+
+/// Take out the `raw_attrs={...}` attribute, which adds attributes that
+/// aren't checked at all.
+fn extract_raw_attrs(attrs: &mut StringyMap<Ident, TokenTree>) -> Option<TokenTree> {
+    let key = attrs.keys().find(|key| *key == "raw_attrs").cloned()?;
+    attrs.remove(&key)
+}
+
+fn missing_output_type_error(span: Span) -> TokenStream {
+    let mut err = quote_spanned! { span =>
+        compile_error! { "when using event handlers, you must declare the output type inside the html! macro" }
+    };
+    let hint = quote_spanned! { Span::call_site() =>
+        compile_error! { "for example: change html!(<div>...</div>) to html!(<div>...</div> : String)" }
+    };
+    err.extend(hint);
+    err
+}
+
 fn process_value(value: &TokenTree) -> TokenStream {
     match value {
         TokenTree::Group(g) if g.delimiter() == Delimiter::Bracket => {
@@ -140,12 +312,173 @@ fn process_value(value: &TokenTree) -> TokenStream {
     }
 }
 
+/// The path to an element's constructor.
+///
+/// Built in elements are found in `typed_html::elements`, and anything else,
+/// like an element declared with `typed_html::declare_elements!`, is looked
+/// up where the macro is used. The glob import is scoped to the path, so it
+/// can't shadow anything in the element's attributes or children.
+fn element_path(typename: &TokenTree, type_args: TokenStream) -> TokenStream {
+    quote!({
+        #[allow(unused_imports)]
+        use typed_html::elements::*;
+        #typename #type_args::new
+    })
+}
+
 fn is_string_literal(literal: &Literal) -> bool {
     // This is the worst API
     literal.to_string().starts_with('"')
 }
 
-#[allow(dead_code)]
+/// Check a string literal attribute value at compile time, if we know how.
+///
+/// Attributes with a type declared by `typed_html::attribute!` are checked by
+/// the type, through `typed_html::attribute::probe()`.
+fn check_literal(
+    element: &str,
+    attr: &str,
+    key: &TokenTree,
+    lit: &Literal,
+    attrs: &StringyMap<Ident, TokenTree>,
+) -> TokenStream {
+    match literal_validator(element, attr, attrs) {
+        Some(validator) => {
+            let span = lit.span();
+            let validator: TokenStream = validator
+                .parse::<TokenStream>()
+                .unwrap()
+                .into_iter()
+                .map(|mut token| {
+                    token.set_span(span);
+                    token
+                })
+                .collect();
+            quote_spanned!(span=>
+                const _: () = typed_html::types::check_literal(#validator(#lit));
+            )
+        }
+        None => check_literal_type(lit, quote!(&element.attrs.#key)),
+    }
+}
+
+/// Whether an attribute is a `Dimension`.
+fn is_dimension(element: &str, attr: &str, attrs: &StringyMap<Ident, TokenTree>) -> bool {
+    literal_validator(element, attr, attrs)
+        .is_some_and(|validator| validator.starts_with("typed_html::types::Dimension::"))
+}
+
+/// Give an unsuffixed integer literal a type suffix, so it has a type of its
+/// own when it's converted into an attribute type with more than one `From`
+/// implementation for integers.
+fn suffix_integer(lit: &Literal, suffix: &str) -> TokenStream {
+    let source = lit.to_string();
+    if source.is_empty() || !source.chars().all(|c| c.is_ascii_digit() || c == '_') {
+        return quote!(#lit);
+    }
+    let mut suffixed: Literal = match format!("{}{}", source, suffix).parse::<TokenStream>() {
+        Ok(stream) => match stream.into_iter().next() {
+            Some(TokenTree::Literal(suffixed)) => suffixed,
+            _ => return quote!(#lit),
+        },
+        Err(_) => return quote!(#lit),
+    };
+    suffixed.set_span(lit.span());
+    quote!(#suffixed)
+}
+
+/// Check a string literal against the type of `target`, an `&Option<T>`, if
+/// `T` was declared by `typed_html::attribute!`.
+fn check_literal_type(lit: &Literal, target: TokenStream) -> TokenStream {
+    quote!({
+        struct Literal;
+        impl typed_html::attribute::Literal for Literal {
+            const VALUE: &'static str = #lit;
+        }
+        #[allow(unused_imports)]
+        use typed_html::attribute::{Checked as _, Unchecked as _};
+        (&typed_html::attribute::probe::<_, Literal>(#target)).check();
+    })
+}
+
+/// Expand a vendor attribute like `x:data="..."` into its full name and an
+/// expression for its value as a string.
+///
+/// The value's type is `x::data`, which `typed_html::vendor!` declares, so an
+/// attribute that wasn't declared won't compile.
+fn vendor_attribute(namespace: &Ident, name: &Ident, value: &TokenTree) -> (Literal, TokenStream) {
+    let name_str = name.to_string();
+    let attr_str = Literal::string(&format!("{}-{}", namespace, name_str.replace('_', "-")));
+    let ty_name = ident::new_raw(&name_str, name.span());
+    let ty = quote!(#namespace::#ty_name);
+    let value = match value {
+        TokenTree::Literal(lit) if is_string_literal(lit) => {
+            let check = check_literal_type(lit, quote!(&None::<#ty>));
+            let error = format!(
+                "<{}:{}={}> failed to parse attribute value",
+                namespace, name_str, lit
+            );
+            quote!({
+                #check
+                let value: #ty = #lit
+                    .parse()
+                    .unwrap_or_else(|err| panic!("{}: {}", #error, err));
+                value.to_string()
+            })
+        }
+        value => {
+            let value = process_value(value);
+            quote!({
+                let value: #ty = std::convert::Into::into(#value);
+                value.to_string()
+            })
+        }
+    };
+    (attr_str, value)
+}
+
+/// Use a local constant marked `#[deprecated]` with the given note, which
+/// makes the compiler warn at the span of `ident`.
+fn deprecation_warning(ident: &Ident, note: &str) -> TokenStream {
+    let span = ident.span();
+    let name = ident::new_raw(&ident.to_string(), span);
+    quote_spanned!(span=> {
+        #[deprecated(note = #note)]
+        #[allow(non_upper_case_globals)]
+        const #name: () = ();
+        let _ = #name;
+    })
+}
+
+/// Expand a mixin like `{..ExternalLink}` into a call to the macro that
+/// `mixin!` declared for it, passing the attributes written on the element so
+/// it can check them for conflicts.
+fn expand_mixin(
+    mixin: &Group,
+    attrs: &StringyMap<Ident, TokenTree>,
+) -> Result<TokenStream, TokenStream> {
+    let tokens: Vec<TokenTree> = mixin.stream().into_iter().collect();
+    match tokens.as_slice() {
+        [TokenTree::Punct(first), TokenTree::Punct(second), path @ ..]
+            if first.as_char() == '.' && second.as_char() == '.' && !path.is_empty() =>
+        {
+            let path = TokenStream::from_iter(path.iter().cloned());
+            let names = attrs
+                .keys()
+                .map(|key| TokenTree::from(Literal::string(&stringify_ident(key))));
+            Ok(quote!(
+                #path!(element, [#(#names),*]);
+            ))
+        }
+        _ => {
+            let span = mixin.span();
+            Err(quote_spanned! { span=>
+                compile_error! { "expected a mixin, like {..ExternalLink}" }
+            })
+        }
+    }
+}
+
 fn stringify_ident(ident: &Ident) -> String {
     let s = ident.to_string();
     if s.starts_with("r#") {
@@ -156,10 +489,109 @@ fn stringify_ident(ident: &Ident) -> String {
 }
 
 impl Element {
+    /// Warn about an obsolete element or deprecated attributes through the
+    /// `deprecated` lint, so they can be allowed or denied like any other
+    /// deprecation.
+    fn deprecation_warnings(&self) -> TokenStream {
+        let name_str = self.name.to_string();
+        let mut warnings = TokenStream::new();
+        if let Some(suggestion) = obsolete_element(&name_str) {
+            let note = format!("<{}> is obsolete, {}", name_str, suggestion);
+            warnings.extend(deprecation_warning(&self.name, &note));
+        }
+        for key in self.attributes.keys() {
+            if let Some(suggestion) = deprecated_attribute(&name_str, &key.to_string()) {
+                let note = format!(
+                    "the `{}` attribute on <{}> is deprecated, {}",
+                    key, name_str, suggestion
+                );
+                warnings.extend(deprecation_warning(key, &note));
+            }
+        }
+        warnings
+    }
+
+    /// Check the `role` and `aria-*` attributes against each other.
+    ///
+    /// Unknown `aria-*` attributes are always an error. When the role is a
+    /// string literal, or there's no `role` but the element has an implicit
+    /// role we know of, attributes which aren't allowed on that role are an
+    /// error too, and so is an explicit role missing the attributes it
+    /// requires.
+    fn check_aria(&self) -> Result<(), TokenStream> {
+        let name_str = self.name.to_string();
+        let mut errors = TokenStream::new();
+        let mut error = |span: Span, message: String| {
+            errors.extend(quote_spanned! {span=>
+                compile_error! { #message }
+            });
+        };
+        let implicit = implicit_role(&name_str, &self.attributes);
+        let role = match self.attributes.iter().find(|(key, _)| key == "role") {
+            Some((_, TokenTree::Literal(lit))) if is_string_literal(lit) => {
+                let role = lit.to_string();
+                let role = role.trim_matches('"');
+                match ARIA_ROLES.iter().find(|known| **known == role) {
+                    Some(role) => Some((*role, Some(lit.span()))),
+                    None => {
+                        error(lit.span(), format!("unknown ARIA role \"{}\"", role));
+                        None
+                    }
+                }
+            }
+            Some(_) => None,
+            None => implicit.map(|role| (role, None)),
+        };
+        let aria: Vec<(&Ident, String)> = self
+            .attributes
+            .keys()
+            .filter(|key| key.to_string().starts_with("aria_"))
+            .map(|key| (key, key.to_string().replace('_', "-")))
+            .collect();
+        for (key, attr) in &aria {
+            match (aria_attribute(attr), role) {
+                (None, _) => error(key.span(), format!("unknown ARIA attribute `{}`", attr)),
+                (Some(AriaAttribute::Roles(roles)), Some((role, explicit)))
+                    if !roles.contains(&role) =>
+                {
+                    let message = match explicit {
+                        Some(_) => format!("`{}` is not allowed with role \"{}\"", attr, role),
+                        None => format!(
+                            "`{}` is not allowed on <{}>, which has the role \"{}\"",
+                            attr, name_str, role
+                        ),
+                    };
+                    error(key.span(), message);
+                }
+                _ => {}
+            }
+        }
+        // Form controls provide the states their roles require themselves.
+        if let Some((role, Some(span))) = role {
+            if name_str != "input" && implicit != Some(role) {
+                for required in required_aria_attributes(role) {
+                    if !aria.iter().any(|(_, attr)| attr == required) {
+                        error(
+                            span,
+                            format!("role \"{}\" requires the `{}` attribute", role, required),
+                        );
+                    }
+                }
+            }
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     fn into_token_stream(mut self, ty: &Option<Vec<Token>>) -> Result<TokenStream, TokenStream> {
+        self.check_aria()?;
+        let warnings = self.deprecation_warnings();
         let name = self.name;
         let name_str = name.to_string();
-        let typename: TokenTree = Ident::new(&name_str, name.span()).into();
+        let typename: TokenTree = ident::new_raw(&name_str, name.span()).into();
         let req_names = required_children(&name_str);
         if req_names.len() > self.children.len() {
             let span = name.span();
@@ -173,8 +605,11 @@ impl Element {
                 compile_error! { #error }
             });
         }
+        let events_value = extract_events_value(&mut self.attributes);
+        let raw_attrs = extract_raw_attrs(&mut self.attributes);
         let events = extract_event_handlers(&mut self.attributes);
         let data_attrs = extract_data_attrs(&mut self.attributes);
+        let aria_attrs = extract_aria_attrs(&mut self.attributes);
         let attrs = self.attributes.iter().map(|(key, value)| {
             (
                 key.to_string(),
@@ -194,7 +629,7 @@ impl Element {
             .map(|node| node.into_token_stream(ty))
             .collect::<Result<Vec<TokenStream>, TokenStream>>()?;
 
-        let mut body = TokenStream::new();
+        let mut body = warnings;
 
         for (attr_str, key, value) in attrs {
             match value {
@@ -223,6 +658,7 @@ impl Element {
                         eprintln_msg += "\nERROR: rebuild with nightly to print source location";
                     }
 
+                    body.extend(check_literal(&name_str, &attr_str, &key, lit, &self.attributes));
                     body.extend(quote!(
                         element.attrs.#key = Some(#lit.parse().unwrap_or_else(|err| {
                             eprintln!(#eprintln_msg, err);
@@ -231,7 +667,14 @@ impl Element {
                     ));
                 }
                 value => {
-                    let value = process_value(value);
+                    let value = match value {
+                        TokenTree::Literal(lit)
+                            if is_dimension(&name_str, &attr_str, &self.attributes) =>
+                        {
+                            suffix_integer(lit, "u32")
+                        }
+                        value => process_value(value),
+                    };
                     body.extend(quote!(
                         element.attrs.#key = Some(std::convert::Into::into(#value));
                     ));
@@ -246,18 +689,47 @@ impl Element {
                 element.data_attributes.push((#key, #value.into()));
             ));
         }
+        for (key, value) in aria_attrs.iter() {
+            let key = Literal::string(key);
+            let value = process_value(value);
+            body.extend(quote!(
+                element.extra_attributes.push((#key, std::string::ToString::to_string(&#value)));
+            ));
+        }
+        for (namespace, name, value) in &self.vendor_attributes {
+            let (key, value) = vendor_attribute(namespace, name, value);
+            body.extend(quote!(
+                element.extra_attributes.push((#key, #value));
+            ));
+        }
+        if let Some(value) = raw_attrs {
+            body.extend(check_raw_attribute_names(&value));
+            let value = process_value(&value);
+            body.extend(quote!(
+                for (name, value) in #value {
+                    if let Some(name) = typed_html::dom::raw_attribute_name(name) {
+                        element.extra_attributes.push((name, value.to_string()));
+                    }
+                }
+            ));
+        }
+        for mixin in &self.mixins {
+            body.extend(expand_mixin(mixin, &self.attributes)?);
+        }
         body.extend(opt_children);
 
+        if let Some((key, value)) = events_value {
+            if ty.is_none() {
+                return Err(missing_output_type_error(key.span()));
+            }
+            let value = process_value(&value);
+            body.extend(quote!(
+                element.events = #value;
+            ));
+        }
         for (key, value) in events.iter() {
             if ty.is_none() {
-                let mut err = quote_spanned! { key.span() =>
-                    compile_error! { "when using event handlers, you must declare the output type inside the html! macro" }
-                };
-                let hint = quote_spanned! { Span::call_site() =>
-                    compile_error! { "for example: change html!(<div>...</div>) to html!(<div>...</div> : String)" }
-                };
-                err.extend(hint);
-                return Err(err);
+                return Err(missing_output_type_error(key.span()));
             }
             let key = TokenTree::Ident(key.clone());
             let value = process_value(value);
@@ -271,15 +743,17 @@ impl Element {
             args.extend(quote!( #arg, ));
         }
 
-        let mut type_annotation = TokenStream::new();
-        if let Some(ty) = ty {
-            let type_var = to_stream(ty.clone());
-            type_annotation.extend(quote!(: typed_html::elements::#typename<#type_var>));
-        }
+        let constructor = match ty {
+            Some(ty) => {
+                let type_var = to_stream(ty.clone());
+                element_path(&typename, quote!(::<#type_var>))
+            }
+            None => element_path(&typename, TokenStream::new()),
+        };
 
         Ok(quote!(
             {
-                let mut element #type_annotation = typed_html::elements::#typename::new(#args);
+                let mut element = #constructor(#args);
                 #body
                 Box::new(element)
             }
@@ -292,8 +766,16 @@ impl Element {
         bump: &Ident,
         is_req_child: bool,
     ) -> Result<TokenStream, TokenStream> {
+        self.check_aria()?;
+        let warnings = self.deprecation_warnings();
         let name = self.name;
         let name_str = stringify_ident(&name);
+        if let Some(mixin) = self.mixins.first() {
+            let span = mixin.span();
+            return Err(quote_spanned! { span=>
+                compile_error! { "mixins aren't supported by the dodrio! macro" }
+            });
+        }
         let typename: TokenTree = ident::new_raw(&name_str, name.span()).into();
         let tag_name = TokenTree::from(Literal::string(&name_str));
         let req_names = required_children(&name_str);
@@ -309,8 +791,16 @@ impl Element {
                 compile_error! { #error }
             });
         }
+        if let Some((key, _)) = extract_events_value(&mut self.attributes) {
+            let span = key.span();
+            return Err(quote_spanned! { span=>
+                compile_error! { "the dodrio! macro doesn't support setting all event handlers with on={...}" }
+            });
+        }
         let events = extract_event_handlers(&mut self.attributes);
         let data_attrs = extract_data_attrs(&mut self.attributes);
+        let aria_attrs = extract_aria_attrs(&mut self.attributes);
+        let raw_attrs = extract_raw_attrs(&mut self.attributes);
         let attrs = self.attributes.iter().map(|(key, value)| {
             (
                 key.to_string(),
@@ -325,7 +815,7 @@ impl Element {
             .map(|node| node.into_dodrio_token_stream(bump, true))
             .collect::<Result<Vec<TokenStream>, TokenStream>>()?;
 
-        let mut set_attrs = TokenStream::new();
+        let mut set_attrs = warnings;
 
         for (attr_str, key, value) in attrs {
             match value {
@@ -354,6 +844,7 @@ impl Element {
                         eprintln_msg += "\nERROR: rebuild with nightly to print source location";
                     }
 
+                    set_attrs.extend(check_literal(&name_str, &attr_str, &key, lit, &self.attributes));
                     set_attrs.extend(quote!(
                         element.attrs.#key = Some(#lit.parse().unwrap_or_else(|err| {
                             eprintln!(#eprintln_msg, err);
@@ -370,7 +861,8 @@ impl Element {
             }
         }
 
-        let attr_max_len = self.attributes.len() + data_attrs.len();
+        let attr_max_len =
+            self.attributes.len() + data_attrs.len() + aria_attrs.len() + self.vendor_attributes.len();
         let mut builder = quote!(
             let mut attr_list = dodrio::bumpalo::collections::Vec::with_capacity_in(#attr_max_len, #bump);
         );
@@ -378,12 +870,13 @@ impl Element {
         // Build the attributes.
         for (key, _) in self.attributes.iter() {
             let key_str = stringify_ident(key);
+            let bare_when_empty = BARE_WHEN_EMPTY.contains(&key_str.as_str());
             let key = ident::new_raw(&key_str, key.span());
             let key_str = TokenTree::from(Literal::string(&key_str));
             builder.extend(quote!(
                 let attr_value = dodrio::bumpalo::format!(
                     in &#bump, "{}", element.attrs.#key.unwrap());
-                if !attr_value.is_empty() {
+                if #bare_when_empty || !attr_value.is_empty() {
                     attr_list.push(dodrio::builder::attr(#key_str, attr_value.into_bump_str()));
                 }
             ));
@@ -401,6 +894,40 @@ impl Element {
                 ));
             ));
         }
+        for (key, value) in aria_attrs.iter() {
+            let key = Literal::string(key);
+            let value = process_value(value);
+            builder.extend(quote!(
+                attr_list.push(dodrio::builder::attr(
+                    #key,
+                    dodrio::bumpalo::format!(in &#bump, "{}", #value).into_bump_str()
+                ));
+            ));
+        }
+
+        for (namespace, name, value) in &self.vendor_attributes {
+            let (key, value) = vendor_attribute(namespace, name, value);
+            builder.extend(quote!(
+                attr_list.push(dodrio::builder::attr(
+                    #key,
+                    dodrio::bumpalo::format!(in &#bump, "{}", #value).into_bump_str()
+                ));
+            ));
+        }
+        if let Some(value) = raw_attrs {
+            builder.extend(check_raw_attribute_names(&value));
+            let value = process_value(&value);
+            builder.extend(quote!(
+                for (name, value) in #value {
+                    if let Some(name) = typed_html::dom::raw_attribute_name(name) {
+                        attr_list.push(dodrio::builder::attr(
+                            name,
+                            dodrio::bumpalo::format!(in &#bump, "{}", value).into_bump_str()
+                        ));
+                    }
+                }
+            ));
+        }
 
         builder.extend(quote!(
             let mut node = dodrio::builder::ElementBuilder::new(#bump, #tag_name)
@@ -465,6 +992,12 @@ impl Element {
                     ));
                     continue;
                 }
+                Node::Let(stmt) => {
+                    static_children = false;
+                    child_builder.extend(stmt);
+                    continue;
+                }
+                Node::Wrapped(parts) => return Err(wrapped_error(&parts)),
             };
             child_array.extend(quote!(
                 #child,
@@ -493,12 +1026,13 @@ impl Element {
         for arg in arg_list {
             args.extend(quote!( #arg, ));
         }
+        let constructor =
+            element_path(&typename, quote!(::<typed_html::output::dodrio::Dodrio>));
 
         Ok(quote!(
             {
                 #make_req_children
-                let mut element: typed_html::elements::#typename<typed_html::output::dodrio::Dodrio> =
-                      typed_html::elements::#typename::new(#args);
+                let mut element = #constructor(#args);
                 #set_attrs
                 #builder
             }
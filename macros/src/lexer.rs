@@ -85,27 +85,42 @@ impl From<Group> for Token {
 pub enum Keyword {
     In,
     With,
+    Let,
 }
 
-pub fn keywordise(tokens: Vec<Token>) -> Vec<Token> {
+fn keywordise_by<F>(tokens: Vec<Token>, keyword: F) -> Vec<Token>
+where
+    F: Fn(&str) -> Option<Keyword>,
+{
     tokens
         .into_iter()
         .map(|token| match token {
-            Token::Ident(ident) => {
-                let name = ident.to_string();
-                if name == "in" {
-                    Token::Keyword(Keyword::In, ident)
-                } else if name == "with" {
-                    Token::Keyword(Keyword::With, ident)
-                } else {
-                    Token::Ident(ident)
-                }
-            }
+            Token::Ident(ident) => match keyword(&ident.to_string()) {
+                Some(keyword) => Token::Keyword(keyword, ident),
+                None => Token::Ident(ident),
+            },
             t => t,
         })
         .collect()
 }
 
+/// Turn the keywords of the `declare_elements!` macro into keyword tokens.
+pub fn keywordise(tokens: Vec<Token>) -> Vec<Token> {
+    keywordise_by(tokens, |name| match name {
+        "in" => Some(Keyword::In),
+        "with" => Some(Keyword::With),
+        _ => None,
+    })
+}
+
+/// Turn the keywords of the `html!` macro into keyword tokens.
+pub fn keywordise_html(tokens: Vec<Token>) -> Vec<Token> {
+    keywordise_by(tokens, |name| match name {
+        "let" => Some(Keyword::Let),
+        _ => None,
+    })
+}
+
 pub fn to_stream<I: IntoIterator<Item = Token>>(tokens: I) -> TokenStream {
     let mut stream = TokenStream::new();
     stream.extend(tokens.into_iter().map(TokenTree::from));
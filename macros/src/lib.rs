@@ -13,8 +13,10 @@ mod html;
 mod ident;
 mod lexer;
 mod map;
+mod maud;
 mod parser;
 mod span;
+mod text;
 
 /// Construct a DOM tree.
 ///
@@ -23,11 +25,27 @@ mod span;
 /// [typed_html]: ../typed_html/index.html
 #[proc_macro_hack]
 pub fn html(input: TokenStream) -> TokenStream {
-    let stream = lexer::unroll_stream(input.into(), false);
+    let stream = lexer::keywordise_html(lexer::unroll_stream(input.into(), false));
     let result = html::expand_html(&stream);
     TokenStream::from(match result {
         Err(err) => error::parse_error(&stream, &err),
-        Ok((node, ty)) => match node.into_token_stream(&ty) {
+        Ok((node, ty)) => match node.into_root_token_stream(&ty) {
+            Err(err) => err,
+            Ok(success) => success,
+        },
+    })
+}
+
+/// Construct a DOM tree from Maud's syntax.
+///
+/// See the crate documentation for [`typed_html`][typed_html].
+///
+/// [typed_html]: ../typed_html/index.html
+#[proc_macro_hack]
+pub fn maud(input: TokenStream) -> TokenStream {
+    TokenStream::from(match maud::expand_maud(input.into()) {
+        Err(err) => err,
+        Ok((node, ty)) => match node.into_root_token_stream(&ty) {
             Err(err) => err,
             Ok(success) => success,
         },
@@ -42,7 +60,7 @@ pub fn html(input: TokenStream) -> TokenStream {
 #[cfg(feature = "dodrio")]
 #[proc_macro_hack]
 pub fn dodrio(input: TokenStream) -> TokenStream {
-    let stream = lexer::unroll_stream(input.into(), false);
+    let stream = lexer::keywordise_html(lexer::unroll_stream(input.into(), false));
     let result = html::expand_dodrio(&stream);
     TokenStream::from(match result {
         Err(err) => error::parse_error(&stream, &err),
@@ -56,17 +74,35 @@ pub fn dodrio(input: TokenStream) -> TokenStream {
 
 /// This macro is used by `typed_html` internally to generate types and
 /// implementations for HTML elements.
+///
+/// When it's used through `typed_html::declare_elements!`, the input starts
+/// with `$crate;`, which is the path to `typed_html`.
 #[proc_macro]
 pub fn declare_elements(input: TokenStream) -> TokenStream {
-    let stream = lexer::keywordise(lexer::unroll_stream(input.into(), true));
+    let (krate, input) = declare::split_crate(input.into());
+    let stream = lexer::keywordise(lexer::unroll_stream(input, true));
     let result = declare::expand_declare(&stream);
     TokenStream::from(match result {
         Err(err) => error::parse_error(&stream, &err),
         Ok(decls) => {
             let mut out = proc_macro2::TokenStream::new();
-            for decl in decls {
+            let mut models = proc_macro2::TokenStream::new();
+            for mut decl in decls {
+                if let Some(krate) = &krate {
+                    decl.set_crate(krate.clone());
+                }
+                models.extend(decl.content_model());
+                models.extend(quote::quote!(,));
                 out.extend(decl.into_token_stream());
             }
+            // The standard elements, declared inside `typed_html`, also get
+            // a table of their content models for the runtime validator.
+            if krate.is_none() {
+                out.extend(quote::quote!(
+                    /// The content models of the standard elements.
+                    pub static CONTENT_MODELS: &[crate::validate::ContentModel] = &[ #models ];
+                ));
+            }
             out
         }
     })
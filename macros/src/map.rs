@@ -21,6 +21,10 @@ where
         self.0.remove(&s).map(|(_, v)| v)
     }
 
+    pub fn get(&self, k: &str) -> Option<&V> {
+        self.0.get(k).map(|(_, v)| v)
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &(K, V)> {
         self.0.values()
     }
@@ -0,0 +1,293 @@
+//! A front end for the `maud!` macro, which parses Maud's syntax into the
+//! same nodes as the `html!` macro, so it's type checked the same way.
+
+use proc_macro2::{Delimiter, Group, Ident, Literal, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+
+use crate::html::{Element, Node};
+use crate::lexer::{unroll_stream, Token};
+use crate::map::StringyMap;
+
+type ParseResult<A> = Result<A, TokenStream>;
+
+fn error<A>(span: Span, message: &str) -> ParseResult<A> {
+    Err(quote_spanned! {span=>
+        compile_error! { #message }
+    })
+}
+
+struct Parser {
+    tokens: Vec<TokenTree>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(stream: TokenStream) -> Self {
+        Parser {
+            tokens: stream.into_iter().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&TokenTree> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<TokenTree> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_punct(&self, c: char) -> bool {
+        match self.peek() {
+            Some(TokenTree::Punct(punct)) => punct.as_char() == c,
+            _ => false,
+        }
+    }
+
+    fn span(&self) -> Span {
+        self.peek()
+            .or_else(|| self.tokens.last())
+            .map_or_else(Span::call_site, TokenTree::span)
+    }
+
+    /// Parse nodes until the end of the input, or a `:` followed by the
+    /// output type at the top level.
+    fn markup(&mut self) -> ParseResult<Vec<Node>> {
+        let mut nodes = Vec::new();
+        while self.peek().is_some() && !self.peek_punct(':') {
+            nodes.extend(self.node()?);
+        }
+        Ok(nodes)
+    }
+
+    fn node(&mut self) -> ParseResult<Vec<Node>> {
+        match self.next() {
+            Some(TokenTree::Literal(literal)) => {
+                if literal.to_string().starts_with('"') {
+                    Ok(vec![Node::Text(literal)])
+                } else {
+                    error(literal.span(), "text must be a string literal")
+                }
+            }
+            Some(TokenTree::Group(group)) => match group.delimiter() {
+                // A splice, like `(name)`, which is rendered as text.
+                Delimiter::Parenthesis => {
+                    let span = group.span();
+                    let expr = group.stream();
+                    let text = quote_spanned!(span=> typed_html::text!("{}", #expr));
+                    Ok(vec![Node::Block(Group::new(Delimiter::Brace, text))])
+                }
+                Delimiter::Brace => Parser::new(group.stream()).markup(),
+                _ => error(group.span(), "expected an element, text or a splice"),
+            },
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '@' => self.control(),
+            Some(TokenTree::Ident(name)) => Ok(vec![Node::Element(self.element(name)?)]),
+            Some(token) => error(token.span(), "expected an element, text or a splice"),
+            None => error(self.span(), "unexpected end of input"),
+        }
+    }
+
+    /// Parse a name which may contain hyphens, like `aria-label`, into an
+    /// ident with underscores, as the `html!` macro does.
+    fn hyphenated(&mut self, first: Ident) -> Ident {
+        let mut name = first.to_string();
+        while self.peek_punct('-') {
+            match self.tokens.get(self.pos + 1) {
+                Some(TokenTree::Ident(part)) => {
+                    name.push('_');
+                    name.push_str(&part.to_string());
+                    self.pos += 2;
+                }
+                _ => break,
+            }
+        }
+        Ident::new(&name, first.span())
+    }
+
+    /// Parse a class or id after a `.` or `#`, which is either a name which
+    /// may contain hyphens or a string literal.
+    fn class_or_id(&mut self) -> ParseResult<String> {
+        match self.next() {
+            Some(TokenTree::Ident(ident)) => {
+                let mut name = ident.to_string();
+                while self.peek_punct('-') {
+                    self.pos += 1;
+                    name.push('-');
+                    match self.next() {
+                        Some(TokenTree::Ident(part)) => name.push_str(&part.to_string()),
+                        Some(TokenTree::Literal(part)) => name.push_str(&part.to_string()),
+                        _ => return error(self.span(), "expected a class or id name"),
+                    }
+                }
+                Ok(name)
+            }
+            Some(TokenTree::Literal(literal)) if literal.to_string().starts_with('"') => {
+                let quoted = literal.to_string();
+                Ok(quoted[1..quoted.len() - 1].to_string())
+            }
+            _ => error(self.span(), "expected a class or id name"),
+        }
+    }
+
+    fn element(&mut self, name: Ident) -> ParseResult<Element> {
+        let mut attributes = StringyMap::new();
+        let mut classes = Vec::new();
+        let mut id = None;
+        let span = name.span();
+
+        // Classes and the id, like `div.card#main`.
+        loop {
+            if self.peek_punct('.') {
+                self.pos += 1;
+                classes.push(self.class_or_id()?);
+            } else if self.peek_punct('#') {
+                self.pos += 1;
+                id = Some(self.class_or_id()?);
+            } else {
+                break;
+            }
+        }
+        if !classes.is_empty() {
+            let class = Literal::string(&classes.join(" "));
+            attributes.insert(Ident::new("class", span), TokenTree::Literal(class));
+        }
+        if let Some(id) = id {
+            attributes.insert(
+                Ident::new("id", span),
+                TokenTree::Literal(Literal::string(&id)),
+            );
+        }
+
+        // Attributes, like `href="/"`, `href=(url)` or `checked[done]`.
+        while let Some(TokenTree::Ident(key)) = self.peek().cloned() {
+            self.pos += 1;
+            let key = self.hyphenated(key);
+            let value = if self.peek_punct('=') {
+                self.pos += 1;
+                match self.next() {
+                    Some(TokenTree::Literal(literal)) => TokenTree::Literal(literal),
+                    Some(TokenTree::Group(group)) if group.delimiter() != Delimiter::Bracket => {
+                        TokenTree::Group(group)
+                    }
+                    _ => return error(key.span(), "expected a literal or a splice"),
+                }
+            } else {
+                match self.peek() {
+                    Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Bracket => {
+                        let group = Group::new(Delimiter::Parenthesis, group.stream());
+                        self.pos += 1;
+                        TokenTree::Group(group)
+                    }
+                    _ => TokenTree::Ident(Ident::new("true", key.span())),
+                }
+            };
+            if (key == "class" && !classes.is_empty())
+                || (key == "id" && attributes.get("id").is_some())
+            {
+                return error(key.span(), "this is already set by the element's shorthand");
+            }
+            attributes.insert(key, value);
+        }
+
+        let children = match self.next() {
+            Some(TokenTree::Group(ref group)) if group.delimiter() == Delimiter::Brace => {
+                Parser::new(group.stream()).markup()?
+            }
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == ';' => Vec::new(),
+            _ => return error(span, "expected `{ ... }` or `;` after the element"),
+        };
+
+        Ok(Element {
+            name,
+            attributes,
+            mixins: Vec::new(),
+            vendor_attributes: Vec::new(),
+            children,
+        })
+    }
+
+    /// Collect the tokens before a `{ ... }` block, and the nodes in it.
+    fn head_and_body(&mut self, head: TokenStream) -> ParseResult<(TokenStream, Vec<Node>)> {
+        let mut head = head;
+        loop {
+            match self.next() {
+                Some(TokenTree::Group(ref group)) if group.delimiter() == Delimiter::Brace => {
+                    let body = Parser::new(group.stream()).markup()?;
+                    return Ok((head, body));
+                }
+                Some(token) => head.extend(Some(token)),
+                None => return error(self.span(), "expected `{ ... }`"),
+            }
+        }
+    }
+
+    /// Parse `@if`, `@for`, `@while` and `@let`.
+    fn control(&mut self) -> ParseResult<Vec<Node>> {
+        let keyword = match self.next() {
+            Some(TokenTree::Ident(keyword)) => keyword,
+            _ => {
+                return error(
+                    self.span(),
+                    "expected `if`, `for`, `while` or `let` after `@`",
+                )
+            }
+        };
+        match keyword.to_string().as_str() {
+            "if" => {
+                let mut parts = vec![self.head_and_body(quote!(#keyword))?];
+                while self.peek_punct('@') {
+                    match self.tokens.get(self.pos + 1) {
+                        Some(TokenTree::Ident(ident)) if ident == "else" => self.pos += 2,
+                        _ => break,
+                    }
+                    parts.push(self.head_and_body(quote!(else))?);
+                }
+                Ok(vec![Node::Wrapped(parts)])
+            }
+            "for" | "while" => Ok(vec![Node::Wrapped(vec![
+                self.head_and_body(quote!(#keyword))?
+            ])]),
+            "let" => {
+                let mut stmt = quote!(#keyword);
+                loop {
+                    match self.next() {
+                        Some(TokenTree::Punct(ref punct)) if punct.as_char() == ';' => {
+                            stmt.extend(quote!(;));
+                            return Ok(vec![Node::Let(stmt)]);
+                        }
+                        Some(token) => stmt.extend(Some(token)),
+                        None => return error(keyword.span(), "expected `;` after `@let`"),
+                    }
+                }
+            }
+            _ => error(
+                keyword.span(),
+                "expected `if`, `for`, `while` or `let` after `@`",
+            ),
+        }
+    }
+}
+
+/// Parse the input to the `maud!` macro into its root node and its output
+/// type, if one is given after a `:`.
+pub fn expand_maud(input: TokenStream) -> Result<(Node, Option<Vec<Token>>), TokenStream> {
+    let mut parser = Parser::new(input);
+    let mut nodes = parser.markup()?;
+    let ty = if parser.peek_punct(':') {
+        parser.pos += 1;
+        let ty: TokenStream = parser.tokens.drain(parser.pos..).collect();
+        Some(unroll_stream(ty, false))
+    } else {
+        None
+    };
+    match nodes.len() {
+        1 => Ok((nodes.remove(0), ty)),
+        0 => error(Span::call_site(), "expected an element"),
+        _ => error(
+            parser.tokens[0].span(),
+            "the maud! macro must have a single element at the top level",
+        ),
+    }
+}
@@ -0,0 +1,207 @@
+use proc_macro2::{Delimiter, Group, Literal, Spacing, TokenStream, TokenTree};
+use quote::{quote, quote_spanned};
+
+/// Turn a text literal into a string literal with its character references,
+/// like `&nbsp;` or `&#x2014;`, decoded.
+///
+/// Literals without any `&` in them are left alone. An ampersand which isn't
+/// followed by a name and a semicolon is just an ampersand, but something
+/// which looks like a reference and isn't one is a compile error.
+pub fn decode_text(text: Literal) -> TokenStream {
+    let value = match string_value(&text.to_string()) {
+        Some(ref value) if value.contains('&') => value.clone(),
+        _ => return quote!(#text),
+    };
+    match decode_references(&value) {
+        Ok(decoded) => {
+            let mut literal = Literal::string(&decoded);
+            literal.set_span(text.span());
+            quote!(#literal)
+        }
+        Err(reference) => {
+            let span = text.span();
+            let error = format!("unknown character reference {}", reference);
+            quote_spanned! { span=>
+                compile_error! { #error }
+            }
+        }
+    }
+}
+
+/// Turn a child block with a format spec, like `{ price:.2 }`, into a
+/// [`text!()`] block which formats the expression with it.
+///
+/// The spec is whatever follows a `:` at the top level of the block, which
+/// can't otherwise be there in an expression. Blocks with statements in them,
+/// or where what follows the `:` isn't a format spec, are left alone.
+pub fn format_block(group: Group) -> Group {
+    let tokens: Vec<TokenTree> = group.stream().into_iter().collect();
+    let is_colon = |index: usize| match tokens.get(index) {
+        Some(TokenTree::Punct(punct)) => punct.as_char() == ':',
+        _ => false,
+    };
+    let mut colon = None;
+    for (index, token) in tokens.iter().enumerate() {
+        if let TokenTree::Punct(punct) = token {
+            match punct.as_char() {
+                ';' => return group,
+                // Skip both halves of a `::`.
+                ':' if punct.spacing() == Spacing::Joint && is_colon(index + 1) => {}
+                ':' if index > 0 && is_colon(index - 1) => {}
+                ':' if colon.is_none() => colon = Some(index),
+                _ => {}
+            }
+        }
+    }
+    let colon = match colon {
+        Some(colon) if colon > 0 => colon,
+        _ => return group,
+    };
+    let spec: String = tokens[colon + 1..]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    if !is_format_spec(&spec) {
+        return group;
+    }
+
+    let span = group.span();
+    // The format string needs the block's span, so names in the spec, like
+    // `width$`, are looked up where the block is.
+    let mut format = Literal::string(&format!("{{:{}}}", spec));
+    format.set_span(span);
+    let expr = Group::new(
+        Delimiter::Parenthesis,
+        tokens[..colon].iter().cloned().collect(),
+    );
+    let text = quote_spanned!(span=> typed_html::text!(#format, #expr));
+    let mut out = Group::new(Delimiter::Brace, text);
+    out.set_span(span);
+    out
+}
+
+/// Whether a string is a format spec, the part of a `format!()` argument
+/// like `{:>8.2}` after the `:`, in the form
+/// `[[fill]align][sign]['#']['0'][width]['.' precision][type]`.
+///
+/// Only the standard formatting traits are accepted as the type, so a block
+/// like `{ x:foo }` is left to fail as an expression instead of in `format!()`.
+fn is_format_spec(spec: &str) -> bool {
+    if spec.is_empty() {
+        return false;
+    }
+    let mut rest = spec;
+    let is_align = |c: Option<char>| matches!(c, Some('<') | Some('^') | Some('>'));
+    let mut chars = rest.chars();
+    let fill = chars.next();
+    if is_align(chars.next()) {
+        rest = &rest[fill.map_or(0, char::len_utf8) + 1..];
+    } else if is_align(fill) {
+        rest = &rest[1..];
+    }
+    for flag in &["+", "-", "#", "0"] {
+        rest = rest.strip_prefix(flag).unwrap_or(rest);
+    }
+    rest = count(rest);
+    if let Some(precision) = rest.strip_prefix('.') {
+        let after = count(precision);
+        if after.len() == precision.len() {
+            return false;
+        }
+        rest = after;
+    }
+    matches!(
+        rest,
+        "" | "?" | "x?" | "X?" | "x" | "X" | "o" | "b" | "e" | "E" | "p"
+    )
+}
+
+/// Skip a width or precision, which is a number or a name followed by `$`.
+fn count(spec: &str) -> &str {
+    let digits = spec
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(spec.len());
+    if digits > 0 {
+        return &spec[digits..];
+    }
+    let name = spec
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(spec.len());
+    match spec[name..].strip_prefix('$') {
+        Some(rest) if name > 0 => rest,
+        _ => spec,
+    }
+}
+
+/// Decode the character references in a string, or return the first one
+/// which isn't valid.
+fn decode_references(text: &str) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let name_len = rest[1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '#'))
+            .unwrap_or(rest.len() - 1);
+        if name_len == 0 || !rest[1 + name_len..].starts_with(';') {
+            out.push('&');
+            rest = &rest[1..];
+            continue;
+        }
+        let reference = &rest[..name_len + 2];
+        match htmlescape::decode_html(reference) {
+            Ok(decoded) => out.push_str(&decoded),
+            Err(_) => return Err(reference.to_string()),
+        }
+        rest = &rest[reference.len()..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// Get the value of a string literal from its source, or `None` if it isn't
+/// one.
+fn string_value(source: &str) -> Option<String> {
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.find('"')?;
+        return Some(raw[hashes + 1..raw.len() - hashes - 1].to_string());
+    }
+    if !source.starts_with('"') {
+        return None;
+    }
+    let mut value = String::with_capacity(source.len());
+    let mut chars = source[1..source.len() - 1].chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            'x' => {
+                let code: String = chars.by_ref().take(2).collect();
+                value.push(u8::from_str_radix(&code, 16).ok()? as char);
+            }
+            'u' => {
+                let code: String = chars
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|c| *c != '}')
+                    .filter(|c| *c != '_')
+                    .collect();
+                value.push(std::char::from_u32(u32::from_str_radix(&code, 16).ok()?)?);
+            }
+            '\n' => {
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            c => value.push(c),
+        }
+    }
+    Some(value)
+}
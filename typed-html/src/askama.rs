@@ -0,0 +1,81 @@
+//! Interop with [Askama] templates.
+//!
+//! DOM trees are marked as [`HtmlSafe`][HtmlSafe], so Askama writes them into
+//! a template as they are instead of escaping them a second time:
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! use askama::Template;
+//!
+//! #[derive(Template)]
+//! #[template(source = "<main>{{ content }}</main>", ext = "html")]
+//! struct Page {
+//!     content: DOMTree<String>,
+//! }
+//!
+//! # fn main() {
+//! let page = Page {
+//!     content: html!(<p>"Tom & Jerry"</p>),
+//! };
+//! assert_eq!("<main><p>Tom &amp; Jerry</p></main>", page.render().unwrap());
+//! # }
+//! ```
+//!
+//! Going the other way, [`fragment()`][fragment] renders an Askama template
+//! into a node you can put in a DOM tree.
+//!
+//! This module is only available with the `askama` feature flag enabled.
+//!
+//! [Askama]: https://docs.rs/askama/
+//! [HtmlSafe]: https://docs.rs/askama/latest/askama/filters/trait.HtmlSafe.html
+//! [fragment]: fn.fragment.html
+
+use ::askama::filters::HtmlSafe;
+use ::askama::Template;
+
+use crate::dom::{Node, TextNode, UnsafeTextNode};
+use crate::OutputType;
+
+impl<T: OutputType> HtmlSafe for dyn Node<T> {}
+impl<T: OutputType> HtmlSafe for TextNode<T> {}
+impl<T: OutputType> HtmlSafe for UnsafeTextNode<T> {}
+
+/// Render an Askama template into a node which can be a child of any element
+/// that accepts text.
+///
+/// Askama escapes the values it writes into a template, so its output is
+/// inserted into the tree as it is, like [`unsafe_text!`][unsafe_text]. The
+/// template is trusted to produce well formed HTML.
+///
+/// Returns `Err` if the template fails to render.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use askama::Template;
+/// use typed_html::askama::fragment;
+///
+/// #[derive(Template)]
+/// #[template(source = "<b>{{ name }}</b>", ext = "html")]
+/// struct Greeting<'a> {
+///     name: &'a str,
+/// }
+///
+/// # fn main() {
+/// let greeting = fragment(&Greeting { name: "<Kitty>" }).unwrap();
+/// let doc: DOMTree<String> = html!(<p>"Hello, " { greeting }</p>);
+/// assert_eq!("<p>Hello, <b>&#60;Kitty&#62;</b></p>", doc.to_string());
+/// # }
+/// ```
+///
+/// [unsafe_text]: ../macro.unsafe_text.html
+pub fn fragment<T, S>(template: &S) -> ::askama::Result<Box<UnsafeTextNode<T>>>
+where
+    T: OutputType,
+    S: Template + ?Sized,
+{
+    Ok(Box::new(UnsafeTextNode::new(template.render()?)))
+}
@@ -0,0 +1,177 @@
+//! Custom attribute value types.
+//!
+//! The [`attribute!`][attribute] macro declares a string type for attribute
+//! values, which is checked by a `const fn` you provide. String literals
+//! given for an attribute of that type in the [`html!`][html] macro are
+//! checked when your crate is built, just like the built in types such as
+//! [`Color`][Color], and values built at runtime are checked when they're
+//! parsed.
+//!
+//! ```
+//! # use typed_html::{attribute, declare_elements, html};
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::elements::*;
+//!
+//! const fn validate_stars(stars: &str) -> Result<(), &'static str> {
+//!     match stars.as_bytes() {
+//!         [b'1'..=b'5'] => Ok(()),
+//!         _ => Err("stars must be a number from 1 to 5"),
+//!     }
+//! }
+//!
+//! attribute! {
+//!     /// A rating from one to five stars.
+//!     pub Stars = validate_stars;
+//! }
+//!
+//! declare_elements! {
+//!     rating {
+//!         stars: Stars,
+//!     } in [FlowContent, PhrasingContent];
+//! }
+//!
+//! # fn main() {
+//! let doc: DOMTree<String> = html!(<rating stars="4"/>);
+//! assert_eq!("<rating stars=\"4\"></rating>", doc.to_string());
+//!
+//! assert!("6".parse::<Stars>().is_err());
+//! # }
+//! ```
+//!
+//! With `stars="6"` in the `html!` macro above, the crate wouldn't build.
+//!
+//! The attributes of the standard elements are fixed, so a new attribute
+//! type is attached to elements by using it in the attributes of elements
+//! declared with [`declare_elements!`][declare_elements].
+//!
+//! [attribute]: ../macro.attribute.html
+//! [declare_elements]: ../macro.declare_elements.html
+//! [html]: ../macro.html.html
+//! [Color]: ../types/struct.Color.html
+
+use std::marker::PhantomData;
+
+/// Declare attribute value types, which are strings checked by a `const fn`.
+///
+/// See the [`attribute`][attribute] module for details.
+///
+/// [attribute]: attribute/index.html
+#[macro_export]
+macro_rules! attribute {
+    ($($(#[$meta:meta])* $vis:vis $name:ident = $validate:path;)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+            $vis struct $name(String);
+
+            impl $name {
+                /// Construct a new value from a string.
+                ///
+                /// Returns `Err` if the provided string is invalid.
+                pub fn try_new<S: Into<String>>(value: S) -> Result<Self, &'static str> {
+                    let value = value.into();
+                    $validate(&value)?;
+                    Ok($name(value))
+                }
+
+                /// Construct a new value from a string.
+                ///
+                /// Panics if the provided string is invalid.
+                pub fn new<S: Into<String>>(value: S) -> Self {
+                    let value = value.into();
+                    Self::try_new(value.clone()).unwrap_or_else(|err| {
+                        panic!(
+                            "{}: {:?} is not valid: {}",
+                            stringify!($name), value, err
+                        )
+                    })
+                }
+            }
+
+            impl std::str::FromStr for $name {
+                type Err = &'static str;
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    $name::try_new(s)
+                }
+            }
+
+            impl<'a> From<&'a str> for $name {
+                fn from(str: &'a str) -> Self {
+                    $name::new(str)
+                }
+            }
+
+            impl From<$name> for String {
+                fn from(value: $name) -> Self {
+                    value.0
+                }
+            }
+
+            impl std::fmt::Display for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    std::fmt::Display::fmt(&self.0, f)
+                }
+            }
+
+            impl std::ops::Deref for $name {
+                type Target = String;
+                fn deref(&self) -> &Self::Target {
+                    &self.0
+                }
+            }
+
+            impl<L: $crate::attribute::Literal> $crate::attribute::CheckLiteral<L> for $name {
+                const CHECK: () = $crate::types::check_literal($validate(L::VALUE));
+            }
+        )*
+    };
+}
+
+/// A string literal from the `html!` macro, as a type.
+#[doc(hidden)]
+pub trait Literal {
+    const VALUE: &'static str;
+}
+
+/// Attribute value types which check string literals at compile time.
+///
+/// Evaluating `CHECK` fails to compile if the literal `L` is invalid.
+#[doc(hidden)]
+pub trait CheckLiteral<L: Literal> {
+    const CHECK: ();
+}
+
+/// Check a string literal attribute value, if its type knows how.
+///
+/// The `html!` macro calls `(&probe::<_, L>(&element.attrs.name)).check()`,
+/// which finds [`Checked`][Checked] if the attribute type implements
+/// [`CheckLiteral`][CheckLiteral], and falls back to [`Unchecked`][Unchecked]
+/// otherwise.
+#[doc(hidden)]
+pub fn probe<T, L>(_attr: &Option<T>) -> Probe<T, L> {
+    Probe(PhantomData)
+}
+
+#[doc(hidden)]
+pub struct Probe<T, L>(PhantomData<(T, L)>);
+
+#[doc(hidden)]
+pub trait Checked {
+    fn check(&self);
+}
+
+impl<T: CheckLiteral<L>, L: Literal> Checked for Probe<T, L> {
+    #[allow(clippy::let_unit_value)]
+    fn check(&self) {
+        let () = T::CHECK;
+    }
+}
+
+#[doc(hidden)]
+pub trait Unchecked {
+    fn check(&self);
+}
+
+impl<T, L> Unchecked for &Probe<T, L> {
+    fn check(&self) {}
+}
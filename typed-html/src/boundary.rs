@@ -0,0 +1,178 @@
+//! Error boundaries, for keeping one broken part of a page from breaking the
+//! whole page.
+//!
+//! An [`ErrorBoundary`][ErrorBoundary] builds and renders a subtree inside a
+//! closure. If the closure returns an error, or building or rendering the
+//! subtree panics, the boundary puts a fallback in its place and records what
+//! went wrong, so the rest of the page can still be sent and the errors can
+//! be logged afterwards.
+//!
+//! Subtrees are rendered to strings with the standard [`Html`][Html]
+//! escaping policy as they're caught, so this is for server side rendering
+//! only.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::{html, text};
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::boundary::ErrorBoundary;
+//!
+//! # fn main() {
+//! let boundary = ErrorBoundary::new(|_| html!(<p class="error">"Not available."</p>));
+//! let doc: DOMTree<String> = html!(
+//!     <div>
+//!         { boundary.render(|| html!(<p>"Weather: sunny"</p>)) }
+//!         { boundary.try_render(|| {
+//!             let price: u32 = "twelve".parse()?;
+//!             Ok::<_, std::num::ParseIntError>(html!(<p>{ text!("Price: {}", price) }</p>))
+//!         }) }
+//!     </div>
+//! );
+//! assert_eq!(
+//!     "<div><p>Weather: sunny</p><p class=\"error\">Not available.</p></div>",
+//!     doc.to_string()
+//! );
+//! assert_eq!(
+//!     "error: invalid digit found in string",
+//!     boundary.errors()[0].to_string()
+//! );
+//! # }
+//! ```
+//!
+//! Panics are still reported by the panic hook as usual when they're caught.
+//!
+//! [ErrorBoundary]: struct.ErrorBoundary.html
+//! [Html]: ../escape/struct.Html.html
+
+use std::any::Any;
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::dom::{DOMTree, Node, UnsafeTextNode};
+
+/// Something which went wrong inside an [`ErrorBoundary`][ErrorBoundary].
+///
+/// [ErrorBoundary]: struct.ErrorBoundary.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CaughtError {
+    /// The closure returned an error, with this message.
+    Error(String),
+    /// Building or rendering the subtree panicked, with this message.
+    Panic(String),
+}
+
+impl Display for CaughtError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CaughtError::Error(message) => write!(f, "error: {}", message),
+            CaughtError::Panic(message) => write!(f, "panic: {}", message),
+        }
+    }
+}
+
+/// Renders subtrees, replacing the ones which fail with a fallback.
+///
+/// The boundary is shared by reference, so it can be used in several places
+/// in the same `html!` invocation.
+pub struct ErrorBoundary {
+    fallback: Box<dyn Fn(&CaughtError) -> DOMTree<String>>,
+    errors: RefCell<Vec<CaughtError>>,
+}
+
+impl ErrorBoundary {
+    /// Construct a boundary which builds its fallbacks with the given
+    /// function, from the error which was caught.
+    pub fn new<F>(fallback: F) -> Self
+    where
+        F: Fn(&CaughtError) -> DOMTree<String> + 'static,
+    {
+        ErrorBoundary {
+            fallback: Box::new(fallback),
+            errors: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Build and render a subtree, or the fallback if that panics.
+    pub fn render<F, N>(&self, build: F) -> Box<UnsafeTextNode<String>>
+    where
+        F: FnOnce() -> Box<N>,
+        N: Node<String> + ?Sized,
+    {
+        self.try_render(|| Ok::<_, String>(build()))
+    }
+
+    /// Build and render a subtree, or the fallback if that returns an error
+    /// or panics.
+    pub fn try_render<F, N, E>(&self, build: F) -> Box<UnsafeTextNode<String>>
+    where
+        F: FnOnce() -> Result<Box<N>, E>,
+        N: Node<String> + ?Sized,
+        E: Display,
+    {
+        let result = catch_unwind(AssertUnwindSafe(|| build().map(|node| node.to_string())));
+        let error = match result {
+            Ok(Ok(html)) => return Box::new(UnsafeTextNode::new(html)),
+            Ok(Err(err)) => CaughtError::Error(err.to_string()),
+            Err(panic) => CaughtError::Panic(panic_message(panic)),
+        };
+        let fallback = (self.fallback)(&error).to_string();
+        self.errors.borrow_mut().push(error);
+        Box::new(UnsafeTextNode::new(fallback))
+    }
+
+    /// The errors which have been caught so far, in the order they happened.
+    pub fn errors(&self) -> Vec<CaughtError> {
+        self.errors.borrow().clone()
+    }
+
+    /// Whether any errors have been caught.
+    pub fn has_errors(&self) -> bool {
+        !self.errors.borrow().is_empty()
+    }
+}
+
+impl Debug for ErrorBoundary {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ErrorBoundary")
+            .field("errors", &self.errors)
+            .finish()
+    }
+}
+
+/// The message a panic was started with, if it was a string.
+fn panic_message(panic: Box<dyn Any + Send>) -> String {
+    match panic.downcast::<String>() {
+        Ok(message) => *message,
+        Err(panic) => match panic.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+
+    #[test]
+    fn test_boundary_catches_panics() {
+        let boundary = ErrorBoundary::new(|err| match err {
+            CaughtError::Panic(_) => html!(<p>"Broken"</p>),
+            CaughtError::Error(_) => html!(<p>"Failed"</p>),
+        });
+        let doc: DOMTree<String> = html!(
+            <div>
+                { boundary.render(|| -> DOMTree<String> { panic!("widget {} is broken", 3) }) }
+                { boundary.render(|| html!(<span>"Fine"</span>)) }
+            </div>
+        );
+        assert_eq!("<div><p>Broken</p><span>Fine</span></div>", doc.to_string());
+        assert_eq!(
+            vec![CaughtError::Panic("widget 3 is broken".to_string())],
+            boundary.errors()
+        );
+    }
+}
@@ -0,0 +1,476 @@
+//! Compressing responses with fragments which were compressed ahead of time.
+//!
+//! Most of a page is often the same on every request: the navigation, the
+//! footer, the icons. A [`Compressed`][Compressed] fragment holds the rendered
+//! HTML of a piece like that, along with its gzip and brotli compressed
+//! bytes, which are worked out once, when it's constructed, so keep it
+//! somewhere it lives as long as the server does.
+//!
+//! An [`Encoder`][Encoder] writes a compressed response body. The parts of
+//! the page which change are compressed as they're written, and compressed
+//! fragments are spliced into the output as they are, without compressing
+//! them again, so the client sees one ordinary gzip or brotli stream.
+//!
+//! This module is only available with the `compress` feature flag enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! use std::io::Read;
+//! use typed_html::compress::{Compressed, Encoder, Encoding};
+//!
+//! # fn main() -> std::io::Result<()> {
+//! let footer: DOMTree<String> = html!(<footer>"Made with cats"</footer>);
+//! let footer = Compressed::from(footer);
+//!
+//! let encoding = Encoding::negotiate("gzip, deflate").unwrap();
+//! let mut body = Encoder::new(Vec::new(), encoding)?;
+//! body.write_str("<main>Hello Joe!</main>")?;
+//! body.write_compressed(&footer)?;
+//! let body = body.finish()?;
+//!
+//! let mut html = String::new();
+//! flate2::read::GzDecoder::new(&body[..]).read_to_string(&mut html)?;
+//! assert_eq!("<main>Hello Joe!</main><footer>Made with cats</footer>", html);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [Compressed]: struct.Compressed.html
+//! [Encoder]: struct.Encoder.html
+
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io::{self, Write};
+
+use brotli::concat::{BroCatli, BroCatliResult};
+use brotli::enc::BrotliEncoderParams;
+use flate2::{Compress, Compression, Crc, FlushCompress, Status};
+
+use crate::dom::DOMTree;
+use crate::OutputType;
+
+/// The brotli quality for the parts of a response compressed as they're
+/// written. Fragments are compressed at the best quality, since it's only
+/// done once.
+const BROTLI_QUALITY: i32 = 5;
+
+/// The gzip header, with no file name or modification time.
+const GZIP_HEADER: [u8; 10] = [0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 0xff];
+
+/// A content coding an [`Encoder`][Encoder] can write.
+///
+/// [Encoder]: struct.Encoder.html
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Encoding {
+    /// `gzip`, which every browser supports.
+    Gzip,
+    /// `br`, which compresses HTML better, but only over HTTPS.
+    Brotli,
+}
+
+impl Encoding {
+    /// Pick the encoding to use for a request from its `Accept-Encoding`
+    /// header, preferring brotli, or return `None` if it accepts neither.
+    pub fn negotiate(accept_encoding: &str) -> Option<Self> {
+        let mut accepted = (None, None);
+        for coding in accept_encoding.split(',') {
+            let mut params = coding.split(';');
+            let name = params.next().unwrap_or("").trim().to_ascii_lowercase();
+            let allowed = params
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .all(|q| q.trim().parse::<f32>().is_ok_and(|q| q > 0.0));
+            let (gzip, brotli) = &mut accepted;
+            match name.as_str() {
+                "gzip" | "x-gzip" => *gzip = Some(allowed),
+                "br" => *brotli = Some(allowed),
+                "*" => {
+                    gzip.get_or_insert(allowed);
+                    brotli.get_or_insert(allowed);
+                }
+                _ => {}
+            }
+        }
+        match accepted {
+            (_, Some(true)) => Some(Encoding::Brotli),
+            (Some(true), _) => Some(Encoding::Gzip),
+            _ => None,
+        }
+    }
+
+    /// The name of the encoding, for the `Content-Encoding` header.
+    pub fn name(self) -> &'static str {
+        match self {
+            Encoding::Gzip => "gzip",
+            Encoding::Brotli => "br",
+        }
+    }
+}
+
+/// A fragment of HTML, compressed ahead of time with each
+/// [`Encoding`][Encoding].
+///
+/// The compressed bytes aren't complete gzip or brotli streams of their own,
+/// only pieces of one, to be written with
+/// [`Encoder::write_compressed()`][write_compressed].
+///
+/// [Encoding]: enum.Encoding.html
+/// [write_compressed]: struct.Encoder.html#method.write_compressed
+pub struct Compressed {
+    html: String,
+    deflate: Vec<u8>,
+    crc: Crc,
+    brotli: Vec<u8>,
+}
+
+impl Compressed {
+    /// Compress a fragment of rendered HTML.
+    pub fn new<S: Into<String>>(html: S) -> Self {
+        let html = html.into();
+        let mut deflate = Vec::new();
+        let mut compress = Compress::new(Compression::best(), false);
+        deflate_into(
+            &mut compress,
+            html.as_bytes(),
+            FlushCompress::Full,
+            &mut deflate,
+        );
+        let mut crc = Crc::new();
+        crc.update(html.as_bytes());
+        let brotli = brotli_stream(html.as_bytes(), 11);
+        Compressed {
+            html,
+            deflate,
+            crc,
+            brotli,
+        }
+    }
+
+    /// The uncompressed HTML.
+    pub fn html(&self) -> &str {
+        &self.html
+    }
+}
+
+impl<T: OutputType + Send> From<DOMTree<T>> for Compressed {
+    fn from(tree: DOMTree<T>) -> Self {
+        Compressed::new(tree.to_string())
+    }
+}
+
+impl Display for Compressed {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.html)
+    }
+}
+
+impl Debug for Compressed {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Compressed")
+            .field("html", &self.html)
+            .field("gzip_len", &self.deflate.len())
+            .field("brotli_len", &self.brotli.len())
+            .finish()
+    }
+}
+
+/// Writes a compressed response body into a writer.
+pub struct Encoder<W: Write> {
+    out: W,
+    state: State,
+}
+
+enum State {
+    Gzip {
+        compress: Compress,
+        crc: Crc,
+        buffer: Vec<u8>,
+    },
+    Brotli {
+        catli: BroCatli,
+        pending: String,
+        // The last byte written by `catli` is held back, since it may have
+        // to change when the next stream is appended.
+        buffer: Vec<u8>,
+    },
+}
+
+impl<W: Write> Encoder<W> {
+    /// Start a response body with the given encoding.
+    pub fn new(mut out: W, encoding: Encoding) -> io::Result<Self> {
+        let state = match encoding {
+            Encoding::Gzip => {
+                out.write_all(&GZIP_HEADER)?;
+                State::Gzip {
+                    compress: Compress::new(Compression::default(), false),
+                    crc: Crc::new(),
+                    buffer: Vec::new(),
+                }
+            }
+            Encoding::Brotli => State::Brotli {
+                catli: BroCatli::new(),
+                pending: String::new(),
+                buffer: Vec::new(),
+            },
+        };
+        Ok(Encoder { out, state })
+    }
+
+    /// Compress and write some HTML.
+    pub fn write_str(&mut self, html: &str) -> io::Result<()> {
+        match &mut self.state {
+            State::Gzip {
+                compress,
+                crc,
+                buffer,
+            } => {
+                crc.update(html.as_bytes());
+                deflate_into(compress, html.as_bytes(), FlushCompress::None, buffer);
+                self.out.write_all(buffer)?;
+                buffer.clear();
+            }
+            State::Brotli { pending, .. } => pending.push_str(html),
+        }
+        Ok(())
+    }
+
+    /// Write a fragment which was compressed ahead of time.
+    pub fn write_compressed(&mut self, fragment: &Compressed) -> io::Result<()> {
+        match &mut self.state {
+            State::Gzip {
+                compress,
+                crc,
+                buffer,
+            } => {
+                // A full flush stops what's written after the fragment from
+                // referring back to what was written before it.
+                deflate_into(compress, &[], FlushCompress::Full, buffer);
+                self.out.write_all(buffer)?;
+                buffer.clear();
+                self.out.write_all(&fragment.deflate)?;
+                crc.combine(&fragment.crc);
+            }
+            State::Brotli {
+                catli,
+                pending,
+                buffer,
+            } => {
+                if !pending.is_empty() {
+                    let stream = brotli_stream(pending.as_bytes(), BROTLI_QUALITY);
+                    pending.clear();
+                    concatenate(catli, &stream, buffer, &mut self.out)?;
+                }
+                concatenate(catli, &fragment.brotli, buffer, &mut self.out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write out everything compressed so far, so the client can decompress
+    /// it without waiting for the rest, and flush the writer.
+    pub fn flush(&mut self) -> io::Result<()> {
+        match &mut self.state {
+            State::Gzip {
+                compress, buffer, ..
+            } => {
+                deflate_into(compress, &[], FlushCompress::Sync, buffer);
+                self.out.write_all(buffer)?;
+                buffer.clear();
+            }
+            State::Brotli {
+                catli,
+                pending,
+                buffer,
+            } => {
+                if !pending.is_empty() {
+                    let stream = brotli_stream(pending.as_bytes(), BROTLI_QUALITY);
+                    pending.clear();
+                    concatenate(catli, &stream, buffer, &mut self.out)?;
+                }
+            }
+        }
+        self.out.flush()
+    }
+
+    /// Finish the response body, and return the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        match &mut self.state {
+            State::Gzip {
+                compress,
+                crc,
+                buffer,
+            } => {
+                deflate_into(compress, &[], FlushCompress::Finish, buffer);
+                buffer.extend_from_slice(&crc.sum().to_le_bytes());
+                buffer.extend_from_slice(&crc.amount().to_le_bytes());
+                self.out.write_all(buffer)?;
+            }
+            State::Brotli {
+                catli,
+                pending,
+                buffer,
+            } => {
+                if !pending.is_empty() {
+                    let stream = brotli_stream(pending.as_bytes(), BROTLI_QUALITY);
+                    concatenate(catli, &stream, buffer, &mut self.out)?;
+                }
+                let mut offset = buffer.len();
+                loop {
+                    buffer.resize(offset + 64, 0);
+                    match catli.finish(buffer, &mut offset) {
+                        BroCatliResult::Success => break,
+                        BroCatliResult::NeedsMoreOutput => {}
+                        result => return Err(brotli_error(result)),
+                    }
+                }
+                self.out.write_all(&buffer[..offset])?;
+            }
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+}
+
+impl<W: Write> Debug for Encoder<W> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let encoding = match self.state {
+            State::Gzip { .. } => Encoding::Gzip,
+            State::Brotli { .. } => Encoding::Brotli,
+        };
+        f.debug_struct("Encoder")
+            .field("encoding", &encoding)
+            .finish()
+    }
+}
+
+/// Compress `input` as raw deflate data, without a header, onto the end of
+/// `out`.
+fn deflate_into(
+    compress: &mut Compress,
+    mut input: &[u8],
+    flush: FlushCompress,
+    out: &mut Vec<u8>,
+) {
+    loop {
+        out.reserve(input.len() / 2 + 64);
+        let before = compress.total_in();
+        let status = compress
+            .compress_vec(input, out, flush)
+            .expect("deflate can't fail on valid input");
+        input = &input[(compress.total_in() - before) as usize..];
+        if status == Status::StreamEnd || (input.is_empty() && out.len() < out.capacity()) {
+            return;
+        }
+    }
+}
+
+/// Compress `input` as a brotli stream which can be concatenated with
+/// others.
+fn brotli_stream(mut input: &[u8], quality: i32) -> Vec<u8> {
+    let params = BrotliEncoderParams {
+        quality,
+        catable: true,
+        use_dictionary: false,
+        ..BrotliEncoderParams::default()
+    };
+    let mut out = Vec::new();
+    brotli::BrotliCompress(&mut input, &mut out, &params).expect("writing to a Vec can't fail");
+    out
+}
+
+/// Append a brotli stream to the output, writing everything but the last
+/// byte, which is kept at the start of `buffer`.
+fn concatenate<W: Write>(
+    catli: &mut BroCatli,
+    stream: &[u8],
+    buffer: &mut Vec<u8>,
+    out: &mut W,
+) -> io::Result<()> {
+    catli.new_brotli_file();
+    let mut read = 0;
+    let mut offset = buffer.len();
+    loop {
+        buffer.resize(offset + stream.len() + 64, 0);
+        match catli.stream(stream, &mut read, buffer, &mut offset) {
+            BroCatliResult::NeedsMoreInput => break,
+            BroCatliResult::NeedsMoreOutput => {}
+            result => return Err(brotli_error(result)),
+        }
+    }
+    buffer.truncate(offset);
+    if let Some(last) = buffer.pop() {
+        out.write_all(buffer)?;
+        buffer.clear();
+        buffer.push(last);
+    }
+    Ok(())
+}
+
+fn brotli_error(result: BroCatliResult) -> io::Error {
+    io::Error::other(format!("can't concatenate brotli streams: {:?}", result))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn decompress(encoding: Encoding, body: &[u8]) -> String {
+        let mut html = String::new();
+        match encoding {
+            Encoding::Gzip => flate2::read::GzDecoder::new(body).read_to_string(&mut html),
+            Encoding::Brotli => brotli::Decompressor::new(body, 4096).read_to_string(&mut html),
+        }
+        .unwrap();
+        html
+    }
+
+    #[test]
+    fn test_encoder() {
+        let nav = Compressed::new("<nav><a href=\"/\">Home</a><a href=\"/cats\">Cats</a></nav>");
+        let footer = Compressed::new("<footer>Home of the cats</footer>");
+        for &encoding in &[Encoding::Gzip, Encoding::Brotli] {
+            let mut body = Encoder::new(Vec::new(), encoding).unwrap();
+            body.write_str("<body>").unwrap();
+            body.write_compressed(&nav).unwrap();
+            body.write_str("<main>Cats at home, cats at home</main>")
+                .unwrap();
+            body.flush().unwrap();
+            body.write_compressed(&footer).unwrap();
+            body.write_compressed(&nav).unwrap();
+            body.write_str("</body>").unwrap();
+            let body = body.finish().unwrap();
+            assert_eq!(
+                format!(
+                    "<body>{}<main>Cats at home, cats at home</main>{}{}</body>",
+                    nav, footer, nav
+                ),
+                decompress(encoding, &body),
+                "{:?}",
+                encoding
+            );
+
+            let empty = Encoder::new(Vec::new(), encoding)
+                .unwrap()
+                .finish()
+                .unwrap();
+            assert_eq!("", decompress(encoding, &empty), "{:?}", encoding);
+        }
+    }
+
+    #[test]
+    fn test_negotiate() {
+        assert_eq!(
+            Some(Encoding::Brotli),
+            Encoding::negotiate("gzip, deflate, br")
+        );
+        assert_eq!(
+            Some(Encoding::Gzip),
+            Encoding::negotiate("gzip;q=1.0, br;q=0")
+        );
+        assert_eq!(Some(Encoding::Gzip), Encoding::negotiate("*;q=0.5, br;q=0"));
+        assert_eq!(None, Encoding::negotiate("deflate, identity"));
+        assert_eq!(None, Encoding::negotiate(""));
+    }
+}
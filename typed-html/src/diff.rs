@@ -0,0 +1,464 @@
+//! Diffing DOM trees into patches.
+//!
+//! [`diff()`][diff] compares the [`VNode`][VNode]s of two renders of a tree
+//! and returns the list of [`Patch`][Patch]es which turn the old one into the
+//! new one. Patches only contain strings and numbers, so a server can keep
+//! the tree it last sent to a client and push just the changes, over a
+//! WebSocket or server sent events, when something happens.
+//!
+//! With the `serde` feature flag enabled, patches implement `Serialize` and
+//! `Deserialize`, so they can be sent as JSON or any binary format serde
+//! supports.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::{html, text};
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::diff::{diff, Patch};
+//!
+//! # fn main() {
+//! let render = |count: usize| -> DOMTree<String> {
+//!     html!(<p class="count">{ text!("{} clicks", count) }</p>)
+//! };
+//! let mut old = render(1);
+//! let mut new = render(2);
+//! assert_eq!(
+//!     vec![Patch::SetText {
+//!         path: vec![0],
+//!         text: "2 clicks".to_string(),
+//!     }],
+//!     diff(&old.vnode(), &new.vnode())
+//! );
+//! # }
+//! ```
+//!
+//! # Applying Patches
+//!
+//! Patches are applied in order, and each one refers to the tree as the
+//! patches before it have left it. A node is found by its path: the index of
+//! each element on the way down from the root among its parent's child
+//! nodes, with an empty path for the root itself.
+//!
+//! Children are matched up by position, except for elements with an `id`,
+//! which are matched by it and moved into place if they've been reordered.
+//!
+//! In the browser, the `stdweb` output's [`Stdweb::patch()`][patch] applies
+//! patches to the live document.
+//!
+//! [diff]: fn.diff.html
+//! [VNode]: ../dom/enum.VNode.html
+//! [Patch]: enum.Patch.html
+//! [patch]: ../output/stdweb/struct.Stdweb.html#method.patch
+
+use std::collections::HashMap;
+use std::ptr;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::dom::{VElement, VNode};
+use crate::OutputType;
+
+/// A change to a tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Patch {
+    /// Insert a node into the element at `parent`, before the child which is
+    /// currently at `index`, or at the end if there are only `index`
+    /// children.
+    Insert {
+        parent: Vec<usize>,
+        index: usize,
+        node: PatchNode,
+    },
+    /// Replace the node at `path` with a new one.
+    Replace { path: Vec<usize>, node: PatchNode },
+    /// Remove the node at `path`.
+    Remove { path: Vec<usize> },
+    /// Move the child at index `from` of the element at `parent` to index
+    /// `to`, which is always lower.
+    Move {
+        parent: Vec<usize>,
+        from: usize,
+        to: usize,
+    },
+    /// Set an attribute on the element at `path`.
+    SetAttribute {
+        path: Vec<usize>,
+        name: String,
+        value: String,
+    },
+    /// Remove an attribute from the element at `path`.
+    RemoveAttribute { path: Vec<usize>, name: String },
+    /// Replace the contents of the text or comment node at `path`.
+    SetText { path: Vec<usize>, text: String },
+}
+
+/// A new node in a [`Patch`][Patch].
+///
+/// A [portal][Portal] holds an element which only exists in the browser, so
+/// it's sent as an empty text node.
+///
+/// [Patch]: enum.Patch.html
+/// [Portal]: ../dom/enum.VNode.html#variant.Portal
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PatchNode {
+    /// A text node.
+    Text(String),
+    /// Unescaped HTML, from an [`unsafe_text!`][unsafe_text] node.
+    ///
+    /// [unsafe_text]: ../macro.unsafe_text.html
+    Html(String),
+    /// A comment.
+    Comment(String),
+    /// An element.
+    Element {
+        name: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<PatchNode>,
+    },
+}
+
+impl<'a, T: OutputType> From<&VNode<'a, T>> for PatchNode {
+    fn from(node: &VNode<'a, T>) -> Self {
+        match node {
+            VNode::Text(text) => PatchNode::Text(text.to_string()),
+            VNode::UnsafeText(html) => PatchNode::Html(html.to_string()),
+            VNode::Comment(text) => PatchNode::Comment(text.to_string()),
+            VNode::Element(element) => PatchNode::Element {
+                name: element.name.to_string(),
+                attributes: element
+                    .attributes
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.clone()))
+                    .collect(),
+                children: element.children.iter().map(PatchNode::from).collect(),
+            },
+            VNode::Portal(_) => PatchNode::Text(String::new()),
+        }
+    }
+}
+
+/// Compute the patches which turn the `old` tree into the `new` one.
+///
+/// Event handlers aren't compared.
+pub fn diff<T: OutputType>(old: &VNode<'_, T>, new: &VNode<'_, T>) -> Vec<Patch> {
+    let mut patches = Vec::new();
+    diff_node(&mut patches, &mut Vec::new(), old, new);
+    patches
+}
+
+fn key<'a, T: OutputType>(node: &'a VNode<'_, T>) -> Option<&'a str> {
+    match node {
+        VNode::Element(element) => element_key(element),
+        _ => None,
+    }
+}
+
+/// The `id` of an element, which children are matched up by.
+fn element_key<'a, T: OutputType>(element: &'a VElement<'_, T>) -> Option<&'a str> {
+    attribute(element, "id")
+}
+
+fn attribute<'a, T: OutputType>(element: &'a VElement<'_, T>, name: &str) -> Option<&'a str> {
+    element
+        .attributes
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Whether `new` can be made from `old` by patching it rather than replacing
+/// it.
+///
+/// A customised built-in element's `is` attribute only takes effect when the
+/// element is created, so elements with different ones are replaced.
+fn same_kind<T: OutputType>(old: &VNode<'_, T>, new: &VNode<'_, T>) -> bool {
+    match (old, new) {
+        (VNode::Text(_), VNode::Text(_)) => true,
+        // Memoized subtrees from the same cache entry are the same string.
+        (VNode::UnsafeText(old), VNode::UnsafeText(new)) => ptr::eq(*old, *new) || old == new,
+        (VNode::Comment(_), VNode::Comment(_)) => true,
+        (VNode::Element(old), VNode::Element(new)) => same_element_kind(old, new),
+        (VNode::Portal(_), VNode::Portal(_)) => true,
+        _ => false,
+    }
+}
+
+/// Whether the element `new` can be made from `old` by patching it.
+fn same_element_kind<T: OutputType>(old: &VElement<'_, T>, new: &VElement<'_, T>) -> bool {
+    old.name == new.name && attribute(old, "is") == attribute(new, "is")
+}
+
+/// Whether the element `new` is the same element as `old`, to be updated in
+/// place rather than replaced: the same kind, with the same `id`.
+#[cfg(any(test, feature = "stdweb"))]
+pub(crate) fn same_element<T: OutputType>(old: &VElement<'_, T>, new: &VElement<'_, T>) -> bool {
+    same_element_kind(old, new) && element_key(old) == element_key(new)
+}
+
+/// A change to an element's attributes: `Some` value to set it, or `None`
+/// to remove it.
+pub(crate) type AttributeChange<'a> = (&'static str, Option<&'a str>);
+
+/// The attributes to remove from the element `old` and set on it to turn
+/// it into `new`, with the removals first.
+pub(crate) fn attribute_changes<'a, T: OutputType>(
+    old: &VElement<'_, T>,
+    new: &'a VElement<'_, T>,
+) -> Vec<AttributeChange<'a>> {
+    let mut changes = Vec::new();
+    for (name, _) in &old.attributes {
+        if !new.attributes.iter().any(|(new_name, _)| new_name == name) {
+            changes.push((*name, None));
+        }
+    }
+    for (name, value) in &new.attributes {
+        if attribute(old, name) != Some(value.as_str()) {
+            changes.push((*name, Some(value.as_str())));
+        }
+    }
+    changes
+}
+
+fn diff_node<T: OutputType>(
+    patches: &mut Vec<Patch>,
+    path: &mut Vec<usize>,
+    old: &VNode<'_, T>,
+    new: &VNode<'_, T>,
+) {
+    if !same_kind(old, new) || key(old) != key(new) {
+        patches.push(Patch::Replace {
+            path: path.clone(),
+            node: new.into(),
+        });
+        return;
+    }
+    match (old, new) {
+        (VNode::Text(old), VNode::Text(new)) | (VNode::Comment(old), VNode::Comment(new))
+            if old != new =>
+        {
+            patches.push(Patch::SetText {
+                path: path.clone(),
+                text: new.to_string(),
+            })
+        }
+        (VNode::Element(old), VNode::Element(new)) => diff_element(patches, path, old, new),
+        _ => {}
+    }
+}
+
+fn diff_element<T: OutputType>(
+    patches: &mut Vec<Patch>,
+    path: &mut Vec<usize>,
+    old: &VElement<'_, T>,
+    new: &VElement<'_, T>,
+) {
+    for (name, value) in attribute_changes(old, new) {
+        patches.push(match value {
+            Some(value) => Patch::SetAttribute {
+                path: path.clone(),
+                name: name.to_string(),
+                value: value.to_string(),
+            },
+            None => Patch::RemoveAttribute {
+                path: path.clone(),
+                name: name.to_string(),
+            },
+        });
+    }
+    diff_children(patches, path, &old.children, &new.children);
+}
+
+fn diff_children<T: OutputType>(
+    patches: &mut Vec<Patch>,
+    path: &mut Vec<usize>,
+    old: &[VNode<'_, T>],
+    new: &[VNode<'_, T>],
+) {
+    let new_keys: HashMap<&str, usize> = new
+        .iter()
+        .enumerate()
+        .filter_map(|(index, node)| key(node).map(|key| (key, index)))
+        .collect();
+    // The old children as they'll be after the patches so far, with `None`
+    // for the ones which have been inserted.
+    let mut current: Vec<Option<&VNode<'_, T>>> = old.iter().map(Some).collect();
+
+    for (index, node) in new.iter().enumerate() {
+        if let Some(node_key) = key(node) {
+            let found = current
+                .iter()
+                .skip(index)
+                .position(|old| old.and_then(key) == Some(node_key));
+            if let Some(offset) = found.filter(|offset| *offset > 0) {
+                let moved = current.remove(index + offset);
+                current.insert(index, moved);
+                patches.push(Patch::Move {
+                    parent: path.clone(),
+                    from: index + offset,
+                    to: index,
+                });
+            }
+        }
+        // Patch the child in this position if it matches up, and keep it if
+        // it's an element which is going to be moved later on.
+        let keep = match current.get(index).cloned().flatten() {
+            Some(old) => match key(old) {
+                Some(old_key) if key(node) != Some(old_key) => {
+                    new_keys.get(old_key).is_some_and(|&at| at > index)
+                }
+                _ => {
+                    path.push(index);
+                    diff_node(patches, path, old, node);
+                    path.pop();
+                    continue;
+                }
+            },
+            None => false,
+        };
+        if keep || index >= current.len() {
+            current.insert(index, None);
+            patches.push(Patch::Insert {
+                parent: path.clone(),
+                index,
+                node: node.into(),
+            });
+        } else {
+            current[index] = None;
+            path.push(index);
+            patches.push(Patch::Replace {
+                path: path.clone(),
+                node: node.into(),
+            });
+            path.pop();
+        }
+    }
+
+    for index in (new.len()..current.len()).rev() {
+        path.push(index);
+        patches.push(Patch::Remove { path: path.clone() });
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    fn list(items: &[&str]) -> DOMTree<String> {
+        html!(
+            <ul>
+                { items.iter().map(|item| html!(<li id={ *item }>{ crate::text!("{}", item) }</li>)) }
+            </ul>
+        )
+    }
+
+    #[test]
+    fn test_diff_attributes_and_text() {
+        let mut old: DOMTree<String> = html!(<p class="a" title="x">"Hello"</p>);
+        let mut new: DOMTree<String> = html!(<p class="b" lang="en">"Goodbye"</p>);
+        assert_eq!(
+            vec![
+                Patch::RemoveAttribute {
+                    path: vec![],
+                    name: "title".to_string(),
+                },
+                Patch::SetAttribute {
+                    path: vec![],
+                    name: "class".to_string(),
+                    value: "b".to_string(),
+                },
+                Patch::SetAttribute {
+                    path: vec![],
+                    name: "lang".to_string(),
+                    value: "en".to_string(),
+                },
+                Patch::SetText {
+                    path: vec![0],
+                    text: "Goodbye".to_string(),
+                },
+            ],
+            diff(&old.vnode(), &new.vnode())
+        );
+    }
+
+    #[test]
+    fn test_diff_keyed_children() {
+        let mut old = list(&["a", "b", "c"]);
+        let mut new = list(&["c", "a", "d"]);
+        assert_eq!(
+            vec![
+                Patch::Move {
+                    parent: vec![],
+                    from: 2,
+                    to: 0,
+                },
+                Patch::Replace {
+                    path: vec![2],
+                    node: PatchNode::Element {
+                        name: "li".to_string(),
+                        attributes: vec![("id".to_string(), "d".to_string())],
+                        children: vec![PatchNode::Text("d".to_string())],
+                    },
+                },
+            ],
+            diff(&old.vnode(), &new.vnode())
+        );
+        assert!(diff(&new.vnode(), &list(&["c", "a", "d"]).vnode()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_customised_built_in() {
+        let mut old: DOMTree<String> = html!(<button is="fancy-button">"Go"</button>);
+        let mut new: DOMTree<String> = html!(<button is="plain-button">"Go"</button>);
+        assert_eq!(
+            vec![Patch::Replace {
+                path: vec![],
+                node: PatchNode::Element {
+                    name: "button".to_string(),
+                    attributes: vec![("is".to_string(), "plain-button".to_string())],
+                    children: vec![PatchNode::Text("Go".to_string())],
+                },
+            }],
+            diff(&old.vnode(), &new.vnode())
+        );
+    }
+
+    #[test]
+    fn test_attribute_changes() {
+        let mut old: DOMTree<String> = html!(<p class="a" title="x" lang="en">"Hi"</p>);
+        let mut new: DOMTree<String> = html!(<p class="b" lang="en" id="hi">"Hi"</p>);
+        let (old, new) = (old.vnode(), new.vnode());
+        match (&old, &new) {
+            (VNode::Element(old), VNode::Element(new)) => {
+                assert!(same_element_kind(old, new));
+                assert!(!same_element(old, new));
+                assert_eq!(
+                    vec![("title", None), ("class", Some("b")), ("id", Some("hi"))],
+                    attribute_changes(old, new)
+                );
+                assert!(attribute_changes(new, new).is_empty());
+            }
+            _ => panic!("expected elements"),
+        }
+    }
+
+    #[cfg(feature = "json_ld")]
+    #[test]
+    fn test_patch_json() {
+        let patch = Patch::SetText {
+            path: vec![1, 0],
+            text: "Hi".to_string(),
+        };
+        let json = serde_json::to_string(&patch).unwrap();
+        assert_eq!("{\"set_text\":{\"path\":[1,0],\"text\":\"Hi\"}}", json);
+        assert_eq!(patch, serde_json::from_str(&json).unwrap());
+    }
+}
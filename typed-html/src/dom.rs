@@ -1,11 +1,17 @@
 //! DOM and virtual DOM types.
 
-use std::fmt::Display;
+use std::fmt::{self, Debug, Display};
 use std::marker::PhantomData;
 
 use crate::OutputType;
-use crate::elements::{FlowContent, PhrasingContent};
-use htmlescape::encode_minimal;
+use crate::elements::{
+    DescriptionListContent, FlowContent, MetadataContent, PhrasingContent, ScriptContent,
+    SelectContent, SvgContent, TableColumnContent, TableContent,
+};
+use crate::escape::{Canonical, Escape, Html};
+use crate::selector::{Selection, Selector, SelectorError};
+use crate::transform::Transform;
+use crate::validate::ContentModel;
 
 /// A boxed DOM tree, as returned from the `html!` macro.
 ///
@@ -43,7 +49,14 @@ pub type DOMTree<T> = Box<Node<T>>;
 pub enum VNode<'a, T: OutputType + 'a> {
     Text(&'a str),
     UnsafeText(&'a str),
+    /// A comment, with the text between its `<!--` and `-->`.
+    Comment(&'a str),
     Element(VElement<'a, T>),
+    /// An existing DOM element which is inserted into the tree as it is,
+    /// like a [`Portal`][Portal] for the stdweb output.
+    ///
+    /// [Portal]: ../output/stdweb/struct.Portal.html
+    Portal(&'a T::EventTarget),
 }
 
 /// An untyped representation of an HTML element.
@@ -61,16 +74,112 @@ pub struct VElement<'a, T: OutputType + 'a> {
 ///
 /// It implements [`Display`][Display] for rendering to strings, and the
 /// [`vnode()`][vnode] method can be used to render a virtual DOM structure.
+/// To render a string with an escaping policy other than the standard one, use
+/// an [`escape::Serializer`][Serializer].
+///
+/// It also implements [`Debug`][Debug], which prints an outline of the tree
+/// with the attributes of each element. Use the alternate form (`{:#?}`) to
+/// get it indented.
 ///
+/// [Debug]: https://doc.rust-lang.org/std/fmt/trait.Debug.html
 /// [Display]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+/// [Serializer]: ../escape/struct.Serializer.html
 /// [TextNode]: struct.TextNode.html
 /// [elements]: ../elements/index.html
 /// [vnode]: #tymethod.vnode
-pub trait Node<T: OutputType>: Display {
+pub trait Node<T: OutputType>: Display + Debug {
     /// Render the node into a [`VNode`][VNode] tree.
     ///
     /// [VNode]: enum.VNode.html
     fn vnode(&mut self) -> VNode<T>;
+
+    /// Apply a [`Transform`][Transform] to this node and its children.
+    ///
+    /// Returns `false` if the transform asked for this node to be removed
+    /// from its parent. The root of a tree can't be removed, so you can
+    /// ignore this value when calling `apply` yourself.
+    ///
+    /// [Transform]: ../transform/trait.Transform.html
+    fn apply(&mut self, transform: &mut dyn Transform) -> bool;
+
+    /// Render the node as HTML, escaping text and attribute values with the
+    /// given [`Escape`][Escape] policy.
+    ///
+    /// [Escape]: ../escape/trait.Escape.html
+    fn write_html(&self, f: &mut dyn fmt::Write, escape: &dyn Escape) -> fmt::Result;
+
+    /// Walk this node and its children with a [`Visit`][Visit], without
+    /// changing them.
+    ///
+    /// Elements and text are visited in document order. Nodes which don't
+    /// implement this, like comments, are skipped.
+    ///
+    /// [Visit]: trait.Visit.html
+    fn visit(&self, _visitor: &mut dyn Visit) {}
+
+    /// Render the node to a string in a canonical form, which is meant to
+    /// stay byte for byte the same across versions of typed-html.
+    ///
+    /// Attributes are sorted by name, with event handlers after the others,
+    /// values are always double quoted with only `&`, `<`, `>` and quotes
+    /// escaped, and runs of whitespace in text are collapsed to a single
+    /// space. Unescaped text is written as it is.
+    ///
+    /// This is for comparing trees, like in cache keys and tests, not for
+    /// display: collapsing whitespace changes the content of `<pre>`
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use typed_html::html;
+    /// # use typed_html::dom::DOMTree;
+    /// # fn main() {
+    /// let doc: DOMTree<String> = html!(
+    ///     <p title="Hi!" data-id="1" class="x">"Hello,   Kitty"</p>
+    /// );
+    /// assert_eq!(
+    ///     "<p class=\"x\" data-id=\"1\" title=\"Hi!\">Hello, Kitty</p>",
+    ///     doc.to_canonical_string()
+    /// );
+    /// # }
+    /// ```
+    fn to_canonical_string(&self) -> String {
+        let mut out = String::new();
+        self.write_html(&mut out, &Canonical).unwrap();
+        out
+    }
+
+    /// Find the elements in this tree which match a CSS
+    /// [`Selector`][Selector], in document order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use typed_html::html;
+    /// # use typed_html::dom::DOMTree;
+    /// # fn main() {
+    /// let doc: DOMTree<String> = html!(
+    ///     <ul>
+    ///         <li class="item" title="Tea"><span class="price">"1.50"</span></li>
+    ///         <li class="item" title="Cake"><span class="price">"3.25"</span></li>
+    ///     </ul>
+    /// );
+    /// let items = doc.select("li.item").unwrap();
+    /// assert_eq!(vec!["Tea", "Cake"], items.attributes("title"));
+    ///
+    /// let prices = doc.select(".item > .price").unwrap();
+    /// assert_eq!(vec!["1.50", "3.25"], prices.texts());
+    /// let total: f64 = prices.parse_texts::<f64>().unwrap().iter().sum();
+    /// assert_eq!(4.75, total);
+    /// # }
+    /// ```
+    ///
+    /// [Selector]: ../selector/struct.Selector.html
+    fn select(&self, selector: &str) -> Result<Selection, SelectorError> {
+        let selector: Selector = selector.parse()?;
+        Ok(selector.select_node(self))
+    }
 }
 
 impl<T> IntoIterator for Box<dyn Node<T>>
@@ -95,8 +204,8 @@ pub trait Element<T: OutputType>: Node<T> {
     fn name() -> &'static str;
     /// Get a list of the attribute names for this element.
     ///
-    /// This includes only the typed attributes, not any `data-` attributes
-    /// defined on this particular element instance.
+    /// This includes only the typed attributes, not any vendor or `data-`
+    /// attributes defined on this particular element instance.
     ///
     /// This is probably not useful unless you're the `html!` macro.
     fn attribute_names() -> &'static [&'static str];
@@ -104,6 +213,11 @@ pub trait Element<T: OutputType>: Node<T> {
     ///
     /// This is probably not useful unless you're the `html!` macro.
     fn required_children() -> &'static [&'static str];
+    /// Get the element's content model, for checking trees at runtime with
+    /// a [`Validator`][Validator].
+    ///
+    /// [Validator]: ../validate/struct.Validator.html
+    fn content_model() -> &'static ContentModel;
     /// Get a list of the defined attribute pairs for this element.
     ///
     /// This will convert attribute values into strings and return a vector of
@@ -111,6 +225,320 @@ pub trait Element<T: OutputType>: Node<T> {
     fn attributes(&self) -> Vec<(&'static str, String)>;
 }
 
+/// Write an element's attributes, given its escaped typed attributes, with
+/// `None` for ones written without a value, its extra attributes and its
+/// `data-` attributes.
+#[doc(hidden)]
+pub fn write_attributes(
+    f: &mut dyn fmt::Write,
+    escape: &dyn Escape,
+    mut attributes: Vec<(&'static str, Option<String>)>,
+    extra: &[(&'static str, String)],
+    data: &[(&'static str, String)],
+) -> fmt::Result {
+    if escape.sort_attributes() {
+        let mut all: Vec<(String, Option<String>)> = attributes
+            .drain(..)
+            .map(|(name, value)| (name.to_string(), value))
+            .chain(
+                extra
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), Some(escape.attribute(value)))),
+            )
+            .chain(
+                data.iter()
+                    .map(|(key, value)| (format!("data-{}", key), Some(escape.attribute(value)))),
+            )
+            .collect();
+        all.sort();
+        for (name, value) in all {
+            write_attribute(f, &name, value)?;
+        }
+        return Ok(());
+    }
+    for (name, value) in attributes {
+        write_attribute(f, name, value)?;
+    }
+    for (name, value) in extra {
+        write!(f, " {}=\"{}\"", name, escape.attribute(value))?;
+    }
+    for (key, value) in data {
+        write!(f, " data-{}=\"{}\"", key, escape.attribute(value))?;
+    }
+    Ok(())
+}
+
+fn write_attribute(f: &mut dyn fmt::Write, name: &str, value: Option<String>) -> fmt::Result {
+    match value {
+        Some(value) => write!(f, " {}=\"{}\"", name, value),
+        None => write!(f, " {}", name),
+    }
+}
+
+// The code `declare_elements!` generates for each element only gathers up
+// the element's parts and hands them to the functions below, so the work of
+// rendering is compiled once, rather than once for every element and output
+// type. Typed attributes are passed as a table of names, shared by every
+// instance of the element, alongside their values as `&dyn Display`.
+
+/// How a typed attribute's value is written.
+#[doc(hidden)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AttributeKind {
+    /// Escaped as an attribute value, and left out when it's empty.
+    Text,
+    /// Escaped as a URL, and left out when it's empty.
+    Url,
+    /// Escaped as an attribute value, and written without one when it's
+    /// empty.
+    BareWhenEmpty,
+}
+
+/// How an element's tag is closed.
+#[doc(hidden)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Closing {
+    /// With a closing tag, after the element's children.
+    Tag,
+    /// As `/>`.
+    Void,
+    /// As ` />`.
+    SpacedVoid,
+}
+
+/// The parts of an element which are needed to render it as HTML.
+#[doc(hidden)]
+pub struct ElementHtml<'a> {
+    pub name: &'static str,
+    pub attribute_names: &'static [&'static str],
+    pub attribute_kinds: &'static [AttributeKind],
+    pub attribute_values: &'a [Option<&'a dyn Display>],
+    pub extra_attributes: &'a [(&'static str, String)],
+    pub data_attributes: &'a [(&'static str, String)],
+    pub events: &'a dyn Display,
+    pub source: Option<&'static Source>,
+    pub closing: Closing,
+    pub children: &'a dyn Fn(&mut dyn fmt::Write, &dyn Escape) -> fmt::Result,
+}
+
+impl<'a> ElementHtml<'a> {
+    pub fn write(&self, f: &mut dyn fmt::Write, escape: &dyn Escape) -> fmt::Result {
+        let source = self.source.filter(|_| escape.annotate_sources());
+        if let Some(source) = source {
+            source.write_start(f)?;
+        }
+        f.write_str("<")?;
+        f.write_str(self.name)?;
+        let mut attributes = Vec::new();
+        let typed = self
+            .attribute_names
+            .iter()
+            .zip(self.attribute_kinds)
+            .zip(self.attribute_values);
+        for ((name, kind), value) in typed {
+            if let Some(value) = value {
+                let value = value.to_string();
+                if !value.is_empty() {
+                    let value = match kind {
+                        AttributeKind::Url => escape.url(&value),
+                        _ => escape.attribute(&value),
+                    };
+                    attributes.push((*name, Some(value)));
+                } else if *kind == AttributeKind::BareWhenEmpty {
+                    attributes.push((*name, None));
+                }
+            }
+        }
+        write_attributes(
+            f,
+            escape,
+            attributes,
+            self.extra_attributes,
+            self.data_attributes,
+        )?;
+        write!(f, "{}", self.events)?;
+        match self.closing {
+            Closing::Tag => {
+                f.write_str(">")?;
+                (self.children)(f, escape)?;
+                write!(f, "</{}>", self.name)?;
+            }
+            Closing::Void => f.write_str("/>")?,
+            Closing::SpacedVoid => f.write_str(" />")?,
+        }
+        match source {
+            Some(source) => source.write_end(f),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The typed attributes which are set, converted to strings.
+#[doc(hidden)]
+pub fn attribute_strings(
+    names: &'static [&'static str],
+    values: &[Option<&dyn Display>],
+) -> Vec<(&'static str, String)> {
+    names
+        .iter()
+        .zip(values)
+        .filter_map(|(name, value)| value.map(|value| (*name, value.to_string())))
+        .collect()
+}
+
+#[doc(hidden)]
+pub fn attribute_value<A: Display>(value: &Option<A>) -> Option<&dyn Display> {
+    value.as_ref().map(|value| value as &dyn Display)
+}
+
+#[doc(hidden)]
+pub fn parse_attribute<A>(slot: &mut Option<A>, value: &str) -> Result<(), String>
+where
+    A: std::str::FromStr,
+    A::Err: Display,
+{
+    *slot = Some(value.parse().map_err(|err: A::Err| err.to_string())?);
+    Ok(())
+}
+
+#[doc(hidden)]
+pub fn take_attribute<A: Display>(slot: &mut Option<A>) -> Option<String> {
+    slot.take().map(|value| value.to_string())
+}
+
+/// Check the name of an attribute given to `raw_attrs`, which is written
+/// out as it is, so it can't be empty or have a character which would end
+/// it early, like a space, `=` or `>`.
+///
+/// This is a `const fn` so that the `html!` macro can check literal names
+/// at compile time.
+#[doc(hidden)]
+pub const fn validate_raw_attribute_name(name: &str) -> Result<(), &'static str> {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() {
+        return Err("raw_attrs names cannot be empty");
+    }
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        // C1 control characters are U+0080 to U+009F, encoded as C2 80 to C2 9F.
+        let is_c1 = byte == 0xc2 && index + 1 < bytes.len() && bytes[index + 1] <= 0x9f;
+        if byte <= b' ' || byte == 0x7f || is_c1 {
+            return Err("raw_attrs names cannot contain whitespace or control characters");
+        }
+        if matches!(byte, b'"' | b'\'' | b'>' | b'/' | b'=') {
+            return Err("raw_attrs names cannot contain quotes, `>`, `/` or `=`");
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+/// Check the name of an attribute given to `raw_attrs` when the element is
+/// built, returning `None` if it isn't valid, so it's left out.
+#[doc(hidden)]
+pub fn raw_attribute_name(name: &'static str) -> Option<&'static str> {
+    validate_raw_attribute_name(name).ok().map(|()| name)
+}
+
+/// Where in the source code an element was created, which is recorded on
+/// the root element of each [`html!`][html] invocation.
+///
+/// A [`Serializer`][Serializer] with
+/// [`annotate_sources()`][annotate_sources] turned on writes it in comments
+/// around the element, so you can find out where some markup came from in
+/// the browser's inspector.
+///
+/// [html]: ../macro.html.html
+/// [Serializer]: ../escape/struct.Serializer.html
+/// [annotate_sources]: ../escape/struct.Serializer.html#method.annotate_sources
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Source {
+    /// The source file, as given by `file!()`.
+    pub file: &'static str,
+    /// The line of the macro invocation.
+    pub line: u32,
+    /// The column of the macro invocation.
+    pub column: u32,
+    /// The path of the module the macro was invoked in.
+    pub module: &'static str,
+}
+
+impl Source {
+    /// Write the comment before an annotated element.
+    #[doc(hidden)]
+    pub fn write_start(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        write!(f, "<!-- {} {} -->", self, self.module)
+    }
+
+    /// Write the comment after an annotated element.
+    #[doc(hidden)]
+    pub fn write_end(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        write!(f, "<!-- /{} -->", self)
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Keep a stray `--` in the path from ending the comment early.
+        write!(
+            f,
+            "{}:{}:{}",
+            self.file.replace("--", "-\u{2010}"),
+            self.line,
+            self.column
+        )
+    }
+}
+
+/// A dynamically typed view of an HTML element.
+///
+/// All [HTML elements][elements] implement this, which lets a
+/// [`Transform`][Transform] read and rewrite an element's attributes without
+/// knowing its type.
+///
+/// [elements]: ../elements/index.html
+/// [Transform]: ../transform/trait.Transform.html
+pub trait UntypedElement {
+    /// Get the name of the element.
+    fn name(&self) -> &'static str;
+    /// Get the value of an attribute rendered as a string, if it's set.
+    ///
+    /// This includes only the typed attributes, as listed by
+    /// [`Element::attribute_names()`][attribute_names].
+    ///
+    /// [attribute_names]: trait.Element.html#tymethod.attribute_names
+    fn attribute(&self, name: &str) -> Option<String>;
+    /// Set an attribute by parsing its value from a string.
+    ///
+    /// Returns `Err` if the element has no attribute by that name, or if the
+    /// value can't be parsed into the attribute's type.
+    fn set_attribute(&mut self, name: &str, value: &str) -> Result<(), String>;
+    /// Unset an attribute, returning its previous value rendered as a string.
+    fn remove_attribute(&mut self, name: &str) -> Option<String>;
+    /// Get the `data-` attributes of the element, keyed without the prefix.
+    fn data_attributes_mut(&mut self) -> &mut Vec<(&'static str, String)>;
+    /// Get the attributes of the element which aren't typed attributes or
+    /// `data-` attributes, like [vendor attributes][vendor], keyed by their
+    /// full names.
+    ///
+    /// [vendor]: ../vendor/index.html
+    fn extra_attributes_mut(&mut self) -> &mut Vec<(&'static str, String)>;
+}
+
+/// A read-only walk over a DOM tree, with [`Node::visit()`][visit].
+///
+/// [visit]: trait.Node.html#method.visit
+pub trait Visit {
+    /// Visit an element, with its attributes rendered as strings, before its
+    /// children.
+    fn enter_element(&mut self, name: &'static str, attributes: Vec<(&'static str, String)>);
+    /// Leave the element most recently entered, after its children.
+    fn leave_element(&mut self);
+    /// Visit a text node. Unsafe text is visited as it is.
+    fn text(&mut self, text: &str);
+}
+
 /// An HTML text node.
 pub struct TextNode<T: OutputType>(String, PhantomData<T>);
 
@@ -200,7 +628,13 @@ impl<T: OutputType> TextNode<T> {
 
 impl<T: OutputType> Display for TextNode<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
-        f.write_str(&encode_minimal(&self.0))
+        self.write_html(f, &Html)
+    }
+}
+
+impl<T: OutputType> Debug for TextNode<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        Debug::fmt(&self.0, f)
     }
 }
 
@@ -208,6 +642,18 @@ impl<T: OutputType> Node<T> for TextNode<T> {
     fn vnode(&'_ mut self) -> VNode<'_, T> {
         VNode::Text(&self.0)
     }
+
+    fn apply(&mut self, transform: &mut dyn Transform) -> bool {
+        transform.text(&mut self.0)
+    }
+
+    fn write_html(&self, f: &mut dyn fmt::Write, escape: &dyn Escape) -> fmt::Result {
+        f.write_str(&escape.text(&self.0))
+    }
+
+    fn visit(&self, visitor: &mut dyn Visit) {
+        visitor.text(&self.0)
+    }
 }
 
 impl<T: OutputType> IntoIterator for TextNode<T> {
@@ -230,6 +676,7 @@ impl<T: OutputType> IntoIterator for Box<TextNode<T>> {
 
 impl<T: OutputType> FlowContent<T> for TextNode<T> {}
 impl<T: OutputType> PhrasingContent<T> for TextNode<T> {}
+impl<T: OutputType> ScriptContent<T> for TextNode<T> {}
 
 impl<T: OutputType> UnsafeTextNode<T> {
     /// Construct a unsafe text node.
@@ -249,10 +696,28 @@ impl<T: OutputType> Display for UnsafeTextNode<T> {
     }
 }
 
+impl<T: OutputType> Debug for UnsafeTextNode<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        f.debug_tuple("UnsafeText").field(&self.0).finish()
+    }
+}
+
 impl<T: OutputType> Node<T> for UnsafeTextNode<T> {
     fn vnode(&'_ mut self) -> VNode<'_, T> {
         VNode::UnsafeText(&self.0)
     }
+
+    fn apply(&mut self, transform: &mut dyn Transform) -> bool {
+        transform.unsafe_text(&mut self.0)
+    }
+
+    fn write_html(&self, f: &mut dyn fmt::Write, _escape: &dyn Escape) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+
+    fn visit(&self, visitor: &mut dyn Visit) {
+        visitor.text(&self.0)
+    }
 }
 
 impl<T: OutputType> IntoIterator for UnsafeTextNode<T> {
@@ -275,3 +740,305 @@ impl<T: OutputType> IntoIterator for Box<UnsafeTextNode<T>> {
 
 impl<T: OutputType> FlowContent<T> for UnsafeTextNode<T> {}
 impl<T: OutputType> PhrasingContent<T> for UnsafeTextNode<T> {}
+impl<T: OutputType> ScriptContent<T> for UnsafeTextNode<T> {}
+impl<T: OutputType> SvgContent<T> for UnsafeTextNode<T> {}
+
+/// An HTML comment.
+///
+/// A `--` in the text is written as `-‐`, with a hyphen in place of the
+/// second `-`, so the text can't end the comment early.
+pub struct Comment<T: OutputType>(String, PhantomData<T>);
+
+/// Macro for creating comments.
+///
+/// Returns a boxed comment of type `Box<Comment>`.
+///
+/// # Examples
+///
+/// ```no_compile
+/// html!(
+///     <p>{ comment!("Generated on {}", date) }"Hello Joe!"</p>
+/// )
+/// ```
+#[macro_export]
+macro_rules! comment {
+    ($t:expr) => {
+        Box::new($crate::dom::Comment::new($t))
+    };
+    ($format:tt, $($tail:tt),*) => {
+        Box::new($crate::dom::Comment::new(format!($format, $($tail),*)))
+    };
+}
+
+impl<T: OutputType> Comment<T> {
+    /// Construct a comment.
+    pub fn new<S: Into<String>>(s: S) -> Self {
+        Comment(s.into(), PhantomData)
+    }
+}
+
+/// Keep the text of a comment from ending it early, or from running into
+/// the `<!--` and `-->` around it.
+fn comment_text(text: &str) -> String {
+    let mut text = text.replace("--", "-\u{2010}");
+    if text.starts_with('>') || text.starts_with("->") {
+        text.insert(0, ' ');
+    }
+    if text.ends_with("<!-") || text.ends_with('-') {
+        text.push(' ');
+    }
+    text
+}
+
+impl<T: OutputType> Display for Comment<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        self.write_html(f, &Html)
+    }
+}
+
+impl<T: OutputType> Debug for Comment<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        f.debug_tuple("Comment").field(&self.0).finish()
+    }
+}
+
+impl<T: OutputType> Node<T> for Comment<T> {
+    fn vnode(&'_ mut self) -> VNode<'_, T> {
+        VNode::Comment(&self.0)
+    }
+
+    fn apply(&mut self, transform: &mut dyn Transform) -> bool {
+        transform.comment(&mut self.0)
+    }
+
+    fn write_html(&self, f: &mut dyn fmt::Write, _escape: &dyn Escape) -> fmt::Result {
+        write!(f, "<!--{}-->", comment_text(&self.0))
+    }
+}
+
+impl<T: OutputType> IntoIterator for Box<Comment<T>> {
+    type Item = Box<Comment<T>>;
+    type IntoIter = std::vec::IntoIter<Box<Comment<T>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        vec![self].into_iter()
+    }
+}
+
+/// A downlevel-hidden conditional comment, like `<!--[if mso]>...<![endif]-->`,
+/// whose contents are only read by the clients which match its condition.
+///
+/// Every other client sees a comment, but the contents are typed trees, so
+/// markup for Outlook's HTML engine is checked like the rest of the
+/// document. They're rendered with the same escaping policy as the tree
+/// around them, and in the [`VNode`][VNode] tree the whole thing is a
+/// comment with the contents rendered with the standard one.
+///
+/// A comment inside a conditional comment would end it early, so don't put
+/// any in one.
+///
+/// [VNode]: enum.VNode.html
+pub struct ConditionalComment<T: OutputType> {
+    condition: String,
+    children: Vec<DOMTree<T>>,
+    rendered: String,
+}
+
+/// Macro for creating conditional comments.
+///
+/// Returns a boxed conditional comment of type `Box<ConditionalComment>`,
+/// with the condition and then the trees inside it.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::{conditional_comment, html};
+/// # use typed_html::dom::DOMTree;
+/// # fn main() {
+/// let doc: DOMTree<String> = html!(
+///     <div>
+///         { conditional_comment!("mso", html!(<table><tr><td>"Outlook"</td></tr></table>)) }
+///     </div>
+/// );
+/// assert_eq!(
+///     "<div><!--[if mso]><table><tr><td>Outlook</td></tr></table><![endif]--></div>",
+///     doc.to_string()
+/// );
+/// # }
+/// ```
+#[macro_export]
+macro_rules! conditional_comment {
+    ($condition:expr $(, $child:expr)* $(,)?) => {{
+        let comment = $crate::dom::ConditionalComment::new($condition);
+        $(let comment = comment.child($child);)*
+        Box::new(comment)
+    }};
+}
+
+impl<T: OutputType> ConditionalComment<T> {
+    /// Construct an empty conditional comment, with a condition like `mso`
+    /// or `gte mso 9`.
+    ///
+    /// Panics if the condition contains `<`, `>` or `]`, which would end it
+    /// early.
+    pub fn new<S: Into<String>>(condition: S) -> Self {
+        let condition = condition.into();
+        if condition.contains(['<', '>', ']']) {
+            panic!(
+                "typed_html::dom::ConditionalComment: {:?} is not a valid condition",
+                condition
+            );
+        }
+        ConditionalComment {
+            condition,
+            children: Vec::new(),
+            rendered: String::new(),
+        }
+    }
+
+    /// Add a tree to the end of the contents.
+    pub fn child(mut self, child: DOMTree<T>) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    fn write_contents(&self, f: &mut dyn fmt::Write, escape: &dyn Escape) -> fmt::Result {
+        write!(f, "[if {}]>", self.condition)?;
+        for child in &self.children {
+            child.write_html(f, escape)?;
+        }
+        f.write_str("<![endif]")
+    }
+}
+
+impl<T: OutputType> Display for ConditionalComment<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        self.write_html(f, &Html)
+    }
+}
+
+impl<T: OutputType> Debug for ConditionalComment<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        f.debug_struct("ConditionalComment")
+            .field("condition", &self.condition)
+            .field("children", &self.children)
+            .finish()
+    }
+}
+
+impl<T: OutputType> Node<T> for ConditionalComment<T> {
+    fn vnode(&'_ mut self) -> VNode<'_, T> {
+        let mut rendered = String::new();
+        self.write_contents(&mut rendered, &Html).unwrap();
+        self.rendered = rendered;
+        VNode::Comment(&self.rendered)
+    }
+
+    fn apply(&mut self, transform: &mut dyn Transform) -> bool {
+        if !transform.conditional_comment(&self.condition) {
+            return false;
+        }
+        self.children.retain_mut(|child| child.apply(transform));
+        true
+    }
+
+    fn write_html(&self, f: &mut dyn fmt::Write, escape: &dyn Escape) -> fmt::Result {
+        f.write_str("<!--")?;
+        self.write_contents(f, escape)?;
+        f.write_str("-->")
+    }
+}
+
+impl<T: OutputType> IntoIterator for Box<ConditionalComment<T>> {
+    type Item = Box<ConditionalComment<T>>;
+    type IntoIter = std::vec::IntoIter<Box<ConditionalComment<T>>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        vec![self].into_iter()
+    }
+}
+
+macro_rules! comment_content {
+    ($($trait:ident),*) => {
+        $(
+            impl<T: OutputType> $trait<T> for Comment<T> {}
+            impl<T: OutputType> $trait<T> for ConditionalComment<T> {}
+        )*
+    };
+}
+
+comment_content!(
+    MetadataContent,
+    FlowContent,
+    PhrasingContent,
+    DescriptionListContent,
+    SelectContent,
+    TableContent,
+    TableColumnContent
+);
+
+#[cfg(test)]
+mod tests {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    #[test]
+    fn test_debug_outline() {
+        let tree: DOMTree<String> = html!(
+            <div class="hello" data-id="1234">
+                <p>"Hello "<em>"Joe"</em></p>
+                <br/>
+                { unsafe_text!("<hr/>") }
+            </div>
+        );
+
+        assert_eq!(
+            "div { class: \"hello\", data-id: \"1234\", children: [p { children: \
+             [\"Hello \", em { children: [\"Joe\"] }] }, br, UnsafeText(\"<hr/>\")] }",
+            format!("{:?}", tree)
+        );
+        assert_eq!(
+            r#"p {
+    children: [
+        "Hello ",
+    ],
+}"#,
+            format!("{:#?}", html!(<p>"Hello "</p> : String))
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        use crate::diff::{diff, Patch};
+        use crate::escape::{Serializer, Strict};
+
+        let render = |version: u32| -> DOMTree<String> {
+            html!(
+                <html>
+                    <head>
+                        <title>"Newsletter"</title>
+                        { conditional_comment!("gte mso 9", html!(<style>"td { padding: 0 }"</style>)) }
+                    </head>
+                    <body>
+                        { comment!("version {} -->", version) }
+                        <p>"Hello"</p>
+                    </body>
+                </html>
+            )
+        };
+        let (mut old, mut new) = (render(1), render(2));
+        assert_eq!(
+            "<html><head><title>Newsletter</title>\
+             <!--[if gte mso 9]><style>td&#x20;&#x7B;&#x20;padding&#x3A;&#x20;0&#x20;&#x7D;</style><![endif]-->\
+             </head><body><!--version 1 -\u{2010}>--><p>Hello</p></body></html>",
+            Serializer::new(Strict).render(&*old)
+        );
+        assert_eq!(
+            vec![Patch::SetText {
+                path: vec![1, 0],
+                text: "version 2 -->".to_string(),
+            }],
+            diff(&old.vnode(), &new.vnode())
+        );
+    }
+}
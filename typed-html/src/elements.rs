@@ -1,10 +1,7 @@
 //! Types for all standard HTML5 elements.
 
-#![allow(non_camel_case_types)]
+#![allow(non_camel_case_types, non_snake_case)]
 
-use typed_html_macros::declare_elements;
-
-use crate::OutputType;
 use crate::dom::{Node, TextNode};
 use crate::types::*;
 
@@ -12,21 +9,97 @@ use crate::types::*;
 
 macro_rules! marker_trait {
     ($trait:ident) => {
-        marker_trait!($trait, Node);
+        crate::content_group!(pub $trait);
     };
 
     ($trait:ident, $parent:ident) => {
-        pub trait $trait<T: OutputType>: $parent<T> {}
+        crate::content_group!(pub $trait: $parent);
+    };
+}
 
-        impl<T> IntoIterator for Box<dyn $trait<T>> where T: OutputType {
-            type Item = Box<dyn $trait<T>>;
-            type IntoIter = std::vec::IntoIter<Box<dyn $trait<T>>>;
+/// Declare a content group, for elements declared with
+/// [`declare_elements!`][declare_elements] to belong to and to take as
+/// children.
+///
+/// A content group is a trait, which the elements declared `in` it
+/// implement, like the standard [`FlowContent`][FlowContent] and
+/// [`PhrasingContent`][PhrasingContent]. An element declared `with` the
+/// group only accepts those elements as children, which the `html!` macro
+/// checks when your crate is built:
+///
+/// ```
+/// # use typed_html::{content_group, declare_elements, html};
+/// # use typed_html::dom::DOMTree;
+/// use typed_html::elements::*;
+///
+/// content_group!(pub TabsContent);
+///
+/// declare_elements! {
+///     tabs in [FlowContent] with TabsContent;
+///     tab {
+///         label: String,
+///     } in [TabsContent] with FlowContent;
+///     tablink {
+///         href: String,
+///     } in [TabsContent] with PhrasingContent;
+/// }
+///
+/// # fn main() {
+/// let doc: DOMTree<String> = html!(
+///     <tabs>
+///         <tab label="Cats">"Meow"</tab>
+///         <tablink href="/dogs">"Dogs"</tablink>
+///     </tabs>
+/// );
+/// # }
+/// ```
+///
+/// With a `<p>` or some text inside the `<tabs>` above, the crate wouldn't
+/// build, because neither is in `TabsContent`.
+///
+/// A group can be a subset of another, given after a colon, like
+/// `PhrasingContent: FlowContent`, so every element in it is accepted where
+/// the other group is too.
+///
+/// [declare_elements]: macro.declare_elements.html
+/// [FlowContent]: elements/trait.FlowContent.html
+/// [PhrasingContent]: elements/trait.PhrasingContent.html
+#[macro_export]
+macro_rules! content_group {
+    (@declare [$($attr:tt)*] $vis:vis $group:ident [$($parent:tt)*]) => {
+        $($attr)*
+        $vis trait $group<T: $crate::OutputType>: $($parent)*<T> {}
+
+        impl<T> IntoIterator for Box<dyn $group<T>> where T: $crate::OutputType {
+            type Item = Box<dyn $group<T>>;
+            type IntoIter = std::vec::IntoIter<Box<dyn $group<T>>>;
 
             fn into_iter(self) -> Self::IntoIter {
                 vec![self].into_iter()
             }
         }
     };
+
+    (@model $group:ident $parent:expr) => {
+        impl $crate::validate::Group for dyn $group<std::string::String> {
+            const GROUP: $crate::validate::ContentGroup = $crate::validate::ContentGroup {
+                name: stringify!($group),
+                parent: $parent,
+            };
+        }
+    };
+
+    ($(#[$attr:meta])* $vis:vis $group:ident) => {
+        $crate::content_group!(@declare [$(#[$attr])*] $vis $group [$crate::dom::Node]);
+        $crate::content_group!(@model $group None);
+    };
+
+    ($(#[$attr:meta])* $vis:vis $group:ident : $parent:ident) => {
+        $crate::content_group!(@declare [$(#[$attr])*] $vis $group [$parent]);
+        $crate::content_group!(@model $group Some(
+            &<dyn $parent<std::string::String> as $crate::validate::Group>::GROUP
+        ));
+    };
 }
 
 marker_trait!(MetadataContent);
@@ -44,11 +117,71 @@ marker_trait!(DescriptionListContent);
 marker_trait!(HGroupContent);
 marker_trait!(MapContent);
 marker_trait!(MediaContent); // <audio> and <video>
+marker_trait!(PictureContent);
+marker_trait!(ScriptContent); // text or unescaped text inside <script>
 marker_trait!(SelectContent);
+marker_trait!(SvgContent);
 marker_trait!(TableContent);
 marker_trait!(TableColumnContent);
 
-declare_elements!{
+/// Declare new elements, for use in the [`html!`][html] macro alongside the
+/// standard ones.
+///
+/// Each declaration gives the element's name, then optionally its
+/// attributes and their types, the content groups it belongs to after `in`,
+/// and what it can contain after `with`:
+///
+/// ```
+/// # use typed_html::{declare_elements, html};
+/// # use typed_html::dom::DOMTree;
+/// use typed_html::elements::*;
+/// use typed_html::types::*;
+///
+/// declare_elements! {
+///     badge {
+///         count: usize,
+///     } in [FlowContent, PhrasingContent] with PhrasingContent;
+/// }
+///
+/// # fn main() {
+/// let doc: DOMTree<String> = html!(
+///     <p>
+///         <badge count="3">"Likes"</badge>
+///     </p>
+/// );
+/// assert_eq!(
+///     r#"<p><badge count="3">Likes</badge></p>"#,
+///     doc.to_string()
+/// );
+/// # }
+/// ```
+///
+/// The element's attributes, the content groups and the element types given
+/// as children are looked up where the macro is used, so import them first,
+/// usually with `use typed_html::elements::*` and `use typed_html::types::*`.
+/// Every element also gets the global attributes, like `id` and `class`.
+///
+/// What comes after `with` is either a content group, or the name of a
+/// single element, like `with tab` for an element which only holds `<tab>`s.
+/// To accept a few different elements, declare a content group for them
+/// with [`content_group!`][content_group]. Either way, the `html!` macro
+/// won't build anything else as a child of the element.
+///
+/// Elements are declared as types named after the element, so the `html!`
+/// macro finds them wherever they're in scope. They can't share a name with
+/// a standard element, and the `html!` macro can't build elements which have
+/// required children, like `<html>`'s `<head>` and `<body>`.
+///
+/// [html]: macro.html.html
+/// [content_group]: macro.content_group.html
+#[macro_export]
+macro_rules! declare_elements {
+    ($($tokens:tt)*) => {
+        $crate::__declare_elements! { $crate; $($tokens)* }
+    };
+}
+
+crate::__declare_elements!{
     html {
         xmlns: Uri,
     } with [head, body];
@@ -66,6 +199,7 @@ declare_elements!{
         href: Uri,
         hreflang: LanguageTag,
         media: String, // FIXME media query
+        referrerpolicy: ReferrerPolicy,
         rel: LinkType,
         sizes: String, // FIXME
         title: String, // FIXME
@@ -91,6 +225,7 @@ declare_elements!{
         href: Uri,
         hreflang: LanguageTag,
         ping: SpacedList<Uri>,
+        referrerpolicy: ReferrerPolicy,
         rel: SpacedList<LinkType>,
         target: Target,
         type: Mime,
@@ -125,12 +260,14 @@ declare_elements!{
         formnovalidate: Bool,
         formtarget: Target,
         name: Id,
+        popovertarget: Id,
+        popovertargetaction: PopoverTargetAction,
         type: ButtonType,
         value: String,
     } in [FlowContent, PhrasingContent, InteractiveContent, FormContent] with PhrasingContent;
     canvas {
-        height: usize,
-        width: usize,
+        height: Dimension,
+        width: Dimension,
     } in [FlowContent, PhrasingContent, EmbeddedContent] with FlowContent;
     cite in [FlowContent, PhrasingContent] with PhrasingContent;
     code in [FlowContent, PhrasingContent] with PhrasingContent;
@@ -146,14 +283,17 @@ declare_elements!{
         open: Bool,
     } in [FlowContent, SectioningContent, InteractiveContent] with [summary] FlowContent;
     dfn in [FlowContent, PhrasingContent] with PhrasingContent;
+    dialog {
+        open: Bool,
+    } in [FlowContent] with FlowContent;
     div in [FlowContent] with FlowContent;
     dl in [FlowContent] with DescriptionListContent;
     em in [FlowContent, PhrasingContent] with PhrasingContent;
     embed {
-        height: usize,
+        height: Dimension,
         src: Uri,
         type: Mime,
-        width: usize,
+        width: Dimension,
     } in [FlowContent, PhrasingContent, EmbeddedContent, InteractiveContent];
     // FIXME the legend attribute should be optional
     fieldset in [FlowContent, SectioningContent, FormContent] with [legend] FlowContent;
@@ -184,30 +324,33 @@ declare_elements!{
         allow: FeaturePolicy,
         allowfullscreen: Bool,
         allowpaymentrequest: Bool,
-        height: usize,
+        height: Dimension,
+        loading: Loading,
         name: Id,
         referrerpolicy: ReferrerPolicy,
         sandbox: SpacedSet<Sandbox>,
         src: Uri,
         srcdoc: Uri,
-        width: usize,
+        width: Dimension,
     } in [FlowContent, PhrasingContent, EmbeddedContent, InteractiveContent] with FlowContent;
     img {
         alt: String,
         crossorigin: CrossOrigin,
         decoding: ImageDecoding,
-        height: usize,
+        height: Dimension,
         ismap: Bool,
+        loading: Loading,
+        referrerpolicy: ReferrerPolicy,
         sizes: SpacedList<String>, // FIXME it's not really just a string
         src: Uri,
         srcset: String, // FIXME this is much more complicated
         usemap: String, // FIXME should be a fragment starting with '#'
-        width: usize,
-    } in [FlowContent, PhrasingContent, EmbeddedContent];
+        width: Dimension,
+    } in [FlowContent, PhrasingContent, EmbeddedContent, PictureContent];
     input {
         accept: String,
         alt: String,
-        autocomplete: String,
+        autocomplete: Autocomplete,
         autofocus: Bool,
         capture: String,
         checked: Bool,
@@ -218,7 +361,7 @@ declare_elements!{
         formmethod: FormDialogMethod,
         formnovalidate: Bool,
         formtarget: Target,
-        height: isize,
+        height: Dimension,
         list: Id,
         max: String,
         maxlength: usize,
@@ -228,6 +371,8 @@ declare_elements!{
         name: Id,
         pattern: String,
         placeholder: String,
+        popovertarget: Id,
+        popovertargetaction: PopoverTargetAction,
         readonly: Bool,
         required: Bool,
         size: usize,
@@ -237,7 +382,7 @@ declare_elements!{
         tabindex: usize,
         type: InputType,
         value: String,
-        width: isize,
+        width: Dimension,
     } in [FlowContent, FormContent, PhrasingContent];
     ins {
         cite: Uri,
@@ -254,6 +399,7 @@ declare_elements!{
     } in [FlowContent, PhrasingContent] with MapContent;
     mark in [FlowContent, PhrasingContent] with PhrasingContent;
     // TODO the <math> element
+    menu in [FlowContent] with li;
     meter {
         value: isize,
         min: isize,
@@ -268,12 +414,12 @@ declare_elements!{
     object {
         data: Uri,
         form: Id,
-        height: usize,
+        height: Dimension,
         name: Id,
         type: Mime,
         typemustmatch: Bool,
         usemap: String, // TODO should be a fragment starting with '#'
-        width: usize,
+        width: Dimension,
     } in [FlowContent, PhrasingContent, EmbeddedContent, InteractiveContent, FormContent] with param;
     ol {
         reversed: Bool,
@@ -285,7 +431,8 @@ declare_elements!{
         form: Id,
         name: Id,
     } in [FlowContent, PhrasingContent, FormContent] with PhrasingContent;
-    p in [FlowContent] with PhrasingContent;
+    p in [FlowContent, HGroupContent] with PhrasingContent;
+    picture in [FlowContent, PhrasingContent, EmbeddedContent] with PictureContent;
     pre in [FlowContent] with PhrasingContent;
     progress {
         max: f64,
@@ -294,6 +441,9 @@ declare_elements!{
     q {
         cite: Uri,
     } in [FlowContent, PhrasingContent] with PhrasingContent;
+    // FIXME rp and rt should only be allowed inside <ruby>
+    rp in [FlowContent, PhrasingContent] with PhrasingContent;
+    rt in [FlowContent, PhrasingContent] with PhrasingContent;
     ruby in [FlowContent, PhrasingContent] with PhrasingContent;
     s in [FlowContent, PhrasingContent] with PhrasingContent;
     samp in [FlowContent, PhrasingContent] with PhrasingContent;
@@ -304,13 +454,15 @@ declare_elements!{
         integrity: Integrity,
         nomodule: Bool,
         nonce: Nonce,
+        referrerpolicy: ReferrerPolicy,
         src: Uri,
         text: String,
-        type: String, // TODO could be an enum
-    } in [MetadataContent, FlowContent, PhrasingContent, TableColumnContent] with TextNode;
+        type: ScriptType,
+    } in [MetadataContent, FlowContent, PhrasingContent, TableColumnContent] with ScriptContent;
     section in [FlowContent, SectioningContent] with FlowContent;
+    search in [FlowContent] with FlowContent;
     select {
-        autocomplete: String,
+        autocomplete: Autocomplete,
         autofocus: Bool,
         disabled: Bool,
         form: Id,
@@ -319,6 +471,9 @@ declare_elements!{
         required: Bool,
         size: usize,
     } in [FlowContent, PhrasingContent, InteractiveContent, FormContent] with SelectContent;
+    slot {
+        name: String,
+    } in [FlowContent, PhrasingContent] with FlowContent;
     small in [FlowContent, PhrasingContent] with PhrasingContent;
     span in [FlowContent, PhrasingContent] with PhrasingContent;
     strong in [FlowContent, PhrasingContent] with PhrasingContent;
@@ -327,7 +482,7 @@ declare_elements!{
     table in [FlowContent] with TableContent;
     template in [MetadataContent, FlowContent, PhrasingContent, TableColumnContent] with Node;
     textarea {
-        autocomplete: OnOff,
+        autocomplete: Autocomplete,
         autofocus: Bool,
         cols: usize,
         disabled: Bool,
@@ -345,20 +500,21 @@ declare_elements!{
     time {
         datetime: Datetime,
     } in [FlowContent, PhrasingContent] with PhrasingContent;
+    u in [FlowContent, PhrasingContent] with PhrasingContent;
     ul in [FlowContent] with li;
     var in [FlowContent, PhrasingContent] with PhrasingContent;
     video {
         autoplay: Bool,
         controls: Bool,
         crossorigin: CrossOrigin,
-        height: usize,
+        height: Dimension,
         loop: Bool,
         muted: Bool,
         preload: Preload,
         playsinline: Bool,
         poster: Uri,
         src: Uri,
-        width: usize,
+        width: Dimension,
     } in [FlowContent, PhrasingContent, EmbeddedContent] with MediaContent;
     wbr in [FlowContent, PhrasingContent];
 
@@ -370,6 +526,7 @@ declare_elements!{
         href: Uri,
         hreflang: LanguageTag,
         ping: SpacedList<Uri>,
+        referrerpolicy: ReferrerPolicy,
         rel: SpacedSet<LinkType>,
         shape: AreaShape,
         target: Target,
@@ -403,9 +560,14 @@ declare_elements!{
         value: String,
     };
     source {
+        height: Dimension,
+        media: String, // FIXME media query
+        sizes: SpacedList<String>, // FIXME it's not really just a string
         src: Uri,
+        srcset: String, // FIXME this is much more complicated
         type: Mime,
-    } in [MediaContent];
+        width: Dimension,
+    } in [MediaContent, PictureContent];
     summary with PhrasingContent;
     tbody in [TableContent] with tr;
     td {
@@ -431,20 +593,40 @@ declare_elements!{
         srclang: LanguageTag,
     } in [MediaContent];
 
+    // SVG
+    svg {
+        height: String, // FIXME length
+        preserveAspectRatio: String, // FIXME enum
+        viewBox: String, // FIXME four numbers
+        width: String, // FIXME length
+        xmlns: Uri,
+    } in [FlowContent, PhrasingContent, EmbeddedContent] with SvgContent;
+    symbol {
+        preserveAspectRatio: String, // FIXME enum
+        viewBox: String, // FIXME four numbers
+    } in [SvgContent] with Node;
+    use {
+        height: String, // FIXME length
+        href: Uri,
+        width: String, // FIXME length
+        x: String, // FIXME length
+        y: String, // FIXME length
+    } in [SvgContent];
+
     // Don't @ me
     blink in [FlowContent, PhrasingContent] with PhrasingContent;
     marquee {
         behavior: String, // FIXME enum
-        bgcolor: String, // FIXME colour
+        bgcolor: Color,
         direction: String, // FIXME direction enum
-        height: String, // FIXME size
-        hspace: String, // FIXME size
+        height: Dimension,
+        hspace: Dimension,
         loop: isize,
         scrollamount: usize,
         scrolldelay: usize,
         truespeed: Bool,
-        vspace: String, // FIXME size
-        width: String, // FIXME size
+        vspace: Dimension,
+        width: Dimension,
     } in [FlowContent, PhrasingContent] with PhrasingContent;
 }
 
@@ -457,3 +639,508 @@ fn test_data_attributes() {
 
     assert_eq!("<div data-id=\"1234\">Boo!</div>", frag.to_string());
 }
+
+#[test]
+fn test_keyword_element_names() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(<svg viewBox="0 0 8 8"><use href="dot"/></svg>);
+
+    assert_eq!(
+        "<svg viewBox=\"0&#x20;0&#x20;8&#x20;8\"><use href=\"dot\"></use></svg>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_dimensions() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(<img src="a" width=320 height="240"/>);
+
+    assert_eq!(
+        "<img height=\"240\" src=\"a\" width=\"320\"/>",
+        frag.to_string()
+    );
+
+    let size: u16 = 16;
+    let frag: DOMTree<String> = html!(<img src="a" width=size height=size/>);
+    assert_eq!("<img height=\"16\" src=\"a\" width=\"16\"/>", frag.to_string());
+}
+
+#[test]
+fn test_empty_sandbox() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(<iframe src="a" sandbox=SpacedSet::new()></iframe>);
+    assert_eq!("<iframe sandbox src=\"a\"></iframe>", frag.to_string());
+
+    let frag: DOMTree<String> = html!(<iframe sandbox="allow-scripts allow-forms"></iframe>);
+    assert_eq!(
+        "<iframe sandbox=\"allow&#x2D;forms&#x20;allow&#x2D;scripts\"></iframe>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_crossorigin_boolean_form() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(<img src="a" crossorigin=""/>);
+    assert_eq!(
+        "<img crossorigin=\"anonymous\" src=\"a\"/>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_mobile_keyboard_hints() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(<input inputmode="numeric" enterkeyhint="done"/>);
+    assert_eq!(
+        "<input enterkeyhint=\"done\" inputmode=\"numeric\"/>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_popover() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(
+        <div>
+            <button popovertarget="menu" popovertargetaction="show">"Menu"</button>
+            <div id="menu" popover="">"Items"</div>
+            <dialog open=true>"Hello"</dialog>
+        </div>
+    );
+    assert_eq!(
+        "div { children: [\
+         button { popovertarget: \"menu\", popovertargetaction: \"show\", children: [\"Menu\"] }, \
+         div { id: \"menu\", popover: \"auto\", children: [\"Items\"] }, \
+         dialog { open: \"true\", children: [\"Hello\"] }] }",
+        format!("{:?}", frag)
+    );
+}
+
+#[test]
+fn test_living_standard_elements() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(
+        <search inert=true>
+            <hgroup><h1>"Find"</h1><p>"Anything"</p></hgroup>
+            <picture>
+                <source srcset="a" media="(min-width: 800px)"/>
+                <img src="b" alt=""/>
+            </picture>
+            <menu><li><u>"Item"</u></li></menu>
+        </search>
+    );
+    assert_eq!(
+        "search { inert: \"true\", children: [\
+         hgroup { children: [h1 { children: [\"Find\"] }, p { children: [\"Anything\"] }] }, \
+         picture { children: [source { media: \"(min-width: 800px)\", srcset: \"a\" }, \
+         img { alt: \"\", src: \"b\" }] }, \
+         menu { children: [li { children: [u { children: [\"Item\"] }] }] }] }",
+        format!("{:?}", frag)
+    );
+}
+
+#[test]
+fn test_let_bindings() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+    use crate::text;
+
+    let names = vec!["Joe", "Mike", "Robert"];
+    let frag: DOMTree<String> = html!(
+        <div>
+            let count = names.len();
+            <p>{ text!("{} names", count) }</p>
+            let last = names[count - 1];
+            let twice = count * 2;
+            <p>{ text!("{} is last, {}", last, twice) }</p>
+        </div>
+    );
+    assert_eq!(
+        "<div><p>3 names</p><p>Robert is last, 6</p></div>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_mixins() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+    use crate::mixin;
+
+    mixin! {
+        ExternalLink {
+            target: "_blank",
+            rel: "noopener noreferrer",
+        }
+        Pixel {
+            width: 1,
+            height: { 1u32 },
+            alt: "",
+        }
+    }
+
+    let frag: DOMTree<String> = html!(
+        <p>
+            <a href="a" {..ExternalLink}>"Link"</a>
+            <img src="b" {..Pixel}/>
+        </p>
+    );
+    assert_eq!(
+        "p { children: [\
+         a { href: \"a\", rel: \"noopener noreferrer\", target: \"_blank\", children: [\"Link\"] }, \
+         img { alt: \"\", height: \"1\", src: \"b\", width: \"1\" }] }",
+        format!("{:?}", frag)
+    );
+}
+
+#[test]
+fn test_event_shorthand_and_map() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+    use crate::events::Events;
+
+    let mut events = Events::default();
+    events.click = Some("hi".to_string());
+    events.focus = Some("focus".to_string());
+    let frag: DOMTree<String> = html!(
+        <div>
+            <button on:click="go">"Go"</button>
+            <button on={events} onfocus="override">"Hi"</button>
+        </div>
+    : String);
+    assert_eq!(
+        "<div><button onclick=\"go\">Go</button>\
+         <button onclick=\"hi\" onfocus=\"override\">Hi</button></div>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_escape_policy() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+    use crate::escape::{Escape, Serializer};
+
+    struct Shouty;
+    impl Escape for Shouty {
+        fn text(&self, text: &str) -> String {
+            text.to_uppercase()
+        }
+        fn attribute(&self, value: &str) -> String {
+            value.replace('"', "&quot;")
+        }
+        fn url(&self, _url: &str) -> String {
+            "#".to_string()
+        }
+    }
+
+    let frag: DOMTree<String> = html!(
+        <p title="\"hi\"" data-x="y">
+            <a href="https://example.com/">"link"</a>
+            { crate::unsafe_text!("<br>") }
+        </p>
+    );
+    assert_eq!(
+        "<p title=\"&quot;hi&quot;\" data-x=\"y\"><a href=\"#\">LINK</a><br></p>",
+        Serializer::new(Shouty).render(&*frag)
+    );
+    assert_eq!(
+        "<p title=\"&quot;hi&quot;\" data-x=\"y\">\
+         <a href=\"https&#x3A;&#x2F;&#x2F;example&#x2E;com&#x2F;\">link</a><br></p>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_character_references() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+    use crate::text;
+
+    let frag: DOMTree<String> = html!(
+        <p>"Fish&nbsp;&amp;&#32;chips &mdash; R&D &copy" { text!("&amp;") }</p>
+    );
+    assert_eq!(
+        "<p>Fish\u{a0}&amp; chips \u{2014} R&amp;D &amp;copy&amp;amp;</p>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_canonical_string() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(
+        <form data-z="last" onsubmit="go()" data-a="first" action="/send?a=1&b=2">
+            <input type="checkbox" checked=true name="x"/>
+            "  Tick\n   me  "
+        </form>
+    : String);
+    assert_eq!(
+        "<form action=\"/send?a=1&amp;b=2\" data-a=\"first\" data-z=\"last\" \
+         onsubmit=\"go&#x28;&#x29;\"><input checked=\"true\" name=\"x\" type=\"checkbox\"/> Tick me </form>",
+        frag.to_canonical_string()
+    );
+}
+
+#[test]
+fn test_vendor_attributes() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+    use crate::vendor;
+
+    vendor! {
+        x {
+            data: String,
+            show: bool,
+        }
+    }
+
+    let open = false;
+    let frag: DOMTree<String> = html!(
+        <div data-id="1" x:show={open} class="menu" x:data="menu">"Menu"</div>
+    );
+    assert_eq!(
+        "<div class=\"menu\" x-show=\"false\" x-data=\"menu\" data-id=\"1\">Menu</div>",
+        frag.to_string()
+    );
+    assert_eq!(
+        "<div class=\"menu\" data-id=\"1\" x-data=\"menu\" x-show=\"false\">Menu</div>",
+        frag.to_canonical_string()
+    );
+}
+
+#[test]
+fn test_raw_attributes() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let target = "#menu";
+    let frag: DOMTree<String> = html!(
+        <button class="toggle" raw_attrs={vec![("v-cloak", ""), ("@click", "open = !open")]}>
+            <span raw_attrs={Some(("hx-target", target))}>"Menu"</span>
+        </button>
+    );
+    assert_eq!(
+        "<button class=\"toggle\" v-cloak=\"\" @click=\"open&#x20;&#x3D;&#x20;&#x21;open\">\
+         <span hx-target=\"&#x23;menu\">Menu</span></button>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_raw_attributes_invalid_name() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let name = "on click";
+    let frag: DOMTree<String> =
+        html!(<p raw_attrs={vec![(name, "x"), ("data-ok", "y")]}>"Fine"</p>);
+    assert_eq!("<p data-ok=\"y\">Fine</p>", frag.to_string());
+}
+
+#[test]
+fn test_maud_syntax() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let name = "Kitty";
+    let done = true;
+    let frag: DOMTree<String> = maud! {
+        form.todo.large#edit action="save" {
+            @let label = format!("Hello, {}", name);
+            label for="done" { (label) }
+            input#done type="checkbox" checked[done] disabled;
+            @for n in 1..3 {
+                @if n == 1 {
+                    span data-index="first" { (n) }
+                } @else {
+                    br;
+                }
+            }
+        }
+    };
+    assert_eq!(
+        "<form action=\"save\" class=\"large&#x20;todo\" id=\"edit\">\
+         <label for=\"done\">Hello, Kitty</label>\
+         <input checked=\"true\" disabled=\"true\" id=\"done\" type=\"checkbox\"/>\
+         <span data-index=\"first\">1</span><br/></form>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_microdata_attributes() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let frag: DOMTree<String> = html!(
+        <div itemscope=true itemtype="https://schema.org/Product" itemref="offer">
+            <span itemprop="name">"Kitty"</span>
+            <img itemprop="image" src="kitty.png" alt=""/>
+        </div>
+    );
+    assert_eq!(
+        "<div itemref=\"offer\" itemscope=\"true\" itemtype=\"https://schema.org/Product\">\
+         <span itemprop=\"name\">Kitty</span><img itemprop=\"image\" src=\"kitty.png\"/></div>",
+        frag.to_canonical_string()
+    );
+}
+
+#[test]
+fn test_source_annotations() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+    use crate::escape::{Html, Serializer};
+
+    let item = |name: &str| -> Box<li<String>> { html!(<li>{ crate::text!("{}", name) }</li>) };
+    let line = line!() - 1;
+    let frag: DOMTree<String> = html!(<ul>{ item("Kitty") }</ul>);
+    assert_eq!("<ul><li>Kitty</li></ul>", frag.to_string());
+    assert_eq!(
+        format!(
+            "<!-- {file}:{outer}:33 typed_html::elements --><ul>\
+             <!-- {file}:{inner}:50 typed_html::elements --><li>Kitty</li>\
+             <!-- /{file}:{inner}:50 --></ul><!-- /{file}:{outer}:33 -->",
+            file = file!(),
+            inner = line,
+            outer = line + 2,
+        ),
+        Serializer::new(Html).annotate_sources(true).render(&*frag)
+    );
+}
+
+#[test]
+fn test_render_static_template_after_changes() {
+    use crate as typed_html;
+
+    let card = |first: bool| -> Box<div<String>> {
+        let mut card: Box<div<String>> = html!(<div class="card"><p>"Kitty Cat"</p></div>);
+        let html = "<div class=\"card\"><p>Kitty Cat</p></div>";
+        assert_eq!(html, card.to_string());
+        if first {
+            card.attrs.id = Some("secret".parse().unwrap());
+            card.children.push(html!(<p>"For your eyes only"</p>));
+        }
+        card
+    };
+    assert_eq!(
+        "<div class=\"card\" id=\"secret\"><p>Kitty Cat</p><p>For your eyes only</p></div>",
+        card(true).to_string()
+    );
+    assert_eq!("<div class=\"card\"><p>Kitty Cat</p></div>", card(false).to_string());
+}
+
+#[test]
+fn test_aria_attributes() {
+    use crate as typed_html;
+    use crate::types::Role;
+
+    let checked = false;
+    let frag: Box<div<String>> = html!(
+        <div role="checkbox" aria-checked={checked} aria-label="Agree" tabindex="0"></div>
+    );
+    assert!(frag.attrs.role == Some(Role::Checkbox));
+    assert_eq!(
+        "<div role=\"checkbox\" tabindex=\"0\" aria-checked=\"false\" aria-label=\"Agree\"></div>",
+        frag.to_string()
+    );
+
+    let frag: Box<input<String>> = html!(<input type="checkbox" role="switch"/>);
+    assert!(frag.attrs.role == Some(Role::Switch));
+
+    let frag: Box<table<String>> = html!(
+        <table role="grid"><tbody><tr><td aria-selected="true">"Cell"</td></tr></tbody></table>
+    );
+    assert_eq!(
+        "<table role=\"grid\"><tbody><tr><td aria-selected=\"true\">Cell</td></tr></tbody></table>",
+        frag.to_string()
+    );
+}
+
+#[test]
+fn test_component_children() {
+    use crate as typed_html;
+    use crate::dom::{DOMTree, Element};
+    use crate::validate::Validator;
+
+    crate::content_group!(pub TabsContent);
+
+    crate::declare_elements! {
+        tabs in [FlowContent] with TabsContent;
+        tab {
+            label: String,
+        } in [TabsContent] with FlowContent;
+        tablink in [TabsContent] with PhrasingContent;
+        tabstrip in [FlowContent] with tab;
+    }
+
+    let labels = ["Cats", "Dogs"];
+    let mut doc: DOMTree<String> = html!(
+        <div>
+            <tabs>
+                { labels.iter().map(|label| html!(<tab label=label.to_string()>{ crate::text!(*label) }</tab>)) }
+                <tablink>"More"</tablink>
+            </tabs>
+            <tabstrip><tab label="Only">"Tabs"</tab></tabstrip>
+        </div>
+    );
+    assert_eq!(
+        "<div><tabs><tab label=\"Cats\">Cats</tab><tab label=\"Dogs\">Dogs</tab>\
+         <tablink>More</tablink></tabs><tabstrip><tab label=\"Only\">Tabs</tab></tabstrip></div>",
+        doc.to_string()
+    );
+
+    let validator = Validator::new()
+        .element(tabs::<String>::content_model())
+        .element(tab::<String>::content_model())
+        .element(tablink::<String>::content_model())
+        .element(tabstrip::<String>::content_model());
+    assert!(validator.validate(&doc.vnode()).is_empty());
+}
+
+#[test]
+fn test_format_specs() {
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    let price = 4.5;
+    let count = 42;
+    let name = "Joe";
+    let width = 4;
+    let doc: DOMTree<String> = html!(
+        <table>
+            <tr>
+                <td>{ price:.2 }</td>
+                <td>{ count:>5 }</td>
+                <td>{ count:#x }</td>
+                <td>{ name:*^7 }</td>
+                <td>{ std::f64::consts::PI:08.3 }</td>
+                <td>{ count:0width$ }</td>
+                <td>{ Some(name):? }</td>
+            </tr>
+        </table>
+    );
+    assert_eq!(
+        "<table><tr><td>4.50</td><td>   42</td><td>0x2a</td><td>**Joe**</td>\
+         <td>0003.142</td><td>0042</td><td>Some(&quot;Joe&quot;)</td></tr></table>",
+        doc.to_string()
+    );
+}
@@ -0,0 +1,728 @@
+//! Escaping policies for rendering to strings.
+//!
+//! When a DOM tree is rendered to a string, text and attribute values are
+//! escaped by an [`Escape`][Escape] policy. [`Display`][Display] always uses
+//! the standard [`Html`][Html] policy; use a [`Serializer`][Serializer] to
+//! render with a different one.
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::escape::{Serializer, Strict};
+//!
+//! # fn main() {
+//! let doc: DOMTree<String> = html!(
+//!     <a href="javascript:alert(1)">"Click me"</a>
+//! );
+//! assert_eq!(
+//!     "<a href=\"about&#x3A;invalid\">Click&#x20;me</a>",
+//!     Serializer::new(Strict).render(&*doc)
+//! );
+//! # }
+//! ```
+//!
+//! [Display]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+//! [Escape]: trait.Escape.html
+//! [Html]: struct.Html.html
+//! [Serializer]: struct.Serializer.html
+
+use std::fmt::{self, Write};
+use std::io;
+
+use htmlescape::{encode_attribute, encode_minimal};
+
+use crate::dom::Node;
+#[cfg(feature = "feed")]
+use crate::feed::Feed;
+use crate::OutputType;
+
+mod entities;
+
+/// A policy for escaping strings in each context they can appear in.
+pub trait Escape {
+    /// Escape the contents of a text node.
+    fn text(&self, text: &str) -> String;
+
+    /// Escape an attribute value, which will be written inside double quotes.
+    fn attribute(&self, value: &str) -> String;
+
+    /// Escape the value of an attribute which holds a URL, like `href` or
+    /// `src`.
+    ///
+    /// By default, this escapes it like any other attribute value.
+    fn url(&self, url: &str) -> String {
+        self.attribute(url)
+    }
+
+    /// Whether to write an element's attributes sorted by name, rather than
+    /// its typed attributes followed by its `data-` attributes.
+    ///
+    /// By default, they're not sorted.
+    fn sort_attributes(&self) -> bool {
+        false
+    }
+
+    /// Whether to write the [`Source`][Source] of each element which has one
+    /// in comments around it.
+    ///
+    /// By default, they're not written.
+    ///
+    /// [Source]: ../dom/struct.Source.html
+    fn annotate_sources(&self) -> bool {
+        false
+    }
+}
+
+/// The standard escaping policy.
+///
+/// Text nodes have `&`, `<`, `>` and quotes escaped, and attribute values
+/// have everything except ASCII letters and digits escaped.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Html;
+
+impl Escape for Html {
+    fn text(&self, text: &str) -> String {
+        encode_minimal(text)
+    }
+
+    fn attribute(&self, value: &str) -> String {
+        encode_attribute(value)
+    }
+}
+
+/// A stricter escaping policy, for hardening against injection.
+///
+/// This escapes everything but ASCII letters and digits in text as well as in
+/// attribute values, and replaces URLs which don't use a scheme known to be
+/// harmless, like `javascript:` URLs, with `about:invalid`. Relative URLs are
+/// left alone.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Strict;
+
+/// The escaping policy for [`Node::to_canonical_string()`][canonical].
+///
+/// This escapes only `&`, `<`, `>` and quotes, collapses runs of whitespace in
+/// text to a single space, and sorts attributes by name.
+///
+/// [canonical]: ../dom/trait.Node.html#method.to_canonical_string
+#[derive(Clone, Copy, Default, Debug)]
+pub struct Canonical;
+
+impl Escape for Canonical {
+    fn text(&self, text: &str) -> String {
+        let mut collapsed = String::with_capacity(text.len());
+        let mut space = false;
+        for c in text.chars() {
+            if c.is_ascii_whitespace() {
+                space = true;
+                continue;
+            }
+            if space {
+                collapsed.push(' ');
+                space = false;
+            }
+            collapsed.push(c);
+        }
+        if space {
+            collapsed.push(' ');
+        }
+        encode_minimal(&collapsed)
+    }
+
+    fn attribute(&self, value: &str) -> String {
+        encode_minimal(value)
+    }
+
+    fn sort_attributes(&self) -> bool {
+        true
+    }
+}
+
+/// URL schemes which can't run script.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto", "tel", "ftp"];
+
+impl Escape for Strict {
+    fn text(&self, text: &str) -> String {
+        encode_attribute(text)
+    }
+
+    fn attribute(&self, value: &str) -> String {
+        encode_attribute(value)
+    }
+
+    fn url(&self, url: &str) -> String {
+        // Browsers ignore tabs and newlines anywhere in a URL, and leading
+        // spaces and control characters, so "java\tscript:" is still a
+        // javascript: URL.
+        let url: String = url
+            .trim_start_matches(|c: char| c <= ' ')
+            .chars()
+            .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+            .collect();
+        let scheme = url
+            .find([':', '/', '?', '#'])
+            .filter(|index| url[*index..].starts_with(':'))
+            .map(|index| &url[..index]);
+        match scheme {
+            Some(scheme)
+                if !SAFE_SCHEMES
+                    .iter()
+                    .any(|safe| safe.eq_ignore_ascii_case(scheme)) =>
+            {
+                encode_attribute("about:invalid")
+            }
+            _ => encode_attribute(&url),
+        }
+    }
+}
+
+/// How to write characters outside of ASCII.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum NonAscii {
+    /// Write them as they are, encoded as UTF-8.
+    #[default]
+    Raw,
+    /// Write them as numeric character references, like `&#xE9;`.
+    Numeric,
+    /// Write them as named character references, like `&eacute;`, where
+    /// HTML 4 has a name for them, and as numeric references otherwise.
+    Named,
+}
+
+impl NonAscii {
+    fn encode(self, escaped: String) -> String {
+        if self == NonAscii::Raw || escaped.is_ascii() {
+            return escaped;
+        }
+        let mut out = String::with_capacity(escaped.len() * 2);
+        for c in escaped.chars() {
+            if c.is_ascii() {
+                out.push(c);
+                continue;
+            }
+            let name = match self {
+                NonAscii::Named => entities::NAMED
+                    .binary_search_by_key(&c, |&(entity, _)| entity)
+                    .ok()
+                    .map(|index| entities::NAMED[index].1),
+                _ => None,
+            };
+            match name {
+                Some(name) => write!(out, "&{};", name),
+                None => write!(out, "&#x{:X};", c as u32),
+            }
+            .unwrap();
+        }
+        out
+    }
+}
+
+/// A character encoding to write HTML in.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum Charset {
+    /// UTF-8, which can represent every character.
+    #[default]
+    Utf8,
+    /// ISO-8859-1, which represents the first 256 code points, one byte
+    /// each.
+    Latin1,
+    /// US-ASCII, for output which has to be 7-bit clean.
+    Ascii,
+}
+
+impl Charset {
+    /// The charset's name, as it's written in a `Content-Type` header or a
+    /// `<meta charset>` element.
+    pub fn name(self) -> &'static str {
+        match self {
+            Charset::Utf8 => "UTF-8",
+            Charset::Latin1 => "ISO-8859-1",
+            Charset::Ascii => "US-ASCII",
+        }
+    }
+
+    /// Whether the charset can represent a character.
+    fn contains(self, c: char) -> bool {
+        match self {
+            Charset::Utf8 => true,
+            Charset::Latin1 => (c as u32) < 0x100,
+            Charset::Ascii => c.is_ascii(),
+        }
+    }
+
+    /// Replace the characters the charset can't represent in escaped text
+    /// with numeric character references.
+    fn substitute(self, escaped: String) -> String {
+        if !escaped.contains(|c| !self.contains(c)) {
+            return escaped;
+        }
+        let mut out = String::with_capacity(escaped.len() * 2);
+        for c in escaped.chars() {
+            if self.contains(c) {
+                out.push(c);
+            } else {
+                write!(out, "&#x{:X};", c as u32).unwrap();
+            }
+        }
+        out
+    }
+}
+
+/// Writes HTML on to another writer, failing on the first character the
+/// charset can't represent.
+///
+/// Escaped text has already had those characters replaced, so this only
+/// finds them in unescaped text and comments, where a character reference
+/// would change what the script, style or comment says.
+struct Restrict<'a> {
+    out: &'a mut dyn fmt::Write,
+    charset: Charset,
+    unrepresentable: Option<char>,
+}
+
+impl<'a> fmt::Write for Restrict<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let charset = self.charset;
+        if let Some(c) = s.chars().find(|&c| !charset.contains(c)) {
+            self.unrepresentable = Some(c);
+            return Err(fmt::Error);
+        }
+        self.out.write_str(s)
+    }
+}
+
+/// Encodes HTML, which only has characters the charset can represent, into
+/// bytes, keeping the first I/O error.
+struct Transcode<'a> {
+    out: &'a mut dyn io::Write,
+    charset: Charset,
+    error: Option<io::Error>,
+}
+
+impl<'a> fmt::Write for Transcode<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let result = match self.charset {
+            Charset::Latin1 if !s.is_ascii() => {
+                let bytes: Vec<u8> = s.chars().map(|c| c as u8).collect();
+                self.out.write_all(&bytes)
+            }
+            _ => self.out.write_all(s.as_bytes()),
+        };
+        result.map_err(|err| {
+            self.error = Some(err);
+            fmt::Error
+        })
+    }
+}
+
+/// Renders DOM trees to strings with a given escaping policy.
+///
+/// By default, characters outside of ASCII are written as UTF-8. If your
+/// output goes somewhere which can't be trusted with UTF-8, use
+/// [`non_ascii()`][non_ascii] to have them written as character references
+/// instead:
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use typed_html::escape::{Html, NonAscii, Serializer};
+///
+/// # fn main() {
+/// let doc: DOMTree<String> = html!(<p>"Café – 5€"</p>);
+/// assert_eq!(
+///     "<p>Caf&eacute; &ndash; 5&euro;</p>",
+///     Serializer::new(Html).non_ascii(NonAscii::Named).render(&*doc)
+/// );
+/// assert_eq!(
+///     "<p>Caf&#xE9; &#x2013; 5&#x20AC;</p>",
+///     Serializer::new(Html).non_ascii(NonAscii::Numeric).render(&*doc)
+/// );
+/// # }
+/// ```
+///
+/// This applies to text and attribute values after they've been escaped, so
+/// it doesn't touch [unescaped text][unsafe_text], which may be script or CSS
+/// where character references wouldn't be understood.
+///
+/// To write bytes in an encoding other than UTF-8, for a system which only
+/// accepts ISO-8859-1 or 7-bit ASCII, set a [`charset()`][charset] and use
+/// [`render_bytes()`][render_bytes] or [`write_bytes()`][write_bytes].
+/// Characters the charset can't represent are written as numeric character
+/// references in text and attribute values. Unescaped text and comments are
+/// written as they are, so one of those characters there is an error: in a
+/// `<script>`, `"é"` would otherwise become the string `"&#xE9;"`.
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use typed_html::escape::{Charset, Html, Serializer};
+///
+/// # fn main() {
+/// let doc: DOMTree<String> = html!(<p>"Café – 5€"</p>);
+/// assert_eq!(
+///     b"<p>Caf\xE9 &#x2013; 5&#x20AC;</p>".to_vec(),
+///     Serializer::new(Html).charset(Charset::Latin1).render_bytes(&*doc)
+/// );
+/// # }
+/// ```
+///
+/// [non_ascii]: #method.non_ascii
+/// [unsafe_text]: ../macro.unsafe_text.html
+/// [charset]: #method.charset
+/// [render_bytes]: #method.render_bytes
+/// [write_bytes]: #method.write_bytes
+#[derive(Clone, Default, Debug)]
+pub struct Serializer<E: Escape> {
+    escape: E,
+    non_ascii: NonAscii,
+    charset: Charset,
+    annotate_sources: bool,
+}
+
+impl<E: Escape> Serializer<E> {
+    /// Construct a serializer which escapes with the given policy.
+    pub fn new(escape: E) -> Self {
+        Serializer {
+            escape,
+            non_ascii: NonAscii::default(),
+            charset: Charset::default(),
+            annotate_sources: false,
+        }
+    }
+
+    /// Set how characters outside of ASCII are written.
+    pub fn non_ascii(mut self, non_ascii: NonAscii) -> Self {
+        self.non_ascii = non_ascii;
+        self
+    }
+
+    /// Set the charset to write in, replacing the characters it can't
+    /// represent with numeric character references in text and attribute
+    /// values.
+    ///
+    /// Rendering fails if [unescaped text][unsafe_text] or a comment has a
+    /// character the charset can't represent, so write those with escapes
+    /// the script or CSS understands, like `\u00E9` or `\E9`.
+    ///
+    /// Strings from [`render()`][render] only have characters the charset
+    /// can represent in them, but are still UTF-8 encoded, so use
+    /// [`render_bytes()`][render_bytes] to get them in the charset.
+    ///
+    /// [render]: #method.render
+    /// [render_bytes]: #method.render_bytes
+    /// [unsafe_text]: ../macro.unsafe_text.html
+    pub fn charset(mut self, charset: Charset) -> Self {
+        self.charset = charset;
+        self
+    }
+
+    /// Write comments around the root element of each [`html!`][html]
+    /// invocation recording where it is in the source code, for finding
+    /// where some markup came from while developing.
+    ///
+    /// ```
+    /// # use typed_html::html;
+    /// # use typed_html::dom::DOMTree;
+    /// use typed_html::escape::{Html, Serializer};
+    ///
+    /// # fn main() {
+    /// let doc: DOMTree<String> = html!(<p>"Hello"</p>);
+    /// let html = Serializer::new(Html).annotate_sources(true).render(&*doc);
+    /// // Something like "<!-- src/main.rs:5:28 my_app --><p>Hello</p><!-- /src/main.rs:5:28 -->"
+    /// assert!(html.starts_with("<!-- "));
+    /// assert!(html.contains("--><p>Hello</p><!-- /"));
+    /// # }
+    /// ```
+    ///
+    /// [html]: ../macro.html.html
+    pub fn annotate_sources(mut self, annotate: bool) -> Self {
+        self.annotate_sources = annotate;
+        self
+    }
+
+    /// Render a node to a string.
+    ///
+    /// # Panics
+    ///
+    /// If unescaped text or a comment has a character the serializer's
+    /// [`charset()`][charset] can't represent. Use [`write()`][write] to
+    /// handle that as an error.
+    ///
+    /// [charset]: #method.charset
+    /// [write]: #method.write
+    pub fn render<T: OutputType>(&self, node: &dyn Node<T>) -> String {
+        let mut out = String::new();
+        self.write(node, &mut out).unwrap();
+        out
+    }
+
+    /// Render a node into a writer.
+    ///
+    /// This fails if unescaped text or a comment has a character the
+    /// serializer's [`charset()`][charset] can't represent.
+    ///
+    /// [charset]: #method.charset
+    pub fn write<T: OutputType>(
+        &self,
+        node: &dyn Node<T>,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        if self.charset == Charset::Utf8 {
+            return self.write_escaped(node, out);
+        }
+        let mut out = Restrict {
+            out,
+            charset: self.charset,
+            unrepresentable: None,
+        };
+        self.write_escaped(node, &mut out)
+    }
+
+    /// Render a node to bytes in the serializer's charset.
+    ///
+    /// # Panics
+    ///
+    /// If unescaped text or a comment has a character the serializer's
+    /// [`charset()`][charset] can't represent. Use
+    /// [`write_bytes()`][write_bytes] to handle that as an error.
+    ///
+    /// [charset]: #method.charset
+    /// [write_bytes]: #method.write_bytes
+    pub fn render_bytes<T: OutputType>(&self, node: &dyn Node<T>) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_bytes(node, &mut out).unwrap();
+        out
+    }
+
+    /// Render a node into a byte stream, in the serializer's charset.
+    ///
+    /// If unescaped text or a comment has a character the charset can't
+    /// represent, this fails with an `InvalidData` error.
+    pub fn write_bytes<T: OutputType>(
+        &self,
+        node: &dyn Node<T>,
+        out: &mut dyn io::Write,
+    ) -> io::Result<()> {
+        let mut transcode = Transcode {
+            out,
+            charset: self.charset,
+            error: None,
+        };
+        let mut out = Restrict {
+            out: &mut transcode,
+            charset: self.charset,
+            unrepresentable: None,
+        };
+        match self.write_escaped(node, &mut out) {
+            Ok(()) => Ok(()),
+            Err(_) => Err(match out.unrepresentable {
+                Some(c) => io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "U+{:04X} can't be written in {} outside of escaped text",
+                        c as u32,
+                        self.charset.name()
+                    ),
+                ),
+                None => transcode
+                    .error
+                    .take()
+                    .unwrap_or_else(|| io::Error::other("formatter error")),
+            }),
+        }
+    }
+
+    fn write_escaped<T: OutputType>(
+        &self,
+        node: &dyn Node<T>,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        if self.non_ascii == NonAscii::Raw
+            && self.charset == Charset::Utf8
+            && !self.annotate_sources
+        {
+            return node.write_html(out, &self.escape);
+        }
+        node.write_html(
+            out,
+            &Encode {
+                escape: &self.escape,
+                non_ascii: self.non_ascii,
+                charset: self.charset,
+                annotate_sources: self.annotate_sources,
+            },
+        )
+    }
+}
+
+#[cfg(feature = "feed")]
+impl<E: Escape> Serializer<E> {
+    /// Render a feed to a string.
+    ///
+    /// XML doesn't know HTML's names for characters, so with
+    /// [`NonAscii::Named`][Named] they're written as numeric references.
+    ///
+    /// [Named]: enum.NonAscii.html#variant.Named
+    pub fn render_feed(&self, feed: &dyn Feed) -> String {
+        let mut out = String::new();
+        self.write_feed(feed, &mut out).unwrap();
+        out
+    }
+
+    /// Render a feed into a writer.
+    pub fn write_feed(&self, feed: &dyn Feed, out: &mut dyn fmt::Write) -> fmt::Result {
+        let non_ascii = match self.non_ascii {
+            NonAscii::Named => NonAscii::Numeric,
+            non_ascii => non_ascii,
+        };
+        if non_ascii == NonAscii::Raw {
+            return feed.write_xml(out, &self.escape);
+        }
+        feed.write_xml(
+            out,
+            &Encode {
+                escape: &self.escape,
+                non_ascii,
+                charset: Charset::Utf8,
+                annotate_sources: false,
+            },
+        )
+    }
+}
+
+/// An escaping policy which encodes the non-ASCII characters left over by
+/// another policy and the ones the charset can't represent, and turns on
+/// source annotations.
+struct Encode<'a, E: Escape> {
+    escape: &'a E,
+    non_ascii: NonAscii,
+    charset: Charset,
+    annotate_sources: bool,
+}
+
+impl<'a, E: Escape> Encode<'a, E> {
+    fn encode(&self, escaped: String) -> String {
+        self.charset.substitute(self.non_ascii.encode(escaped))
+    }
+}
+
+impl<'a, E: Escape> Escape for Encode<'a, E> {
+    fn text(&self, text: &str) -> String {
+        self.encode(self.escape.text(text))
+    }
+
+    fn attribute(&self, value: &str) -> String {
+        self.encode(self.escape.attribute(value))
+    }
+
+    fn url(&self, url: &str) -> String {
+        self.encode(self.escape.url(url))
+    }
+
+    fn sort_attributes(&self) -> bool {
+        self.escape.sort_attributes()
+    }
+
+    fn annotate_sources(&self) -> bool {
+        self.annotate_sources || self.escape.annotate_sources()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict_urls() {
+        for unsafe_url in &[
+            "javascript:alert(1)",
+            " JavaScript:alert(1)",
+            "java\tscript:alert(1)",
+            "data:text/html,<script>alert(1)</script>",
+        ] {
+            assert_eq!(
+                "about&#x3A;invalid",
+                Strict.url(unsafe_url),
+                "{:?}",
+                unsafe_url
+            );
+        }
+        for safe_url in &[
+            "https://example.com/",
+            "/path:with/colon",
+            "page?a=b:c",
+            "#top",
+        ] {
+            assert_eq!(
+                encode_attribute(safe_url),
+                Strict.url(safe_url),
+                "{:?}",
+                safe_url
+            );
+        }
+    }
+
+    #[test]
+    fn test_non_ascii() {
+        let text = "naïve → 日本".to_string();
+        assert_eq!(text, NonAscii::Raw.encode(text.clone()));
+        assert_eq!(
+            "na&#xEF;ve &#x2192; &#x65E5;&#x672C;",
+            NonAscii::Numeric.encode(text.clone())
+        );
+        assert_eq!(
+            "na&iuml;ve &rarr; &#x65E5;&#x672C;",
+            NonAscii::Named.encode(text)
+        );
+        let mut last = '\0';
+        for &(c, _) in entities::NAMED {
+            assert!(c > last, "{:?} is out of order", c);
+            last = c;
+        }
+    }
+
+    #[test]
+    fn test_charsets() {
+        use crate as typed_html;
+        use crate::dom::DOMTree;
+
+        let doc: DOMTree<String> = html!(
+            <p title="✓">"naïve → "{ crate::unsafe_text!("<b>日本</b>") }</p>
+        );
+        assert_eq!(
+            doc.to_string().into_bytes(),
+            Serializer::new(Html).render_bytes(&*doc)
+        );
+        let mut out = String::new();
+        assert!(Serializer::new(Html)
+            .charset(Charset::Latin1)
+            .write(&*doc, &mut out)
+            .is_err());
+
+        // Unescaped script keeps what the charset can represent as it is,
+        // rather than as a character reference.
+        let doc: DOMTree<String> = html!(
+            <p title="✓">"naïve → "<script>{ crate::unsafe_text!("let s = \"é\";") }</script></p>
+        );
+        assert_eq!(
+            "<p title=\"&#x2713;\">naïve &#x2192; <script>let s = \"é\";</script></p>",
+            Serializer::new(Html).charset(Charset::Latin1).render(&*doc)
+        );
+        assert_eq!(
+            b"<p title=\"&#x2713;\">na\xEFve &#x2192; <script>let s = \"\xE9\";</script></p>"
+                .to_vec(),
+            Serializer::new(Html)
+                .charset(Charset::Latin1)
+                .render_bytes(&*doc)
+        );
+        let err = Serializer::new(Html)
+            .charset(Charset::Ascii)
+            .write_bytes(&*doc, &mut Vec::new())
+            .unwrap_err();
+        assert_eq!(io::ErrorKind::InvalidData, err.kind());
+        assert_eq!(
+            "U+00E9 can't be written in US-ASCII outside of escaped text",
+            err.to_string()
+        );
+    }
+}
@@ -5,6 +5,12 @@ use htmlescape::encode_attribute;
 use std::fmt::{Display, Error, Formatter};
 use std::iter;
 
+mod bind;
+pub use self::bind::{bind, BindError, Binding};
+
+mod key;
+pub use self::key::Key;
+
 /// Trait for event handlers.
 pub trait EventHandler<T: OutputType, E> {
     /// Build a callback function from this event handler.
@@ -100,6 +106,7 @@ declare_events_struct! {
     abort,
     autocomplete,
     autocompleteerror,
+    beforetoggle,
     blur,
     cancel,
     canplay,
@@ -107,8 +114,13 @@ declare_events_struct! {
     change,
     click,
     close,
+    compositionend,
+    compositionstart,
+    compositionupdate,
     contextmenu,
+    copy,
     cuechange,
+    cut,
     dblclick,
     drag,
     dragend,
@@ -123,6 +135,7 @@ declare_events_struct! {
     ended,
     error,
     focus,
+    gotpointercapture,
     input,
     invalid,
     keydown,
@@ -132,6 +145,7 @@ declare_events_struct! {
     loadeddata,
     loadedmetadata,
     loadstart,
+    lostpointercapture,
     mousedown,
     mouseenter,
     mouseleave,
@@ -139,10 +153,18 @@ declare_events_struct! {
     mouseout,
     mouseover,
     mouseup,
-    mousewheel,
+    paste,
     pause,
     play,
     playing,
+    pointercancel,
+    pointerdown,
+    pointerenter,
+    pointerleave,
+    pointermove,
+    pointerout,
+    pointerover,
+    pointerup,
     progress,
     ratechange,
     reset,
@@ -158,8 +180,13 @@ declare_events_struct! {
     suspend,
     timeupdate,
     toggle,
+    touchcancel,
+    touchend,
+    touchmove,
+    touchstart,
     volumechange,
     waiting,
+    wheel,
 }
 
 #[cfg(test)]
@@ -0,0 +1,130 @@
+use std::any::type_name;
+use std::error;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+/// An event handler which takes the value of a form control parsed into a
+/// Rust type, made with [`bind()`][bind].
+///
+/// [bind]: fn.bind.html
+pub struct Binding<V> {
+    handler: Box<dyn FnMut(V)>,
+    on_error: Option<Box<dyn FnMut(BindError)>>,
+    parse: fn(&str) -> Result<V, String>,
+}
+
+/// Make an event handler for a form control which parses its value into the
+/// type the closure takes, like `i64`, `f64` or [`Date`][Date], before
+/// calling it.
+///
+/// Values which don't parse are passed to the handler set with
+/// [`on_error()`][on_error] instead. Without one, the output backend reports
+/// them: the `stdweb` output passes them to its error handler, and the
+/// `headless` output puts them on the event.
+///
+/// # Examples
+///
+/// ```
+/// # #![recursion_limit = "256"]
+/// # use std::cell::Cell;
+/// # use std::rc::Rc;
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use typed_html::events::bind;
+/// use typed_html::output::headless::{dispatch, Event, Headless};
+///
+/// # fn main() {
+/// let quantity = Rc::new(Cell::new(1));
+/// let set_quantity = quantity.clone();
+/// let mut doc: DOMTree<Headless> = html!(
+///     <input type="number" oninput={ bind(move |value: i64| set_quantity.set(value)) }/>
+/// : Headless);
+///
+/// dispatch(&mut *doc, "input", Event::input("12")).unwrap();
+/// assert_eq!(12, quantity.get());
+///
+/// let event = dispatch(&mut *doc, "input", Event::input("twelve")).unwrap();
+/// assert_eq!(12, quantity.get());
+/// assert_eq!("twelve", event.bind_error().unwrap().value);
+/// # }
+/// ```
+///
+/// [Date]: ../types/struct.Date.html
+/// [on_error]: struct.Binding.html#method.on_error
+pub fn bind<V, F>(handler: F) -> Binding<V>
+where
+    V: FromStr + 'static,
+    V::Err: Display,
+    F: FnMut(V) + 'static,
+{
+    Binding {
+        handler: Box::new(handler),
+        on_error: None,
+        parse: |value| value.parse().map_err(|err: V::Err| err.to_string()),
+    }
+}
+
+impl<V> Binding<V> {
+    /// Set a handler for values which don't parse.
+    pub fn on_error<F>(mut self, on_error: F) -> Self
+    where
+        F: FnMut(BindError) + 'static,
+    {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    /// Parse a form control's value and call the handler with it.
+    ///
+    /// If it doesn't parse, this calls the error handler, or returns the
+    /// error if there isn't one. The output backends call this with the
+    /// value of the event's target.
+    pub fn handle(&mut self, value: &str) -> Result<(), BindError> {
+        match (self.parse)(value) {
+            Ok(value) => {
+                (self.handler)(value);
+                Ok(())
+            }
+            Err(message) => {
+                let err = BindError {
+                    value: value.to_string(),
+                    type_name: type_name::<V>(),
+                    message,
+                };
+                match self.on_error {
+                    Some(ref mut on_error) => {
+                        on_error(err);
+                        Ok(())
+                    }
+                    None => Err(err),
+                }
+            }
+        }
+    }
+}
+
+/// A form control's value which couldn't be parsed by a
+/// [`Binding`][Binding].
+///
+/// [Binding]: struct.Binding.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BindError {
+    /// The value.
+    pub value: String,
+    /// The name of the type it was parsed into.
+    pub type_name: &'static str,
+    /// The error from parsing it.
+    pub message: String,
+}
+
+impl Display for BindError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(
+            f,
+            "can't parse {:?} as {}: {}",
+            self.value, self.type_name, self.message
+        )
+    }
+}
+
+impl error::Error for BindError {}
@@ -0,0 +1,148 @@
+use std::fmt::{Display, Error, Formatter};
+
+macro_rules! declare_keys {
+    ($($(#[$attr:meta])* $name:ident = $value:literal $(| $alias:literal)*,)*) => {
+        /// The value of a keyboard event's `key` property.
+        ///
+        /// Named keys, like `Enter` or `ArrowUp`, get their own variants, and
+        /// keys which type something are a `Character`. Named keys this
+        /// doesn't know about are kept as `Other`.
+        ///
+        /// Convert a `key` string with `Key::from()`. The output backends
+        /// also provide this for their keyboard events.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use typed_html::events::Key;
+        ///
+        /// # fn main() {
+        /// assert_eq!(Key::Enter, Key::from("Enter"));
+        /// assert_eq!(Key::ArrowUp, Key::from("Up"));
+        /// assert_eq!(Key::F(5), Key::from("F5"));
+        /// assert_eq!(Key::Character("q".to_string()), Key::from("q"));
+        /// assert!(Key::from("q").is_character("q"));
+        /// # }
+        /// ```
+        #[derive(Clone, PartialEq, Eq, Hash, Debug)]
+        pub enum Key {
+            $(
+                $(#[$attr])*
+                $name,
+            )*
+            /// A function key, like `F1`.
+            F(u8),
+            /// A key which types a character, like `a` or a space.
+            Character(String),
+            /// A named key which isn't listed here.
+            Other(String),
+        }
+
+        impl<'a> From<&'a str> for Key {
+            fn from(key: &'a str) -> Self {
+                match key {
+                    $(
+                        $value $(| $alias)* => Key::$name,
+                    )*
+                    _ => {
+                        if let Some(number) = key
+                            .strip_prefix('F')
+                            .and_then(|number| number.parse().ok())
+                        {
+                            Key::F(number)
+                        } else if key.chars().count() == 1
+                            || !key.chars().all(|c| c.is_ascii_alphanumeric())
+                        {
+                            Key::Character(key.to_string())
+                        } else {
+                            Key::Other(key.to_string())
+                        }
+                    }
+                }
+            }
+        }
+
+        impl Display for Key {
+            fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+                match self {
+                    $(
+                        Key::$name => f.write_str($value),
+                    )*
+                    Key::F(number) => write!(f, "F{}", number),
+                    Key::Character(key) | Key::Other(key) => f.write_str(key),
+                }
+            }
+        }
+    };
+}
+
+declare_keys! {
+    Alt = "Alt",
+    AltGraph = "AltGraph",
+    CapsLock = "CapsLock",
+    Control = "Control",
+    Fn = "Fn",
+    Meta = "Meta" | "OS",
+    NumLock = "NumLock",
+    ScrollLock = "ScrollLock" | "Scroll",
+    Shift = "Shift",
+    Enter = "Enter",
+    Tab = "Tab",
+    ArrowDown = "ArrowDown" | "Down",
+    ArrowLeft = "ArrowLeft" | "Left",
+    ArrowRight = "ArrowRight" | "Right",
+    ArrowUp = "ArrowUp" | "Up",
+    End = "End",
+    Home = "Home",
+    PageDown = "PageDown",
+    PageUp = "PageUp",
+    Backspace = "Backspace",
+    Clear = "Clear",
+    Copy = "Copy",
+    Cut = "Cut",
+    Delete = "Delete" | "Del",
+    Insert = "Insert",
+    Paste = "Paste",
+    Redo = "Redo",
+    Undo = "Undo",
+    ContextMenu = "ContextMenu" | "Apps",
+    Escape = "Escape" | "Esc",
+    Pause = "Pause",
+    PrintScreen = "PrintScreen",
+    /// A dead key, which combines with the next key to type an accented
+    /// character.
+    Dead = "Dead",
+    /// A key the browser can't identify.
+    Unidentified = "Unidentified",
+}
+
+impl Key {
+    /// Test whether this is a key which types the given character.
+    pub fn is_character(&self, character: &str) -> bool {
+        match self {
+            Key::Character(key) => key == character,
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_from_str() {
+        assert_eq!(Key::Escape, Key::from("Esc"));
+        assert_eq!(Key::F(12), Key::from("F12"));
+        assert_eq!(Key::Character("F".to_string()), Key::from("F"));
+        assert_eq!(Key::Character(" ".to_string()), Key::from(" "));
+        assert_eq!(Key::Character("é".to_string()), Key::from("é"));
+        assert_eq!(
+            Key::Other("MediaPlayPause".to_string()),
+            Key::from("MediaPlayPause")
+        );
+        for key in &["ArrowUp", "F5", "a", "MediaPlayPause"] {
+            assert_eq!(*key, Key::from(*key).to_string());
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! RSS and Atom feeds.
+//!
+//! The [`rss`][rss] and [`atom`][atom] modules have typed builders for the
+//! elements of an RSS 2.0 channel and an Atom feed. Both implement
+//! [`Feed`][Feed], which writes them as XML with an [`Escape`][Escape]
+//! policy, so text and links in a feed are escaped just like they are in a
+//! DOM tree. `Display` uses the standard [`Html`][Html] policy, and a
+//! [`Serializer`][Serializer] renders them with any other.
+//!
+//! This module is only available with the `feed` feature flag enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use typed_html::feed::atom::{Entry, Feed, Link};
+//!
+//! let updated = "2019-03-28T12:00:00Z".parse().unwrap();
+//! let feed = Feed::new("urn:uuid:60a76c80", "Kitty News", updated)
+//!     .link(Link::new("https://example.com/").rel("alternate"))
+//!     .entry(Entry::new("urn:uuid:1225c695", "Cats & dogs", updated).summary("Friends?"));
+//! assert!(feed
+//!     .to_string()
+//!     .contains("<title>Cats &amp; dogs</title><updated>2019-03-28T12:00:00Z</updated>"));
+//! ```
+//!
+//! [rss]: rss/index.html
+//! [atom]: atom/index.html
+//! [Feed]: trait.Feed.html
+//! [Escape]: ../escape/trait.Escape.html
+//! [Html]: ../escape/struct.Html.html
+//! [Serializer]: ../escape/struct.Serializer.html
+
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use crate::dom::DOMTree;
+use crate::escape::Escape;
+use crate::types::Date;
+
+pub mod atom;
+pub mod rss;
+
+/// A document which can be written as XML, like an RSS channel or an Atom
+/// feed.
+pub trait Feed: Display {
+    /// Write the feed, escaping text and attribute values with the given
+    /// [`Escape`][Escape] policy.
+    ///
+    /// [Escape]: ../escape/trait.Escape.html
+    fn write_xml(&self, f: &mut dyn fmt::Write, escape: &dyn Escape) -> fmt::Result;
+}
+
+/// A moment in UTC, for the publication and update times in a feed.
+///
+/// It's written in the RFC 3339 format Atom uses, like
+/// `2019-03-28T12:00:00Z`, and parsed from it too. RSS feeds write it in
+/// the older RFC 822 format instead, like `Thu, 28 Mar 2019 12:00:00 GMT`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct DateTime {
+    date: Date,
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl DateTime {
+    /// Construct a time on the given date, or return `None` if the time of
+    /// day is out of range.
+    pub fn new(date: Date, hour: u8, minute: u8, second: u8) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        Some(DateTime {
+            date,
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    /// The date.
+    pub fn date(&self) -> Date {
+        self.date
+    }
+
+    /// Write the time in the RFC 822 format used by RSS.
+    fn rfc822(&self) -> String {
+        const DAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+        const MONTHS: [&str; 12] = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ];
+        // Sakamoto's method for the day of the week.
+        const OFFSETS: [u32; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+        let (month, day) = (self.date.month() as usize, self.date.day() as u32);
+        let year = self.date.year() - if month < 3 { 1 } else { 0 };
+        let weekday = (year + year / 4 - year / 100 + year / 400 + OFFSETS[month - 1] + day) % 7;
+        format!(
+            "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+            DAYS[weekday as usize],
+            day,
+            MONTHS[month - 1],
+            self.date.year(),
+            self.hour,
+            self.minute,
+            self.second
+        )
+    }
+}
+
+impl FromStr for DateTime {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const FORMAT: &str = "time must be written as YYYY-MM-DDTHH:MM:SSZ";
+        let (date, time) = s.split_once('T').ok_or(FORMAT)?;
+        let time = time.strip_suffix('Z').ok_or(FORMAT)?;
+        let mut parts = time.split(':');
+        let mut part = || {
+            parts
+                .next()
+                .filter(|part| part.len() == 2 && part.bytes().all(|b| b.is_ascii_digit()))
+                .map(|part| part.parse().unwrap())
+                .ok_or(FORMAT)
+        };
+        let (hour, minute, second) = (part()?, part()?, part()?);
+        if parts.next().is_some() {
+            return Err(FORMAT);
+        }
+        DateTime::new(date.parse()?, hour, minute, second).ok_or("time does not exist")
+    }
+}
+
+impl Display for DateTime {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}T{:02}:{:02}:{:02}Z",
+            self.date, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// The text of an element which can hold either plain text or HTML, like
+/// an item's description.
+///
+/// HTML is given as a DOM tree, which is rendered with the feed's escaping
+/// policy and then escaped again as the element's text, the way feed
+/// readers expect it.
+#[derive(Debug)]
+pub enum Content {
+    /// Plain text.
+    Text(String),
+    /// Markup.
+    Html(DOMTree<String>),
+}
+
+impl<'a> From<&'a str> for Content {
+    fn from(text: &'a str) -> Self {
+        Content::Text(text.to_string())
+    }
+}
+
+impl From<String> for Content {
+    fn from(text: String) -> Self {
+        Content::Text(text)
+    }
+}
+
+impl From<DOMTree<String>> for Content {
+    fn from(html: DOMTree<String>) -> Self {
+        Content::Html(html)
+    }
+}
+
+/// Writes the elements of a feed with an escaping policy.
+struct Writer<'a> {
+    f: &'a mut dyn fmt::Write,
+    escape: &'a dyn Escape,
+}
+
+impl<'a> Writer<'a> {
+    fn declaration(&mut self) -> fmt::Result {
+        self.f
+            .write_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>")
+    }
+
+    /// Write an opening tag, with the attributes which are set. The names
+    /// of attributes which hold URLs are given with a leading `@`.
+    fn open(&mut self, name: &str, attrs: &[(&str, Option<&str>)]) -> fmt::Result {
+        write!(self.f, "<{}", name)?;
+        self.attrs(attrs)?;
+        self.f.write_str(">")
+    }
+
+    /// Write an element with no content.
+    fn empty(&mut self, name: &str, attrs: &[(&str, Option<&str>)]) -> fmt::Result {
+        write!(self.f, "<{}", name)?;
+        self.attrs(attrs)?;
+        self.f.write_str("/>")
+    }
+
+    fn attrs(&mut self, attrs: &[(&str, Option<&str>)]) -> fmt::Result {
+        for (name, value) in attrs {
+            if let Some(value) = value {
+                match name.strip_prefix('@') {
+                    Some(name) => write!(self.f, " {}=\"{}\"", name, self.escape.url(value))?,
+                    None => write!(self.f, " {}=\"{}\"", name, self.escape.attribute(value))?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn text_content(&mut self, text: &str) -> fmt::Result {
+        self.f.write_str(&self.escape.text(text))
+    }
+
+    fn close(&mut self, name: &str) -> fmt::Result {
+        write!(self.f, "</{}>", name)
+    }
+
+    /// Write an element containing text or HTML, if there is any, with a
+    /// `type` attribute saying which if `typed` is set.
+    fn content(&mut self, name: &str, content: &Option<Content>, typed: bool) -> fmt::Result {
+        let (kind, text) = match content {
+            Some(Content::Text(text)) => ("text", self.escape.text(text)),
+            Some(Content::Html(html)) => {
+                let mut markup = String::new();
+                html.write_html(&mut markup, self.escape)?;
+                ("html", self.escape.text(&markup))
+            }
+            None => return Ok(()),
+        };
+        self.open(name, &[("type", Some(kind).filter(|_| typed))])?;
+        self.f.write_str(&text)?;
+        self.close(name)
+    }
+
+    /// Write an element containing a URL, if there is one, escaped as one.
+    fn url<S: AsRef<str>>(&mut self, name: &str, url: &Option<S>) -> fmt::Result {
+        match url {
+            Some(url) => write!(
+                self.f,
+                "<{}>{}</{}>",
+                name,
+                self.escape.url(url.as_ref()),
+                name
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Write an element containing text, if there is any.
+    fn text<S: AsRef<str>>(&mut self, name: &str, text: &Option<S>) -> fmt::Result {
+        match text {
+            Some(text) => write!(
+                self.f,
+                "<{}>{}</{}>",
+                name,
+                self.escape.text(text.as_ref()),
+                name
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_time() {
+        let time: DateTime = "2019-03-28T09:05:00Z".parse().unwrap();
+        assert_eq!("2019-03-28T09:05:00Z", time.to_string());
+        assert_eq!("Thu, 28 Mar 2019 09:05:00 GMT", time.rfc822());
+        let leap: DateTime = "2000-02-29T23:59:59Z".parse().unwrap();
+        assert_eq!("Tue, 29 Feb 2000 23:59:59 GMT", leap.rfc822());
+        for invalid in &[
+            "2019-03-28",
+            "2019-03-28T09:05Z",
+            "2019-03-28T09:05:00",
+            "2019-03-28T09:05:00+01:00",
+            "2019-03-28T24:00:00Z",
+            "2019-02-29T00:00:00Z",
+        ] {
+            assert!(invalid.parse::<DateTime>().is_err(), "{:?}", invalid);
+        }
+    }
+}
@@ -0,0 +1,348 @@
+//! Atom feeds.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::feed::atom::{Entry, Feed, Link, Person};
+//!
+//! # fn main() {
+//! let updated = "2019-03-28T12:00:00Z".parse().unwrap();
+//! let content: DOMTree<String> = html!(<p>"Cats are "<em>"great"</em></p>);
+//! let feed = Feed::new("urn:uuid:60a76c80", "Kitty News", updated)
+//!     .author(Person::new("Joe"))
+//!     .link(Link::new("https://example.com/feed").rel("self"))
+//!     .entry(
+//!         Entry::new("urn:uuid:1225c695", "Cats & dogs", updated)
+//!             .link(Link::new("https://example.com/1"))
+//!             .content(content),
+//!     );
+//! assert_eq!(
+//!     "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+//!      <feed xmlns=\"http&#x3A;&#x2F;&#x2F;www&#x2E;w3&#x2E;org&#x2F;2005&#x2F;Atom\">\
+//!      <id>urn:uuid:60a76c80</id><title>Kitty News</title>\
+//!      <updated>2019-03-28T12:00:00Z</updated><author><name>Joe</name></author>\
+//!      <link href=\"https&#x3A;&#x2F;&#x2F;example&#x2E;com&#x2F;feed\" rel=\"self\"/>\
+//!      <entry><id>urn:uuid:1225c695</id><title>Cats &amp; dogs</title>\
+//!      <updated>2019-03-28T12:00:00Z</updated>\
+//!      <link href=\"https&#x3A;&#x2F;&#x2F;example&#x2E;com&#x2F;1\"/>\
+//!      <content type=\"html\">&lt;p&gt;Cats are &lt;em&gt;great&lt;/em&gt;&lt;/p&gt;</content>\
+//!      </entry></feed>",
+//!     feed.to_string()
+//! );
+//! # }
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use super::{Content, DateTime, Writer};
+use crate::escape::{Escape, Html};
+use crate::types::Uri;
+
+/// An Atom feed.
+#[derive(Debug)]
+pub struct Feed {
+    id: String,
+    title: String,
+    updated: DateTime,
+    subtitle: Option<String>,
+    authors: Vec<Person>,
+    links: Vec<Link>,
+    entries: Vec<Entry>,
+}
+
+impl Feed {
+    /// Construct a feed with its permanent, unique identifier, its title,
+    /// and when it last changed.
+    pub fn new<I, T>(id: I, title: T, updated: DateTime) -> Self
+    where
+        I: Into<String>,
+        T: Into<String>,
+    {
+        Feed {
+            id: id.into(),
+            title: title.into(),
+            updated,
+            subtitle: None,
+            authors: Vec::new(),
+            links: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Set a description of the feed.
+    pub fn subtitle<S: Into<String>>(mut self, subtitle: S) -> Self {
+        self.subtitle = Some(subtitle.into());
+        self
+    }
+
+    /// Add an author of the feed, who is also the author of each entry
+    /// which doesn't have one.
+    pub fn author(mut self, author: Person) -> Self {
+        self.authors.push(author);
+        self
+    }
+
+    /// Add a link from the feed, usually to the website it's for and to
+    /// itself.
+    pub fn link(mut self, link: Link) -> Self {
+        self.links.push(link);
+        self
+    }
+
+    /// Add an entry to the end of the feed.
+    pub fn entry(mut self, entry: Entry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+}
+
+impl super::Feed for Feed {
+    fn write_xml(&self, f: &mut dyn fmt::Write, escape: &dyn Escape) -> fmt::Result {
+        let mut w = Writer { f, escape };
+        w.declaration()?;
+        w.open("feed", &[("@xmlns", Some("http://www.w3.org/2005/Atom"))])?;
+        w.text("id", &Some(&self.id))?;
+        w.text("title", &Some(&self.title))?;
+        w.text("subtitle", &self.subtitle)?;
+        w.text("updated", &Some(self.updated.to_string()))?;
+        for author in &self.authors {
+            author.write(&mut w)?;
+        }
+        for link in &self.links {
+            link.write(&mut w)?;
+        }
+        for entry in &self.entries {
+            entry.write(&mut w)?;
+        }
+        w.close("feed")
+    }
+}
+
+impl Display for Feed {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        super::Feed::write_xml(self, f, &Html)
+    }
+}
+
+/// An entry in an Atom feed.
+#[derive(Debug)]
+pub struct Entry {
+    id: String,
+    title: String,
+    updated: DateTime,
+    published: Option<DateTime>,
+    authors: Vec<Person>,
+    links: Vec<Link>,
+    categories: Vec<String>,
+    summary: Option<Content>,
+    content: Option<Content>,
+}
+
+impl Entry {
+    /// Construct an entry with its permanent, unique identifier, its title,
+    /// and when it last changed.
+    pub fn new<I, T>(id: I, title: T, updated: DateTime) -> Self
+    where
+        I: Into<String>,
+        T: Into<String>,
+    {
+        Entry {
+            id: id.into(),
+            title: title.into(),
+            updated,
+            published: None,
+            authors: Vec::new(),
+            links: Vec::new(),
+            categories: Vec::new(),
+            summary: None,
+            content: None,
+        }
+    }
+
+    /// Set when the entry was first published.
+    pub fn published(mut self, time: DateTime) -> Self {
+        self.published = Some(time);
+        self
+    }
+
+    /// Add an author of the entry.
+    pub fn author(mut self, author: Person) -> Self {
+        self.authors.push(author);
+        self
+    }
+
+    /// Add a link from the entry, usually to its page.
+    pub fn link(mut self, link: Link) -> Self {
+        self.links.push(link);
+        self
+    }
+
+    /// Add a category the entry is in.
+    pub fn category<C: Into<String>>(mut self, category: C) -> Self {
+        self.categories.push(category.into());
+        self
+    }
+
+    /// Set a summary of the entry, as text or HTML.
+    pub fn summary<C: Into<Content>>(mut self, summary: C) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Set the content of the entry, as text or HTML.
+    pub fn content<C: Into<Content>>(mut self, content: C) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    fn write(&self, w: &mut Writer) -> fmt::Result {
+        w.open("entry", &[])?;
+        w.text("id", &Some(&self.id))?;
+        w.text("title", &Some(&self.title))?;
+        w.text("updated", &Some(self.updated.to_string()))?;
+        w.text("published", &self.published.map(|time| time.to_string()))?;
+        for author in &self.authors {
+            author.write(w)?;
+        }
+        for link in &self.links {
+            link.write(w)?;
+        }
+        for category in &self.categories {
+            w.empty("category", &[("term", Some(category))])?;
+        }
+        w.content("summary", &self.summary, true)?;
+        w.content("content", &self.content, true)?;
+        w.close("entry")
+    }
+}
+
+/// A link from an Atom feed or entry.
+#[derive(Clone, Debug)]
+pub struct Link {
+    href: Uri,
+    rel: Option<String>,
+    type_: Option<String>,
+    hreflang: Option<String>,
+    title: Option<String>,
+}
+
+impl Link {
+    /// Construct a link to a URL.
+    pub fn new<H: Into<Uri>>(href: H) -> Self {
+        Link {
+            href: href.into(),
+            rel: None,
+            type_: None,
+            hreflang: None,
+            title: None,
+        }
+    }
+
+    /// Set how the link is related to the feed or entry, like `alternate`
+    /// for the page an entry is about, or `self` for the feed's own URL.
+    pub fn rel<R: Into<String>>(mut self, rel: R) -> Self {
+        self.rel = Some(rel.into());
+        self
+    }
+
+    /// Set the media type of what the link points to.
+    pub fn type_<T: Into<String>>(mut self, type_: T) -> Self {
+        self.type_ = Some(type_.into());
+        self
+    }
+
+    /// Set the language of what the link points to.
+    pub fn hreflang<L: Into<String>>(mut self, hreflang: L) -> Self {
+        self.hreflang = Some(hreflang.into());
+        self
+    }
+
+    /// Set a title for the link.
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    fn write(&self, w: &mut Writer) -> fmt::Result {
+        w.empty(
+            "link",
+            &[
+                ("@href", Some(&self.href)),
+                ("rel", self.rel.as_deref()),
+                ("type", self.type_.as_deref()),
+                ("hreflang", self.hreflang.as_deref()),
+                ("title", self.title.as_deref()),
+            ],
+        )
+    }
+}
+
+/// The author of an Atom feed or entry.
+#[derive(Clone, Debug)]
+pub struct Person {
+    name: String,
+    email: Option<String>,
+    uri: Option<Uri>,
+}
+
+impl Person {
+    /// Construct a person with their name.
+    pub fn new<N: Into<String>>(name: N) -> Self {
+        Person {
+            name: name.into(),
+            email: None,
+            uri: None,
+        }
+    }
+
+    /// Set the person's email address.
+    pub fn email<E: Into<String>>(mut self, email: E) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Set the URL of the person's home page.
+    pub fn uri<U: Into<Uri>>(mut self, uri: U) -> Self {
+        self.uri = Some(uri.into());
+        self
+    }
+
+    fn write(&self, w: &mut Writer) -> fmt::Result {
+        w.open("author", &[])?;
+        w.text("name", &Some(&self.name))?;
+        w.text("email", &self.email)?;
+        w.url("uri", &self.uri)?;
+        w.close("author")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::escape::{NonAscii, Serializer, Strict};
+
+    #[test]
+    fn test_feed_serializer() {
+        let updated = "2019-03-28T12:00:00Z".parse().unwrap();
+        let feed = Feed::new("urn:uuid:60a76c80", "Café", updated).entry(
+            Entry::new("tag:example.com,2019:1", "Hi", updated)
+                .link(Link::new("javascript:alert(1)")),
+        );
+        let xml = Serializer::new(Strict)
+            .non_ascii(NonAscii::Named)
+            .render_feed(&feed);
+        assert!(xml.contains("<title>Caf&#xE9;</title>"), "{}", xml);
+        assert!(
+            xml.contains("<id>tag&#x3A;example&#x2E;com&#x2C;2019&#x3A;1</id>"),
+            "{}",
+            xml
+        );
+        assert!(
+            xml.contains("<link href=\"about&#x3A;invalid\"/>"),
+            "{}",
+            xml
+        );
+    }
+}
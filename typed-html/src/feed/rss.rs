@@ -0,0 +1,207 @@
+//! RSS 2.0 channels.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::feed::rss::{Channel, Item};
+//!
+//! # fn main() {
+//! let published = "2019-03-28T12:00:00Z".parse().unwrap();
+//! let description: DOMTree<String> = html!(<p>"Cats are "<em>"great"</em></p>);
+//! let channel = Channel::new("Kitty News", "https://example.com/", "News about cats")
+//!     .item(
+//!         Item::new("Cats & dogs")
+//!             .link("https://example.com/1")
+//!             .description(description)
+//!             .pub_date(published),
+//!     );
+//! assert_eq!(
+//!     "<?xml version=\"1.0\" encoding=\"UTF-8\"?><rss version=\"2&#x2E;0\"><channel>\
+//!      <title>Kitty News</title><link>https&#x3A;&#x2F;&#x2F;example&#x2E;com&#x2F;</link>\
+//!      <description>News about cats</description>\
+//!      <item><title>Cats &amp; dogs</title>\
+//!      <link>https&#x3A;&#x2F;&#x2F;example&#x2E;com&#x2F;1</link>\
+//!      <description>&lt;p&gt;Cats are &lt;em&gt;great&lt;/em&gt;&lt;/p&gt;</description>\
+//!      <pubDate>Thu, 28 Mar 2019 12:00:00 GMT</pubDate></item>\
+//!      </channel></rss>",
+//!     channel.to_string()
+//! );
+//! # }
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use super::{Content, DateTime, Feed, Writer};
+use crate::escape::{Escape, Html};
+use crate::types::{LanguageTag, Uri};
+
+/// An RSS channel, the root of an RSS feed.
+#[derive(Debug)]
+pub struct Channel {
+    title: String,
+    link: Uri,
+    description: String,
+    language: Option<LanguageTag>,
+    pub_date: Option<DateTime>,
+    last_build_date: Option<DateTime>,
+    items: Vec<Item>,
+}
+
+impl Channel {
+    /// Construct a channel with its title, the URL of the website it's
+    /// for, and a description.
+    pub fn new<T, L, D>(title: T, link: L, description: D) -> Self
+    where
+        T: Into<String>,
+        L: Into<Uri>,
+        D: Into<String>,
+    {
+        Channel {
+            title: title.into(),
+            link: link.into(),
+            description: description.into(),
+            language: None,
+            pub_date: None,
+            last_build_date: None,
+            items: Vec::new(),
+        }
+    }
+
+    /// Set the language the channel is written in.
+    pub fn language(mut self, language: LanguageTag) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Set when the channel's content was published.
+    pub fn pub_date(mut self, time: DateTime) -> Self {
+        self.pub_date = Some(time);
+        self
+    }
+
+    /// Set when the channel's content last changed.
+    pub fn last_build_date(mut self, time: DateTime) -> Self {
+        self.last_build_date = Some(time);
+        self
+    }
+
+    /// Add an item to the end of the channel.
+    pub fn item(mut self, item: Item) -> Self {
+        self.items.push(item);
+        self
+    }
+}
+
+impl Feed for Channel {
+    fn write_xml(&self, f: &mut dyn fmt::Write, escape: &dyn Escape) -> fmt::Result {
+        let mut w = Writer { f, escape };
+        w.declaration()?;
+        w.open("rss", &[("version", Some("2.0"))])?;
+        w.open("channel", &[])?;
+        w.text("title", &Some(&self.title))?;
+        w.url("link", &Some(&self.link))?;
+        w.text("description", &Some(&self.description))?;
+        w.text("language", &self.language.as_ref().map(ToString::to_string))?;
+        w.text("pubDate", &self.pub_date.map(|time| time.rfc822()))?;
+        w.text(
+            "lastBuildDate",
+            &self.last_build_date.map(|time| time.rfc822()),
+        )?;
+        for item in &self.items {
+            item.write(&mut w)?;
+        }
+        w.close("channel")?;
+        w.close("rss")
+    }
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.write_xml(f, &Html)
+    }
+}
+
+/// A story in an RSS channel.
+#[derive(Debug)]
+pub struct Item {
+    title: String,
+    link: Option<Uri>,
+    description: Option<Content>,
+    author: Option<String>,
+    categories: Vec<String>,
+    guid: Option<(String, bool)>,
+    pub_date: Option<DateTime>,
+}
+
+impl Item {
+    /// Construct an item with its title.
+    pub fn new<T: Into<String>>(title: T) -> Self {
+        Item {
+            title: title.into(),
+            link: None,
+            description: None,
+            author: None,
+            categories: Vec::new(),
+            guid: None,
+            pub_date: None,
+        }
+    }
+
+    /// Set the URL of the item's page.
+    pub fn link<L: Into<Uri>>(mut self, link: L) -> Self {
+        self.link = Some(link.into());
+        self
+    }
+
+    /// Set the item's synopsis, as text or HTML.
+    pub fn description<C: Into<Content>>(mut self, description: C) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the email address of the item's author.
+    pub fn author<A: Into<String>>(mut self, author: A) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Add a category the item is in.
+    pub fn category<C: Into<String>>(mut self, category: C) -> Self {
+        self.categories.push(category.into());
+        self
+    }
+
+    /// Set a string which uniquely identifies the item, and whether it's
+    /// the URL of the item's page.
+    pub fn guid<G: Into<String>>(mut self, guid: G, is_permalink: bool) -> Self {
+        self.guid = Some((guid.into(), is_permalink));
+        self
+    }
+
+    /// Set when the item was published.
+    pub fn pub_date(mut self, time: DateTime) -> Self {
+        self.pub_date = Some(time);
+        self
+    }
+
+    fn write(&self, w: &mut Writer) -> fmt::Result {
+        w.open("item", &[])?;
+        w.text("title", &Some(&self.title))?;
+        w.url("link", &self.link)?;
+        w.content("description", &self.description, false)?;
+        w.text("author", &self.author)?;
+        for category in &self.categories {
+            w.text("category", &Some(category))?;
+        }
+        if let Some((guid, is_permalink)) = &self.guid {
+            let is_permalink = if *is_permalink { "true" } else { "false" };
+            w.open("guid", &[("isPermaLink", Some(is_permalink))])?;
+            w.text_content(guid)?;
+            w.close("guid")?;
+        }
+        w.text("pubDate", &self.pub_date.map(|time| time.rfc822()))?;
+        w.close("item")
+    }
+}
@@ -0,0 +1,192 @@
+//! SVG icon sprites.
+//!
+//! Register your icons once in an [`IconSet`][IconSet], then for each page,
+//! get a [`SpriteSheet`][SpriteSheet] from it. The sprite sheet hands out
+//! `<svg>` elements which reference an icon by ID, and keeps track of which
+//! icons have been used, so that it can emit a single hidden `<svg>` with
+//! one `<symbol>` for each of them at the end of the page.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::icons::IconSet;
+//!
+//! # fn main() {
+//! let mut icons = IconSet::new();
+//! icons.register("close", "0 0 24 24", r#"<path d="M6 6l12 12M18 6L6 18"/>"#);
+//!
+//! let mut sprites = icons.sprite_sheet();
+//! let doc: DOMTree<String> = html!(
+//!     <body>
+//!         <button>{ sprites.icon("close") }"Close"</button>
+//!         <button>{ sprites.icon("close") }"Also close"</button>
+//!         { sprites.sprite() }
+//!     </body>
+//! );
+//! # }
+//! ```
+//!
+//! [IconSet]: struct.IconSet.html
+//! [SpriteSheet]: struct.SpriteSheet.html
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::dom::UnsafeTextNode;
+use crate::elements::{svg, symbol, r#use};
+use crate::types::Id;
+use crate::OutputType;
+
+struct Icon {
+    id: Id,
+    view_box: String,
+    body: String,
+}
+
+/// A set of registered SVG icons.
+#[derive(Default)]
+pub struct IconSet {
+    icons: BTreeMap<String, Icon>,
+}
+
+impl IconSet {
+    /// Construct an empty icon set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an icon.
+    ///
+    /// The `view_box` is the value of the icon's `viewBox` attribute, and
+    /// `body` is the SVG markup inside it. The body is inserted into the page
+    /// without escaping, so it must come from a trusted source.
+    ///
+    /// Registering an icon under an ID which is already taken replaces the
+    /// previous icon.
+    pub fn register<I, V, B>(&mut self, id: I, view_box: V, body: B)
+    where
+        I: Into<Id>,
+        V: Into<String>,
+        B: Into<String>,
+    {
+        let id = id.into();
+        self.icons.insert(
+            id.to_string(),
+            Icon {
+                id,
+                view_box: view_box.into(),
+                body: body.into(),
+            },
+        );
+    }
+
+    /// Start a sprite sheet for a new page.
+    pub fn sprite_sheet(&self) -> SpriteSheet<'_> {
+        SpriteSheet {
+            icons: self,
+            used: BTreeSet::new(),
+        }
+    }
+}
+
+/// The icons used on a single page.
+///
+/// This is constructed by [`IconSet::sprite_sheet()`][sprite_sheet].
+///
+/// [sprite_sheet]: struct.IconSet.html#method.sprite_sheet
+pub struct SpriteSheet<'a> {
+    icons: &'a IconSet,
+    used: BTreeSet<&'a str>,
+}
+
+impl<'a> SpriteSheet<'a> {
+    /// Construct an `<svg>` element which refers to an icon, and record
+    /// that the icon needs to go in the sprite.
+    ///
+    /// Panics if no icon was registered under the given ID.
+    pub fn icon<T>(&mut self, id: &str) -> Box<svg<T>>
+    where
+        T: OutputType + 'static,
+    {
+        let icon = self.icons.icons.get(id).unwrap_or_else(|| {
+            panic!("typed_html::icons: no icon registered as {:?}", id)
+        });
+        self.used.insert(&icon.id);
+        let mut reference = r#use::new();
+        reference.attrs.href = Some(format!("#{}", id));
+        let mut element = svg::new();
+        element.children.push(Box::new(reference));
+        Box::new(element)
+    }
+
+    /// Construct a hidden `<svg>` element containing a `<symbol>` for each
+    /// icon used so far.
+    ///
+    /// This should go at the end of the page, after the last call to
+    /// [`icon()`][icon].
+    ///
+    /// [icon]: #method.icon
+    pub fn sprite<T>(&self) -> Box<svg<T>>
+    where
+        T: OutputType + 'static,
+    {
+        let mut sprite = svg::new();
+        sprite.attrs.style = Some("display: none".to_string());
+        for id in &self.used {
+            let icon = &self.icons.icons[*id];
+            let mut element = symbol::new();
+            element.attrs.id = Some(icon.id.clone());
+            element.attrs.viewBox = Some(icon.view_box.clone());
+            element
+                .children
+                .push(Box::new(UnsafeTextNode::new(icon.body.clone())));
+            sprite.children.push(Box::new(element));
+        }
+        Box::new(sprite)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    #[test]
+    fn test_sprite_sheet() {
+        let mut icons = IconSet::new();
+        icons.register("close", "0 0 24 24", "<path d=\"M6 6l12 12\"/>");
+        icons.register("menu", "0 0 16 16", "<path d=\"M0 8h16\"/>");
+        icons.register("unused", "0 0 8 8", "<circle r=\"4\"/>");
+
+        let mut sprites = icons.sprite_sheet();
+        let doc: DOMTree<String> = html!(
+            <div>
+                { sprites.icon("menu") }
+                { sprites.icon("close") }
+                { sprites.icon("menu") }
+                { sprites.sprite() }
+            </div>
+        );
+        assert_eq!(
+            "div { children: [\
+             svg { children: [use { href: \"#menu\" }] }, \
+             svg { children: [use { href: \"#close\" }] }, \
+             svg { children: [use { href: \"#menu\" }] }, \
+             svg { style: \"display: none\", children: [\
+             symbol { id: \"close\", viewBox: \"0 0 24 24\", \
+             children: [UnsafeText(\"<path d=\\\"M6 6l12 12\\\"/>\")] }, \
+             symbol { id: \"menu\", viewBox: \"0 0 16 16\", \
+             children: [UnsafeText(\"<path d=\\\"M0 8h16\\\"/>\")] }] }] }",
+            format!("{:?}", doc)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_unregistered_icon() {
+        let icons = IconSet::new();
+        let _: Box<svg<String>> = icons.sprite_sheet().icon("nope");
+    }
+}
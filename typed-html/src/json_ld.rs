@@ -0,0 +1,87 @@
+//! [JSON-LD] structured data.
+//!
+//! This module is only available with the `json_ld` feature flag enabled.
+//!
+//! [JSON-LD]: https://json-ld.org/
+
+use serde::Serialize;
+
+use crate::dom::UnsafeTextNode;
+use crate::elements::script;
+use crate::OutputType;
+
+/// Construct a `<script type="application/ld+json">` element containing a
+/// value serialised as JSON.
+///
+/// The contents of a `<script>` element aren't HTML escaped, so the JSON is
+/// escaped instead: `<`, `>` and `&` are written as Unicode escapes, which
+/// means a string in the data can't close the script element early.
+///
+/// Returns `Err` if the value can't be serialised.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// # use serde::Serialize;
+/// use typed_html::json_ld;
+///
+/// #[derive(Serialize)]
+/// struct Organization {
+///     #[serde(rename = "@context")]
+///     context: &'static str,
+///     #[serde(rename = "@type")]
+///     kind: &'static str,
+///     name: &'static str,
+/// }
+///
+/// # fn main() {
+/// let org = Organization {
+///     context: "https://schema.org",
+///     kind: "Organization",
+///     name: "Sanrio </script>",
+/// };
+/// let doc: DOMTree<String> = html!(
+///     <head>
+///         <title>"Hello Kitty"</title>
+///         { json_ld::script(&org).unwrap() }
+///     </head>
+/// );
+/// assert!(doc.to_string().contains(r#""name":"Sanrio \u003c/script\u003e""#));
+/// # }
+/// ```
+pub fn script<T, S>(value: &S) -> Result<Box<script<T>>, serde_json::Error>
+where
+    T: OutputType + 'static,
+    S: Serialize + ?Sized,
+{
+    let json = serde_json::to_string(value)?
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+        .replace('&', "\\u0026");
+    let mut element = script::new();
+    element.attrs.r#type = Some("application/ld+json".parse().unwrap());
+    element.children.push(Box::new(UnsafeTextNode::new(json)));
+    Ok(Box::new(element))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_json_ld_escaping() {
+        let mut value = BTreeMap::new();
+        value.insert("name", "Tom & Jerry </script><script>alert(1)</script>");
+        let element: Box<script<String>> = script(&value).unwrap();
+        assert_eq!(
+            "<script type=\"application&#x2F;ld&#x2B;json\">\
+             {\"name\":\"Tom \\u0026 Jerry \\u003c/script\\u003e\
+             \\u003cscript\\u003ealert(1)\\u003c/script\\u003e\"}\
+             </script>",
+            element.to_string()
+        );
+    }
+}
@@ -1,11 +1,11 @@
-#![recursion_limit = "128"]
+#![recursion_limit = "256"]
 //! This crate provides the `html!` macro for building HTML documents inside your
 //! Rust code using roughly [JSX] compatible syntax.
 //!
 //! # Quick Preview
 //!
 //! ```
-//! # #![recursion_limit = "128"]
+//! # #![recursion_limit = "256"]
 //! # use typed_html::html;
 //! # use typed_html::dom::{DOMTree, VNode};
 //! # use typed_html::types::Metadata;
@@ -44,6 +44,10 @@
 //!   handle outside string literals. So, instead of `<p>Hello</p>`, you need to
 //!   write `<p>"Hello"</p>`. (The parser will throw an error asking you to do this
 //!   if you forget.)
+//! * Character references like `&nbsp;` or `&#x2014;` in quoted text are
+//!   decoded into the characters they stand for, so `<p>"&copy; 2019"</p>`
+//!   renders as `<p>© 2019</p>`. Text which isn't quoted, like a
+//!   [`text!()`][text] block, is left alone.
 //! * Element attributes will accept simple Rust expressions, but the parser has
 //!   its limits, as it's not a full Rust parser. You can use literals,
 //!   variables, dotted properties, type constructors and single function or
@@ -51,6 +55,22 @@
 //!   handling, it will complain. You can put braces or parentheses around the
 //!   expression if the parser doesn't understand
 //!   it. You can use any Rust code inside a brace or parenthesis block.
+//! * Groups of attributes declared with [`mixin!`][mixin] can be added to an
+//!   element by writing `{..Name}` among its attributes.
+//! * Event handlers are attributes named after the event, written either as
+//!   `onclick` or as `on:click`. An `on={events}` attribute sets all of an
+//!   element's handlers at once from a pre-built `Events` value, and
+//!   individual handlers on the element override its own.
+//! * Vendor attributes declared with [`vendor!`][vendor], like `x:data` for
+//!   Alpine's `x-data`, can be set on any element.
+//! * Attributes which aren't checked at all can be added to an element with
+//!   `raw_attrs={...}`, given anything which iterates over pairs of a
+//!   `&'static str` name and a value. The values are escaped, but the names
+//!   are written as they are, for when a JavaScript library wants attributes
+//!   which aren't valid Rust, like `@click`. A name which HTML wouldn't
+//!   read as one, because it's empty or has a space, quote, `=`, `/` or `>`
+//!   in it, is a compile error if it's a string literal in the macro, like
+//!   `raw_attrs={vec![("@click", handler)]}`, and is left out otherwise.
 //!
 //! # Valid HTML5
 //!
@@ -60,6 +80,12 @@
 //! particular, are mostly omitted just for brevity, and you're welcome to implement
 //! them).
 //!
+//! The few obsolete elements and attributes which are still accepted, like
+//! `<marquee>` and `<param>`, produce a deprecation warning suggesting what to
+//! use instead. These go through the `deprecated` lint, so you can silence
+//! them with `#[allow(deprecated)]` on the function using them, or turn them
+//! into errors with `#![deny(deprecated)]`.
+//!
 //! The structure validation is simplistic by necessity, as it defers to the type
 //! system: a few elements will have one or more required children, and any element
 //! which accepts children will have a restriction on the type of the children,
@@ -133,6 +159,93 @@
 //! # ;}
 //! ```
 //!
+//! A block can also be a single expression followed by a `:` and a
+//! [format spec][fmt], like `{ price:.2 }` or `{ count:>5 }`, which is short
+//! for formatting it into a text node with [`text!()`][text].
+//!
+//! ## Example
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! # fn main() {
+//! let price = 4.5;
+//! let doc: DOMTree<String> = html!(
+//!     <p>"Total: $" { price:.2 }</p>
+//! );
+//! assert_eq!("<p>Total: $4.50</p>", doc.to_string());
+//! # }
+//! ```
+//!
+//! # Let Bindings
+//!
+//! A `let` statement in the child node position binds a value for the rest of
+//! the element's children, so you can compute something once and use it in
+//! several places. Because required children are built before the rest, a
+//! binding can't come before them.
+//!
+//! ## Example
+//!
+//! ```
+//! # use typed_html::{html, text};
+//! # use typed_html::dom::DOMTree;
+//! # fn main() {
+//! # let items = vec!["Joe", "Mike"];
+//! # let doc: DOMTree<String> =
+//! html!(
+//!     <div>
+//!         let count = items.len();
+//!         <h2>{ text!("{} items", count) }</h2>
+//!         <p>{ text!("Showing {} of {}", count, count) }</p>
+//!     </div>
+//! )
+//! # ;}
+//! ```
+//!
+//! # Maud Syntax
+//!
+//! The [`maud!`][maud] macro accepts the syntax of the [Maud] crate's `html!`
+//! macro, to make it easier to move templates over from Maud. It produces the
+//! same checked elements as `html!`, so a template which Maud accepts may
+//! still fail to compile if it isn't valid HTML5.
+//!
+//! * `div.card#main` is short for `div class="card" id="main"`.
+//! * Elements end with either a block of children or a `;`, like `br;`.
+//! * Attributes are written `name="value"` or `name=(expression)`. An attribute
+//!   with no value, like `checked`, is `true`, and `checked[done]` sets it to
+//!   the value of `done`.
+//! * `(expression)` in a child position is formatted with `Display` as a text
+//!   node.
+//! * `@if`, `@else`, `@for`, `@while` and `@let` work as they do in Maud, but
+//!   not at the top level or as a required child. `@match` isn't supported.
+//! * The macro must produce a single element at the top level, and its output
+//!   type can follow a `:`, as with `html!`.
+//!
+//! ## Example
+//!
+//! ```
+//! # use typed_html::maud;
+//! # use typed_html::dom::DOMTree;
+//! # fn main() {
+//! # let items = vec!["Joe", "Mike"];
+//! # let doc: DOMTree<String> =
+//! maud! {
+//!     div.card#people {
+//!         h2 { "People" }
+//!         @if items.is_empty() {
+//!             p.empty { "Nobody here." }
+//!         } @else {
+//!             ul {
+//!                 @for item in &items {
+//!                     li { (item) }
+//!                 }
+//!             }
+//!         }
+//!     }
+//! }
+//! # ;}
+//! ```
+//!
 //! # Rendering
 //!
 //! You have two options for actually producing something useful from the DOM tree
@@ -157,6 +270,10 @@
 //! # }
 //! ```
 //!
+//! Text and attribute values are escaped as they're rendered. To escape them
+//! differently, render the tree with an [`escape::Serializer`][Serializer]
+//! and your own [`Escape`][Escape] policy, or the stricter one provided.
+//!
 //! ## Render to a virtual DOM
 //!
 //! The DOM tree structure also implements a method called `vnode()`, which renders
@@ -165,6 +282,36 @@
 //! DOM tree and use it to build an actual DOM tree with `stdweb` or pass it on to
 //! your favourite virtual DOM system.
 //!
+//! # Feature Flags
+//!
+//! With no features enabled, typed-html is built for server side rendering
+//! only: event handlers are strings, rendered as `on*` attributes, and
+//! nothing web or wasm related is compiled. The browser backends are opt in:
+//!
+//! * `stdweb` adds the [`Stdweb`][Stdweb] output type, with event handlers as
+//!   closures that are attached to the DOM.
+//! * `dodrio_macro` adds the `dodrio!` macro and the Dodrio output type.
+//! * `feed` adds the [`feed`][feed] module, for writing RSS and Atom feeds
+//!   with the same escaping policies as HTML.
+//! * `compress` adds the [`compress`][compress] module, for gzip and brotli
+//!   compressed responses with fragments which were compressed ahead of time
+//!   spliced in.
+//! * `json_ld` adds the [`json_ld`][json_ld] module for embedding structured
+//!   data, which pulls in `serde` and `serde_json`.
+//! * `serde` makes the [`diff`][diff] module's patches serialisable, so they
+//!   can be sent to a browser.
+//! * `proptest` adds the [`proptest`][proptest] module, with generators of
+//!   random DOM trees for property testing.
+//! * `tide` adds the [`tide`][tide] module, which lets Tide endpoints return
+//!   DOM trees as responses.
+//! * `askama` and `tera` add the [`askama`][askama] and [`tera`][tera]
+//!   modules, for putting DOM trees into templates for those engines and
+//!   templates into DOM trees.
+//!
+//! If you only render to strings, leave the backends off and use
+//! [`DOMTree<String>`][DOMTree], so any attempt to use a closure as an event
+//! handler is a type error rather than something that fails at runtime.
+//!
 //! # Licence
 //!
 //! Copyright 2018 Bodil Stokke
@@ -181,6 +328,8 @@
 //! [coc]: https://www.contributor-covenant.org/version/1/4/code-of-conduct
 //! [JSX]: https://reactjs.org/docs/introducing-jsx.html
 //! [Display]: https://doc.rust-lang.org/std/fmt/trait.Display.html
+//! [Escape]: escape/trait.Escape.html
+//! [Serializer]: escape/struct.Serializer.html
 //! [String]: https://doc.rust-lang.org/std/string/struct.String.html
 //! [to_string]: https://doc.rust-lang.org/std/string/trait.ToString.html#tymethod.to_string
 //! [Node]: dom/trait.Node.html
@@ -191,6 +340,21 @@
 //! [Into]: https://doc.rust-lang.org/std/convert/trait.Into.html
 //! [Into::into]: https://doc.rust-lang.org/std/convert/trait.Into.html#method.into
 //! [DOMTree]: dom/type.DOMTree.html
+//! [mixin]: mixin/index.html
+//! [text]: macro.text.html
+//! [fmt]: https://doc.rust-lang.org/std/fmt/index.html#formatting-parameters
+//! [maud]: macro.maud.html
+//! [Maud]: https://maud.lambda.xyz/
+//! [vendor]: vendor/index.html
+//! [Stdweb]: output/stdweb/struct.Stdweb.html
+//! [json_ld]: json_ld/index.html
+//! [diff]: diff/index.html
+//! [proptest]: proptest/index.html
+//! [askama]: askama/index.html
+//! [tera]: tera/index.html
+//! [feed]: feed/index.html
+//! [compress]: compress/index.html
+//! [tide]: tide/index.html
 
 pub extern crate htmlescape;
 
@@ -200,15 +364,49 @@ use std::fmt::Display;
 #[proc_macro_hack(support_nested)]
 pub use typed_html_macros::html;
 
+#[proc_macro_hack(support_nested)]
+pub use typed_html_macros::maud;
+
 #[cfg(feature = "dodrio_macro")]
 #[proc_macro_hack(support_nested)]
 pub use typed_html_macros::dodrio;
 
+#[doc(hidden)]
+pub use typed_html_macros::declare_elements as __declare_elements;
+
+#[cfg(feature = "askama")]
+pub mod askama;
+pub mod attribute;
+pub mod boundary;
+#[cfg(feature = "compress")]
+pub mod compress;
+pub mod diff;
 pub mod dom;
 pub mod elements;
+pub mod escape;
 pub mod events;
+#[cfg(feature = "feed")]
+pub mod feed;
+pub mod icons;
+#[cfg(feature = "json_ld")]
+pub mod json_ld;
+pub mod memo;
+pub mod mixin;
 pub mod output;
+#[cfg(feature = "proptest")]
+pub mod proptest;
+pub mod sax;
+pub mod selector;
+pub mod stream;
+pub mod suspense;
+#[cfg(feature = "tera")]
+pub mod tera;
+#[cfg(feature = "tide")]
+pub mod tide;
+pub mod transform;
 pub mod types;
+pub mod validate;
+pub mod vendor;
 
 /// Marker trait for outputs
 pub trait OutputType {
@@ -0,0 +1,242 @@
+//! Reusing rendered subtrees when the data they're built from hasn't
+//! changed.
+//!
+//! A [`MemoCache`][MemoCache] renders a subtree inside a closure, and keeps
+//! the HTML it rendered under a hash of a key, which is the data the subtree
+//! is built from. The next time it's asked for a subtree with the same key,
+//! it skips the closure and returns the HTML it kept.
+//!
+//! The kept HTML is shared rather than copied, so when a page is rendered
+//! again and [`diff()`][diff]ed against the last render, memoized subtrees
+//! which haven't changed are recognised as the same without being compared,
+//! and produce no patches.
+//!
+//! Subtrees are rendered to strings with the standard [`Html`][Html]
+//! escaping policy, so this is for server side rendering only.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::{html, text};
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::memo::MemoCache;
+//!
+//! # fn main() {
+//! let cache = MemoCache::new();
+//! let render = |names: &[&str]| -> DOMTree<String> {
+//!     html!(
+//!         <div>
+//!             { names.iter().map(|name| cache.memo(name, || html!(<p>{ text!(*name) }</p>))) }
+//!         </div>
+//!     )
+//! };
+//! assert_eq!("<div><p>Joe</p><p>Mike</p></div>", render(&["Joe", "Mike"]).to_string());
+//! // Only Robert's paragraph is built this time.
+//! assert_eq!(
+//!     "<div><p>Joe</p><p>Robert</p></div>",
+//!     render(&["Joe", "Robert"]).to_string()
+//! );
+//! # }
+//! ```
+//!
+//! A memoized subtree can go anywhere an [`unsafe_text!`][unsafe_text] node
+//! can, which is in elements that accept flow or phrasing content.
+//!
+//! The cache only looks at the key, so each kind of subtree needs its own
+//! cache, and the key has to include everything the subtree is built from.
+//!
+//! [MemoCache]: struct.MemoCache.html
+//! [diff]: ../diff/fn.diff.html
+//! [Html]: ../escape/struct.Html.html
+//! [unsafe_text]: ../macro.unsafe_text.html
+
+use std::cell::RefCell;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::mem;
+use std::rc::Rc;
+
+use crate::dom::{Node, VNode, Visit};
+use crate::elements::{FlowContent, PhrasingContent};
+use crate::escape::Escape;
+use crate::transform::Transform;
+
+/// A cache of rendered subtrees, keyed by the data they were built from.
+///
+/// The cache is shared by reference, so it can be used in several places in
+/// the same `html!` invocation.
+///
+/// Subtrees are kept until they go unused for a whole
+/// [`sweep()`][sweep] period, so a cache which is used for every render of a
+/// page only holds on to what the page still shows.
+///
+/// [sweep]: #method.sweep
+#[derive(Default)]
+pub struct MemoCache {
+    /// Subtrees which have been used since the last sweep.
+    current: RefCell<HashMap<u64, Rc<str>>>,
+    /// Subtrees which were used before the last sweep, but not since.
+    previous: RefCell<HashMap<u64, Rc<str>>>,
+}
+
+impl MemoCache {
+    /// Construct an empty cache.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Return the subtree which was rendered for `key` before, or build and
+    /// render it if there isn't one.
+    pub fn memo<K, F, N>(&self, key: K, build: F) -> Box<Memoized>
+    where
+        K: Hash,
+        F: FnOnce() -> Box<N>,
+        N: Node<String> + ?Sized,
+    {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let found = self.current.borrow().get(&hash).cloned();
+        let html = match found.or_else(|| self.previous.borrow_mut().remove(&hash)) {
+            Some(html) => html,
+            None => build().to_string().into(),
+        };
+        self.current.borrow_mut().insert(hash, html.clone());
+        Box::new(Memoized(html))
+    }
+
+    /// Drop the subtrees which haven't been used since the last sweep.
+    ///
+    /// Call this after each render of a page, so subtrees for data which is
+    /// no longer shown don't pile up.
+    pub fn sweep(&self) {
+        let current = mem::take(&mut *self.current.borrow_mut());
+        *self.previous.borrow_mut() = current;
+    }
+
+    /// The number of subtrees in the cache.
+    pub fn len(&self) -> usize {
+        self.current.borrow().len() + self.previous.borrow().len()
+    }
+
+    /// Whether the cache is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Debug for MemoCache {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("MemoCache")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// A subtree from a [`MemoCache`][MemoCache], as the HTML it was rendered
+/// to.
+///
+/// It's a [`VNode::UnsafeText`][UnsafeText] in a `VNode` tree, which points
+/// at the HTML the cache holds, so the same subtree from two renders is the
+/// same string.
+///
+/// [MemoCache]: struct.MemoCache.html
+/// [UnsafeText]: ../dom/enum.VNode.html#variant.UnsafeText
+pub struct Memoized(Rc<str>);
+
+impl Display for Memoized {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Debug for Memoized {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_tuple("Memoized").field(&&*self.0).finish()
+    }
+}
+
+impl Node<String> for Memoized {
+    fn vnode(&'_ mut self) -> VNode<'_, String> {
+        VNode::UnsafeText(&self.0)
+    }
+
+    fn apply(&mut self, transform: &mut dyn Transform) -> bool {
+        // A transform gets its own copy, so the cached subtree is left alone.
+        let mut html = self.0.to_string();
+        let keep = transform.unsafe_text(&mut html);
+        if *html != *self.0 {
+            self.0 = html.into();
+        }
+        keep
+    }
+
+    fn write_html(&self, f: &mut dyn fmt::Write, _escape: &dyn Escape) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+
+    fn visit(&self, visitor: &mut dyn Visit) {
+        visitor.text(&self.0)
+    }
+}
+
+impl IntoIterator for Box<Memoized> {
+    type Item = Box<Memoized>;
+    type IntoIter = std::vec::IntoIter<Box<Memoized>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        vec![self].into_iter()
+    }
+}
+
+impl FlowContent<String> for Memoized {}
+impl PhrasingContent<String> for Memoized {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use crate::diff::{diff, Patch, PatchNode};
+    use crate::dom::DOMTree;
+    use std::cell::Cell;
+
+    #[test]
+    fn test_memo_reuses_output() {
+        let cache = MemoCache::new();
+        let builds = Cell::new(0);
+        let render = |items: &[(u32, &str)]| -> DOMTree<String> {
+            html!(
+                <div>
+                    { items.iter().map(|item| cache.memo(item, || {
+                        builds.set(builds.get() + 1);
+                        html!(<p>{ crate::text!(item.1) }</p>)
+                    })) }
+                </div>
+            )
+        };
+
+        let mut old = render(&[(1, "One"), (2, "Two")]);
+        assert_eq!(2, builds.get());
+        let mut new = render(&[(1, "One"), (2, "Deux")]);
+        assert_eq!(3, builds.get());
+        assert_eq!("<div><p>One</p><p>Deux</p></div>", new.to_string());
+        assert_eq!(
+            vec![Patch::Replace {
+                path: vec![1],
+                node: PatchNode::Html("<p>Deux</p>".to_string()),
+            }],
+            diff(&old.vnode(), &new.vnode())
+        );
+
+        // Only the subtrees used since the last sweep survive the next one.
+        cache.sweep();
+        render(&[(1, "One")]);
+        assert_eq!(3, builds.get());
+        assert_eq!(3, cache.len());
+        cache.sweep();
+        assert_eq!(1, cache.len());
+    }
+}
@@ -0,0 +1,133 @@
+//! Reusable groups of attributes.
+//!
+//! The [`mixin!`][mixin] macro declares a named group of attributes, which you
+//! can then add to elements in the [`html!`][html] macro by writing
+//! `{..Name}` among their attributes:
+//!
+//! ```
+//! # use typed_html::{html, mixin};
+//! # use typed_html::dom::DOMTree;
+//! # fn main() {
+//! mixin! {
+//!     ExternalLink {
+//!         target: "_blank",
+//!         rel: "noopener noreferrer",
+//!     }
+//! }
+//!
+//! let doc: DOMTree<String> = html!(
+//!     <a href="https://example.com/" {..ExternalLink}>"Example"</a>
+//! );
+//! # }
+//! ```
+//!
+//! The attributes are type checked against each element the mixin is used on,
+//! just like attributes written on the element, so using `ExternalLink` on a
+//! `<div>` won't compile. Neither will using it on a link which sets its own
+//! `target` or `rel`, because it wouldn't be clear which one should win.
+//!
+//! Attribute values are either literals, which are parsed like string literals
+//! in the `html!` macro, or blocks, which are converted with
+//! [`Into::into`][Into::into].
+//!
+//! The mixin is a `macro_rules!` macro under the hood, so it follows the same
+//! scoping rules: declare it before you use it, or put `#[macro_export]` on it
+//! to use it anywhere.
+//!
+//! [mixin]: ../macro.mixin.html
+//! [html]: ../macro.html.html
+//! [Into::into]: https://doc.rust-lang.org/std/convert/trait.Into.html#method.into
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Declare a reusable group of attributes.
+///
+/// See the [`mixin`][mixin] module for details.
+///
+/// [mixin]: mixin/index.html
+#[macro_export]
+macro_rules! mixin {
+    ($($(#[$meta:meta])* $name:ident { $($key:ident : $value:tt),* $(,)* })*) => {
+        $(
+            $crate::mixin!(@declare ($) $(#[$meta])* $name { $($key : $value),* });
+        )*
+    };
+    (@declare ($d:tt) $(#[$meta:meta])* $name:ident { $($key:ident : $value:tt),* }) => {
+        $(#[$meta])*
+        macro_rules! $name {
+            ($d element:ident, [$d ($d explicit:expr),*]) => {
+                const _: () = $crate::mixin::check_conflicts(
+                    &[$(stringify!($key)),*],
+                    &[$d ($d explicit),*],
+                );
+                $(
+                    $d element.attrs.$key = Some($crate::mixin!(@value $value));
+                )*
+            };
+        }
+    };
+    (@value $value:block) => {
+        std::convert::Into::into($value)
+    };
+    (@value $value:literal) => {
+        $crate::mixin::parse_literal($value)
+    };
+}
+
+/// Parse a literal attribute value from a mixin.
+#[doc(hidden)]
+pub fn parse_literal<L: Display, T: FromStr>(literal: L) -> T
+where
+    T::Err: Display,
+{
+    let literal = literal.to_string();
+    literal.parse().unwrap_or_else(|err| {
+        panic!(
+            "failed to parse mixin attribute value {:?}: {}",
+            literal, err
+        )
+    })
+}
+
+/// Fail to compile if a mixin sets an attribute which is also written on the
+/// element.
+#[doc(hidden)]
+pub const fn check_conflicts(mixin: &[&str], explicit: &[&str]) {
+    let mut i = 0;
+    while i < mixin.len() {
+        let mut j = 0;
+        while j < explicit.len() {
+            if attr_name_eq(mixin[i].as_bytes(), explicit[j].as_bytes()) {
+                panic!("a mixin sets an attribute which is already set on the element");
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
+/// Compare attribute names, ignoring the `r#` of raw identifiers.
+const fn attr_name_eq(left: &[u8], right: &[u8]) -> bool {
+    let left = strip_raw(left);
+    let right = strip_raw(right);
+    if left.len() != right.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < left.len() {
+        if left[index] != right[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+const fn strip_raw(name: &[u8]) -> &[u8] {
+    if name.len() > 2 && name[0] == b'r' && name[1] == b'#' {
+        name.split_at(2).1
+    } else {
+        name
+    }
+}
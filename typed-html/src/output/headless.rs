@@ -0,0 +1,341 @@
+//! Headless output for testing event handlers without a browser.
+//!
+//! The [`Headless`][Headless] output type takes closures as event handlers,
+//! like the stdweb output, and [`dispatch()`][dispatch] fires a simulated
+//! [`Event`][Event] at the element a [selector][selector] finds. The event
+//! bubbles up through the element's ancestors the way it would in a browser,
+//! so you can check that the right handlers are wired to the right elements
+//! in an ordinary unit test.
+//!
+//! ```
+//! # #![recursion_limit = "256"]
+//! use std::cell::RefCell;
+//! use std::rc::Rc;
+//! use typed_html::dom::DOMTree;
+//! use typed_html::html;
+//! use typed_html::output::headless::{dispatch, Event, Headless};
+//!
+//! # fn main() {
+//! let messages = Rc::new(RefCell::new(Vec::new()));
+//! let (on_input, on_click) = (messages.clone(), messages.clone());
+//! let mut doc: DOMTree<Headless> = html!(
+//!     <form>
+//!         <input type="text" name="q" oninput={ move |event: &mut Event| {
+//!             on_input.borrow_mut().push(format!("typed {}", event.value().unwrap()));
+//!         } }/>
+//!         <button class="go" onclick={ move |event: &mut Event| {
+//!             event.prevent_default();
+//!             on_click.borrow_mut().push("clicked".to_string());
+//!         } }>"Go"</button>
+//!     </form>
+//! : Headless);
+//!
+//! dispatch(&mut *doc, "input[name=q]", Event::input("kitty")).unwrap();
+//! let clicked = dispatch(&mut *doc, "form .go", Event::click()).unwrap();
+//! assert!(clicked.default_prevented());
+//! assert_eq!(vec!["typed kitty", "clicked"], *messages.borrow());
+//! # }
+//! ```
+//!
+//! Rendered as a string, handlers are empty `on*` attributes.
+//!
+//! [Headless]: struct.Headless.html
+//! [dispatch]: fn.dispatch.html
+//! [Event]: struct.Event.html
+//! [selector]: ../../selector/index.html
+
+use std::error;
+use std::fmt::{Display, Error, Formatter};
+
+use crate::dom::{Node, VElement, VNode};
+use crate::events::{self, BindError, Binding, Key};
+use crate::selector::{Ancestor, Selectable, Selector, SelectorError};
+use crate::OutputType;
+
+/// Headless output, with closures as event handlers which are called by
+/// [`dispatch()`][dispatch].
+///
+/// [dispatch]: fn.dispatch.html
+pub struct Headless;
+impl OutputType for Headless {
+    type Events = events::Events<Handler>;
+    type EventTarget = ();
+    type EventListenerHandle = ();
+}
+
+/// An event handler for the [`Headless`][Headless] output.
+///
+/// Any closure taking a `&mut Event` converts into one.
+///
+/// [Headless]: struct.Headless.html
+pub struct Handler(Box<dyn FnMut(&mut Event)>);
+
+impl<F> From<F> for Handler
+where
+    F: FnMut(&mut Event) + 'static,
+{
+    fn from(f: F) -> Self {
+        Handler(Box::new(f))
+    }
+}
+
+impl<V: 'static> From<Binding<V>> for Handler {
+    fn from(mut binding: Binding<V>) -> Self {
+        Handler(Box::new(move |event: &mut Event| {
+            let value = event.value().unwrap_or_default().to_string();
+            if let Err(err) = binding.handle(&value) {
+                event.bind_error = Some(err);
+            }
+        }))
+    }
+}
+
+impl Display for Handler {
+    fn fmt(&self, _: &mut Formatter) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// A simulated event.
+#[derive(Clone, Debug)]
+pub struct Event {
+    name: String,
+    value: Option<String>,
+    key: Option<Key>,
+    target: Vec<(&'static str, String)>,
+    bind_error: Option<BindError>,
+    default_prevented: bool,
+    propagation_stopped: bool,
+}
+
+impl Event {
+    /// Construct an event with the given name, like `"click"`.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        Event {
+            name: name.into(),
+            value: None,
+            key: None,
+            target: Vec::new(),
+            bind_error: None,
+            default_prevented: false,
+            propagation_stopped: false,
+        }
+    }
+
+    /// A `click` event.
+    pub fn click() -> Self {
+        Event::new("click")
+    }
+
+    /// A `submit` event.
+    pub fn submit() -> Self {
+        Event::new("submit")
+    }
+
+    /// An `input` event, for a form control whose value is now `value`.
+    pub fn input<S: Into<String>>(value: S) -> Self {
+        Event::new("input").with_value(value)
+    }
+
+    /// A `change` event, for a form control whose value is now `value`.
+    pub fn change<S: Into<String>>(value: S) -> Self {
+        Event::new("change").with_value(value)
+    }
+
+    /// A `keydown` event for the given key.
+    pub fn keydown(key: Key) -> Self {
+        let mut event = Event::new("keydown");
+        event.key = Some(key);
+        event
+    }
+
+    /// Set the value of the event's target.
+    pub fn with_value<S: Into<String>>(mut self, value: S) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// The name of the event, like `"click"`.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The value of the event's target, for `input` and `change` events.
+    pub fn value(&self) -> Option<&str> {
+        self.value.as_deref()
+    }
+
+    /// The key, for keyboard events.
+    pub fn key(&self) -> Option<&Key> {
+        self.key.as_ref()
+    }
+
+    /// An attribute of the element the event was dispatched to.
+    pub fn target_attribute(&self, name: &str) -> Option<&str> {
+        self.target
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The value a [`bind()`][bind] handler without an error handler
+    /// couldn't parse, if there was one.
+    ///
+    /// [bind]: ../../events/fn.bind.html
+    pub fn bind_error(&self) -> Option<&BindError> {
+        self.bind_error.as_ref()
+    }
+
+    /// Stop the browser's default action for the event.
+    pub fn prevent_default(&mut self) {
+        self.default_prevented = true;
+    }
+
+    /// Whether a handler called [`prevent_default()`][prevent_default].
+    ///
+    /// [prevent_default]: #method.prevent_default
+    pub fn default_prevented(&self) -> bool {
+        self.default_prevented
+    }
+
+    /// Stop the event from bubbling up to the ancestors of the element
+    /// handling it.
+    pub fn stop_propagation(&mut self) {
+        self.propagation_stopped = true;
+    }
+}
+
+/// An error from [`dispatch()`][dispatch].
+///
+/// [dispatch]: fn.dispatch.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DispatchError {
+    /// The selector couldn't be parsed.
+    Selector(SelectorError),
+    /// No element in the tree matches the selector.
+    NoMatch(String),
+}
+
+impl Display for DispatchError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            DispatchError::Selector(err) => Display::fmt(err, f),
+            DispatchError::NoMatch(selector) => {
+                write!(f, "no element matches selector {:?}", selector)
+            }
+        }
+    }
+}
+
+impl error::Error for DispatchError {}
+
+/// Dispatch an event to the first element in the tree, in document order,
+/// which matches `selector`, and then to each of its ancestors in turn until
+/// a handler stops its propagation.
+///
+/// Returns the event as the handlers have left it.
+pub fn dispatch(
+    tree: &mut dyn Node<Headless>,
+    selector: &str,
+    mut event: Event,
+) -> Result<Event, DispatchError> {
+    let parsed: Selector = selector.parse().map_err(DispatchError::Selector)?;
+    let mut vnode = tree.vnode();
+    if visit(&mut vnode, &parsed, &mut Vec::new(), &mut event) {
+        Ok(event)
+    } else {
+        Err(DispatchError::NoMatch(selector.to_string()))
+    }
+}
+
+/// Look for the target in this node, and call this node's handler on the
+/// way back up if it was found.
+fn visit<'a>(
+    node: &'a mut VNode<'_, Headless>,
+    selector: &Selector,
+    ancestors: &mut Vec<Ancestor<'a>>,
+    event: &mut Event,
+) -> bool {
+    let VElement {
+        name,
+        attributes,
+        events,
+        children,
+    } = match node {
+        VNode::Element(element) => element,
+        _ => return false,
+    };
+    let this = Ancestor {
+        name,
+        attributes: &attributes[..],
+    };
+    let found = {
+        let ancestor_refs: Vec<&dyn Selectable> =
+            ancestors.iter().map(|a| a as &dyn Selectable).collect();
+        if selector.matches(&this, &ancestor_refs) {
+            event.target = attributes.clone();
+            true
+        } else {
+            false
+        }
+    };
+    let found = found || {
+        ancestors.push(this);
+        let found = children
+            .iter_mut()
+            .any(|child| visit(child, selector, ancestors, event));
+        ancestors.pop();
+        found
+    };
+    if found && !event.propagation_stopped {
+        for (event_name, handler) in events.iter_mut() {
+            if event.name == event_name {
+                (handler.0)(event);
+            }
+        }
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_dispatch_bubbles() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let handler = |name: &'static str, stop: bool| {
+            let log = log.clone();
+            move |event: &mut Event| {
+                log.borrow_mut()
+                    .push(format!("{} {:?}", name, event.target_attribute("id")));
+                if stop {
+                    event.stop_propagation();
+                }
+            }
+        };
+        let mut doc: DOMTree<Headless> = html!(
+            <div onclick={handler("div", false)}>
+                <p onclick={handler("p", true)}>
+                    <span id="a">"A"</span>
+                </p>
+                <span id="b" onclick={handler("span", false)}>"B"</span>
+            </div>
+        : Headless);
+
+        dispatch(&mut *doc, "p span", Event::click()).unwrap();
+        dispatch(&mut *doc, "div > span", Event::click()).unwrap();
+        assert_eq!(
+            vec!["p Some(\"a\")", "span Some(\"b\")", "div Some(\"b\")"],
+            *log.borrow()
+        );
+        assert_eq!(
+            DispatchError::NoMatch("em".to_string()),
+            dispatch(&mut *doc, "em", Event::click()).unwrap_err()
+        );
+    }
+}
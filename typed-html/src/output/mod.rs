@@ -1,3 +1,4 @@
+pub mod headless;
 #[cfg(feature = "stdweb")]
 pub mod stdweb;
 #[cfg(feature = "dodrio_macro")]
@@ -1,19 +1,149 @@
-use std::fmt::{Display, Error, Formatter};
+//! DOM output using the stdweb crate.
+//!
+//! Event handlers are closures taking the stdweb event type for the event,
+//! like `ClickEvent` for `onclick`. They're `FnMut`s, so a handler can keep
+//! its own state:
+//!
+//! ```no_run
+//! # #![recursion_limit = "256"]
+//! use typed_html::dom::DOMTree;
+//! use typed_html::html;
+//! use typed_html::output::stdweb::Stdweb;
+//!
+//! # fn main() {
+//! let mut clicks = 0;
+//! let doc: DOMTree<Stdweb> = html!(
+//!     <button onclick={ move |_| {
+//!         clicks += 1;
+//!         stdweb::console!(log, format!("Clicked {} times", clicks));
+//!     } }>"Click me"</button>
+//! : Stdweb);
+//! # }
+//! ```
+//!
+//! The traits in `stdweb::traits` give access
+//! to the event's properties: for instance, the drag events implement
+//! `IDragEvent`, whose `data_transfer()` returns the `DataTransfer` holding
+//! the dragged data.
+//!
+//! ```no_run
+//! # #![recursion_limit = "256"]
+//! use stdweb::traits::*;
+//! use typed_html::dom::DOMTree;
+//! use typed_html::html;
+//! use typed_html::output::stdweb::Stdweb;
+//!
+//! # fn main() {
+//! let doc: DOMTree<Stdweb> = html!(
+//!     <div>
+//!         <p draggable=true ondragstart={ |event: stdweb::web::event::DragStartEvent| {
+//!             if let Some(data) = event.data_transfer() {
+//!                 data.set_data("text/plain", "Hello Joe!");
+//!             }
+//!         } }>"Drag me"</p>
+//!         <div ondragover={ |event: stdweb::web::event::DragOverEvent| event.prevent_default() }
+//!              ondrop={ |event: stdweb::web::event::DragDropEvent| {
+//!             event.prevent_default();
+//!             if let Some(data) = event.data_transfer() {
+//!                 stdweb::web::alert(&data.get_data("text/plain"));
+//!             }
+//!         } }>"Drop here"</div>
+//!     </div>
+//! : Stdweb);
+//! # }
+//! ```
+//!
+//! [`bind()`][bind] makes a handler which reads the value of the event's
+//! target and parses it into the type the closure takes:
+//!
+//! ```no_run
+//! # #![recursion_limit = "256"]
+//! use typed_html::dom::DOMTree;
+//! use typed_html::events::bind;
+//! use typed_html::html;
+//! use typed_html::output::stdweb::Stdweb;
+//! use typed_html::types::Date;
+//!
+//! # fn main() {
+//! let doc: DOMTree<Stdweb> = html!(
+//!     <form>
+//!         <input type="number" oninput={ bind(|amount: f64| {
+//!             stdweb::console!(log, amount);
+//!         }) }/>
+//!         <input type="date" onchange={ bind(|date: Date| {
+//!             stdweb::console!(log, date.to_string());
+//!         }) }/>
+//!     </form>
+//! : Stdweb);
+//! # }
+//! ```
+//!
+//! [bind]: ../../events/fn.bind.html
+
+use std::cell::RefCell;
+use std::error;
+use std::fmt::{self, Debug, Display, Error, Formatter};
 use std::marker::PhantomData;
 
 use stdweb::web::event::*;
-use stdweb::web::{self, Element, EventListenerHandle, IElement, IEventTarget, INode};
+use stdweb::unstable::TryInto;
+use stdweb::web::{self, Element, EventListenerHandle, IElement, IEventTarget, INode, IParentNode};
+use stdweb::{js, Mut, Reference, __js_raw_asm_bool};
+use stdweb_derive::ReferenceType;
 
 use crate::OutputType;
-use crate::dom::VNode;
-use crate::events::EventHandler;
+use crate::diff::{attribute_changes, same_element, Patch, PatchNode};
+use crate::dom::{DOMTree, Node, VElement, VNode};
+use crate::elements::{FlowContent, PhrasingContent};
+use crate::escape::{Escape, Html};
+use crate::events::{Binding, EventHandler, Key};
+use crate::transform::Transform;
 
 /// DOM output using the stdweb crate
 pub struct Stdweb;
 impl OutputType for Stdweb {
     type Events = Events;
     type EventTarget = Element;
-    type EventListenerHandle = EventListenerHandle;
+    type EventListenerHandle = ListenerHandle;
+}
+
+/// A handle to an event listener attached by an event handler, which can be
+/// used to remove it again.
+pub struct ListenerHandle(Handle);
+
+enum Handle {
+    Stdweb(EventListenerHandle),
+    Passive {
+        event_type: &'static str,
+        target: Reference,
+        listener: Reference,
+    },
+}
+
+impl ListenerHandle {
+    /// Remove the event listener from the element it was attached to.
+    pub fn remove(self) {
+        match self.0 {
+            Handle::Stdweb(handle) => handle.remove(),
+            Handle::Passive {
+                event_type,
+                target,
+                listener,
+            } => {
+                js! { @(no_return)
+                    var listener = @{listener};
+                    @{target}.removeEventListener(@{event_type}, listener);
+                    listener.drop();
+                }
+            }
+        }
+    }
+}
+
+impl From<EventListenerHandle> for ListenerHandle {
+    fn from(handle: EventListenerHandle) -> Self {
+        ListenerHandle(Handle::Stdweb(handle))
+    }
 }
 
 macro_rules! declare_events {
@@ -23,6 +153,13 @@ macro_rules! declare_events {
             $(
                 pub $name: Option<Box<dyn EventHandler<Stdweb, $type>>>,
             )*
+            /// The listeners which [`Stdweb::build()`][build] attached for
+            /// these handlers, by event name, so
+            /// [`Stdweb::update()`][update] can remove them again.
+            ///
+            /// [build]: struct.Stdweb.html#method.build
+            /// [update]: struct.Stdweb.html#method.update
+            pub listeners: Vec<(&'static str, ListenerHandle)>,
         }
 
         impl Default for Events {
@@ -31,10 +168,26 @@ macro_rules! declare_events {
                     $(
                         $name: None,
                     )*
+                    listeners: Vec::new(),
                 }
             }
         }
 
+        impl Events {
+            /// Whether any handlers are set.
+            fn has_handlers(&self) -> bool {
+                false $(|| self.$name.is_some())*
+            }
+
+            fn attach(&mut self, target: &mut Element) {
+                $(
+                    if let Some(ref mut handler) = self.$name {
+                        self.listeners.push((stringify!($name), handler.attach(target)));
+                    }
+                )*
+            }
+        }
+
         /// Iterate over the defined events on a DOM object.
         #[macro_export]
         macro_rules! for_events {
@@ -47,6 +200,149 @@ macro_rules! declare_events {
     }
 }
 
+macro_rules! declare_concrete_events {
+    ($($interface:literal {
+        $($(#[$attr:meta])* $name:ident = $event_type:literal,)*
+    })*) => {
+        $($(
+            $(#[$attr])*
+            #[derive(Clone, Debug, PartialEq, Eq, ReferenceType)]
+            #[reference(instance_of = $interface)]
+            #[reference(event = $event_type)]
+            pub struct $name(Reference);
+
+            impl IEvent for $name {}
+        )*)*
+    }
+}
+
+/// Typed keys for keyboard events.
+pub trait TypedKeyboardEvent: IKeyboardEvent {
+    /// The key for this event, as a [`Key`][Key] instead of a string.
+    ///
+    /// [Key]: ../../events/enum.Key.html
+    fn typed_key(&self) -> Key {
+        Key::from(self.key().as_str())
+    }
+}
+
+impl<E: IKeyboardEvent> TypedKeyboardEvent for E {}
+
+/// The `ClipboardEvent` interface, for the `copy`, `cut` and `paste` events.
+pub trait IClipboardEvent: IEvent {
+    /// The data on the clipboard, which a `paste` handler can read and a
+    /// `copy` or `cut` handler can replace after calling
+    /// `prevent_default()`.
+    fn clipboard_data(&self) -> Option<DataTransfer> {
+        js!(
+            return @{self.as_ref()}.clipboardData;
+        )
+        .try_into()
+        .unwrap()
+    }
+}
+
+/// The `CompositionEvent` interface, for the events an input method editor
+/// fires while the user composes text.
+pub trait ICompositionEvent: IEvent {
+    /// The text being composed: the empty string for `compositionstart`, the
+    /// text so far for `compositionupdate`, and the committed text for
+    /// `compositionend`.
+    fn data(&self) -> String {
+        js!(
+            return @{self.as_ref()}.data;
+        )
+        .try_into()
+        .unwrap()
+    }
+}
+
+// Event types which stdweb doesn't provide. Most of these are plain `Event`s
+// with no properties of their own; use `target()` to get at the element.
+
+declare_concrete_events! {
+    "ClipboardEvent" {
+        /// The `copy` event.
+        CopyEvent = "copy",
+        /// The `cut` event.
+        CutEvent = "cut",
+        /// The `paste` event.
+        PasteEvent = "paste",
+    }
+    "CompositionEvent" {
+        /// The `compositionstart` event, fired when an input method editor
+        /// starts composing text.
+        CompositionStartEvent = "compositionstart",
+        /// The `compositionupdate` event, fired when the text being composed
+        /// changes.
+        CompositionUpdateEvent = "compositionupdate",
+        /// The `compositionend` event, fired when composition is committed
+        /// or cancelled.
+        CompositionEndEvent = "compositionend",
+    }
+    "Event" {
+        /// The `canplay` event, fired when a media element can start playing.
+        CanPlayEvent = "canplay",
+        /// The `canplaythrough` event, fired when a media element can play to
+        /// the end without stopping to buffer.
+        CanPlayThroughEvent = "canplaythrough",
+        /// The `durationchange` event, fired when a media element's `duration`
+        /// changes.
+        DurationChangeEvent = "durationchange",
+        /// The `invalid` event, fired at a form control which fails its
+        /// constraints when its form is checked or submitted.
+        InvalidEvent = "invalid",
+        /// The `reset` event, fired when a form is reset.
+        ResetEvent = "reset",
+        /// The `emptied` event, fired when a media element is reset.
+        EmptiedEvent = "emptied",
+        /// The `ended` event, fired when a media element reaches the end.
+        EndedEvent = "ended",
+        /// The `loadeddata` event, fired when a media element has loaded the
+        /// frame at its current playback position.
+        LoadedDataEvent = "loadeddata",
+        /// The `loadedmetadata` event, fired when a media element knows its
+        /// duration and dimensions.
+        LoadedMetadataEvent = "loadedmetadata",
+        /// The `pause` event, fired when a media element is paused.
+        PauseEvent = "pause",
+        /// The `play` event, fired when a media element is no longer paused.
+        PlayEvent = "play",
+        /// The `playing` event, fired when a media element starts playing after
+        /// being paused or stopping to buffer.
+        PlayingEvent = "playing",
+        /// The `ratechange` event, fired when a media element's `playbackRate`
+        /// changes.
+        RateChangeEvent = "ratechange",
+        /// The `seeked` event, fired when a media element finishes seeking.
+        SeekedEvent = "seeked",
+        /// The `seeking` event, fired when a media element starts seeking.
+        SeekingEvent = "seeking",
+        /// The `stalled` event, fired when a media element is waiting for data
+        /// which isn't arriving.
+        StalledEvent = "stalled",
+        /// The `suspend` event, fired when a media element stops loading data
+        /// before it has all of it.
+        SuspendEvent = "suspend",
+        /// The `timeupdate` event, fired when a media element's `currentTime`
+        /// changes.
+        TimeUpdateEvent = "timeupdate",
+        /// The `volumechange` event, fired when a media element's `volume` or
+        /// `muted` changes.
+        VolumeChangeEvent = "volumechange",
+        /// The `waiting` event, fired when a media element stops playing to
+        /// buffer.
+        WaitingEvent = "waiting",
+    }
+}
+
+impl IClipboardEvent for CopyEvent {}
+impl IClipboardEvent for CutEvent {}
+impl IClipboardEvent for PasteEvent {}
+impl ICompositionEvent for CompositionStartEvent {}
+impl ICompositionEvent for CompositionUpdateEvent {}
+impl ICompositionEvent for CompositionEndEvent {}
+
 // TODO? these are all the "on*" attributes defined in the HTML5 standard, with
 // the ones I've been unable to match to stdweb event types commented out.
 //
@@ -56,15 +352,21 @@ declare_events! {
     abort: ResourceAbortEvent,
     // autocomplete: Event,
     // autocompleteerror: Event,
+    // beforetoggle: Event,
     blur: BlurEvent,
     // cancel: Event,
-    // canplay: Event,
-    // canplaythrough: Event,
+    canplay: CanPlayEvent,
+    canplaythrough: CanPlayThroughEvent,
     change: ChangeEvent,
     click: ClickEvent,
     // close: Event,
+    compositionend: CompositionEndEvent,
+    compositionstart: CompositionStartEvent,
+    compositionupdate: CompositionUpdateEvent,
     contextmenu: ContextMenuEvent,
+    copy: CopyEvent,
     // cuechange: Event,
+    cut: CutEvent,
     dblclick: DoubleClickEvent,
     drag: DragEvent,
     dragend: DragEndEvent,
@@ -74,20 +376,22 @@ declare_events! {
     dragover: DragOverEvent,
     dragstart: DragStartEvent,
     drop: DragDropEvent,
-    // durationchange: Event,
-    // emptied: Event,
-    // ended: Event,
+    durationchange: DurationChangeEvent,
+    emptied: EmptiedEvent,
+    ended: EndedEvent,
     error: ResourceErrorEvent,
     focus: FocusEvent,
+    gotpointercapture: GotPointerCaptureEvent,
     input: InputEvent,
-    // invalid: Event,
+    invalid: InvalidEvent,
     keydown: KeyDownEvent,
     keypress: KeyPressEvent,
     keyup: KeyUpEvent,
     load: ResourceLoadEvent,
-    // loadeddata: Event,
-    // loadedmetadata: Event,
+    loadeddata: LoadedDataEvent,
+    loadedmetadata: LoadedMetadataEvent,
     loadstart: LoadStartEvent,
+    lostpointercapture: LostPointerCaptureEvent,
     mousedown: MouseDownEvent,
     mouseenter: MouseEnterEvent,
     mouseleave: MouseLeaveEvent,
@@ -95,27 +399,40 @@ declare_events! {
     mouseout: MouseOutEvent,
     mouseover: MouseOverEvent,
     mouseup: MouseUpEvent,
-    mousewheel: MouseWheelEvent,
-    // pause: Event,
-    // play: Event,
-    // playing: Event,
+    paste: PasteEvent,
+    pause: PauseEvent,
+    play: PlayEvent,
+    playing: PlayingEvent,
+    pointercancel: PointerCancelEvent,
+    pointerdown: PointerDownEvent,
+    pointerenter: PointerEnterEvent,
+    pointerleave: PointerLeaveEvent,
+    pointermove: PointerMoveEvent,
+    pointerout: PointerOutEvent,
+    pointerover: PointerOverEvent,
+    pointerup: PointerUpEvent,
     progress: ProgressEvent,
-    // ratechange: Event,
-    // reset: Event,
+    ratechange: RateChangeEvent,
+    reset: ResetEvent,
     resize: ResizeEvent,
     scroll: ScrollEvent,
-    // seeked: Event,
-    // seeking: Event,
+    seeked: SeekedEvent,
+    seeking: SeekingEvent,
     // select: Event,
     // show: Event,
     // sort: Event,
-    // stalled: Event,
+    stalled: StalledEvent,
     submit: SubmitEvent,
-    // suspend: Event,
-    // timeupdate: Event,
+    suspend: SuspendEvent,
+    timeupdate: TimeUpdateEvent,
     // toggle: Event,
-    // volumechange: Event,
-    // waiting: Event,
+    touchcancel: TouchCancel,
+    touchend: TouchEnd,
+    touchmove: TouchMove,
+    touchstart: TouchStart,
+    volumechange: VolumeChangeEvent,
+    waiting: WaitingEvent,
+    wheel: MouseWheelEvent,
 }
 
 impl Display for Events {
@@ -151,9 +468,203 @@ where
     F: FnMut(E) + 'static,
     E: ConcreteEvent + 'static,
 {
-    fn attach(&mut self, target: &mut <Stdweb as OutputType>::EventTarget) -> EventListenerHandle {
+    fn attach(&mut self, target: &mut <Stdweb as OutputType>::EventTarget) -> ListenerHandle {
+        let handler = self.0.take().unwrap();
+        target.add_event_listener(handler).into()
+    }
+
+    fn render(&self) -> Option<String> {
+        None
+    }
+}
+
+/// Wrap an event handler closure to call `prevent_default()` on the event
+/// before the closure gets it.
+///
+/// ```no_run
+/// # #![recursion_limit = "256"]
+/// use typed_html::dom::DOMTree;
+/// use typed_html::html;
+/// use typed_html::output::stdweb::{prevent_default, Stdweb};
+///
+/// # fn main() {
+/// let doc: DOMTree<Stdweb> = html!(
+///     <form onsubmit={ prevent_default(|_: stdweb::web::event::SubmitEvent| {
+///         stdweb::web::alert("Submitted without leaving the page");
+///     }) }>
+///         <button type="submit">"Submit"</button>
+///     </form>
+/// : Stdweb);
+/// # }
+/// ```
+pub fn prevent_default<F, E>(mut f: F) -> impl FnMut(E) + 'static
+where
+    F: FnMut(E) + 'static,
+    E: IEvent,
+{
+    move |event: E| {
+        event.prevent_default();
+        f(event)
+    }
+}
+
+/// Wrap an event handler closure to call `stop_propagation()` on the event
+/// before the closure gets it.
+pub fn stop_propagation<F, E>(mut f: F) -> impl FnMut(E) + 'static
+where
+    F: FnMut(E) + 'static,
+    E: IEvent,
+{
+    move |event: E| {
+        event.stop_propagation();
+        f(event)
+    }
+}
+
+thread_local! {
+    static ERROR_HANDLER: RefCell<Option<Box<dyn Fn(Box<dyn error::Error>)>>> = RefCell::new(None);
+}
+
+/// Wrap an event handler closure which returns a `Result`, passing any error
+/// it returns to the error handler set with
+/// [`Stdweb::set_error_handler()`][set_error_handler].
+///
+/// ```no_run
+/// # #![recursion_limit = "256"]
+/// use typed_html::dom::DOMTree;
+/// use typed_html::html;
+/// use stdweb::web::event::ClickEvent;
+/// use typed_html::output::stdweb::{fallible, Stdweb};
+///
+/// # fn main() {
+/// let mut count: u8 = 0;
+/// let doc: DOMTree<Stdweb> = html!(
+///     <button onclick={ fallible(move |_: ClickEvent| -> Result<(), &str> {
+///         count = count.checked_add(1).ok_or("too many clicks")?;
+///         Ok(())
+///     }) }>"Click me"</button>
+/// : Stdweb);
+/// # }
+/// ```
+///
+/// [set_error_handler]: struct.Stdweb.html#method.set_error_handler
+pub fn fallible<F, E, Err>(mut f: F) -> impl FnMut(E) + 'static
+where
+    F: FnMut(E) -> Result<(), Err> + 'static,
+    Err: Into<Box<dyn error::Error>>,
+{
+    move |event: E| {
+        if let Err(err) = f(event) {
+            Stdweb::handle_error(err.into());
+        }
+    }
+}
+
+/// Wrapper type for closures as passive event handlers.
+///
+/// A passive event handler promises the browser that it won't call
+/// `prevent_default()`, so the browser can go ahead and scroll without
+/// waiting for it. Use this for `wheel` and touch handlers which only watch
+/// the scrolling.
+///
+/// ```no_run
+/// # #![recursion_limit = "256"]
+/// use typed_html::dom::DOMTree;
+/// use typed_html::html;
+/// use typed_html::output::stdweb::{Passive, Stdweb};
+///
+/// # fn main() {
+/// let doc: DOMTree<Stdweb> = html!(
+///     <div onwheel={ Passive::new(|event: stdweb::web::event::MouseWheelEvent| {
+///         stdweb::console!(log, event.delta_y());
+///     }) }>"Scroll me"</div>
+/// : Stdweb);
+/// # }
+/// ```
+pub struct Passive<F, E>(Option<F>, PhantomData<E>);
+
+impl<F, E> Passive<F, E>
+where
+    F: FnMut(E) + 'static,
+    E: ConcreteEvent + 'static,
+{
+    pub fn new(f: F) -> Self {
+        Passive(Some(f), PhantomData)
+    }
+}
+
+impl<F, E> From<Passive<F, E>> for Box<dyn EventHandler<Stdweb, E>>
+where
+    F: FnMut(E) + 'static,
+    E: ConcreteEvent + 'static,
+{
+    fn from(handler: Passive<F, E>) -> Self {
+        Box::new(handler)
+    }
+}
+
+impl<F, E> EventHandler<Stdweb, E> for Passive<F, E>
+where
+    F: FnMut(E) + 'static,
+    E: ConcreteEvent + 'static,
+{
+    fn attach(&mut self, target: &mut <Stdweb as OutputType>::EventTarget) -> ListenerHandle {
         let handler = self.0.take().unwrap();
-        target.add_event_listener(handler)
+        let listener = js!(
+            var listener = @{Mut(handler)};
+            @{target.as_ref()}.addEventListener(@{E::EVENT_TYPE}, listener, { passive: true });
+            return listener;
+        )
+        .try_into()
+        .unwrap();
+        ListenerHandle(Handle::Passive {
+            event_type: E::EVENT_TYPE,
+            target: target.as_ref().clone(),
+            listener,
+        })
+    }
+
+    fn render(&self) -> Option<String> {
+        None
+    }
+}
+
+/// An event handler made from a [`Binding`][Binding], which reads the value
+/// of the event's target.
+///
+/// [Binding]: ../../events/struct.Binding.html
+struct BindingHandler<V, E>(Option<Binding<V>>, PhantomData<E>);
+
+impl<V, E> From<Binding<V>> for Box<dyn EventHandler<Stdweb, E>>
+where
+    V: 'static,
+    E: ConcreteEvent + 'static,
+{
+    fn from(binding: Binding<V>) -> Self {
+        Box::new(BindingHandler(Some(binding), PhantomData))
+    }
+}
+
+impl<V, E> EventHandler<Stdweb, E> for BindingHandler<V, E>
+where
+    V: 'static,
+    E: ConcreteEvent + 'static,
+{
+    fn attach(&mut self, target: &mut <Stdweb as OutputType>::EventTarget) -> ListenerHandle {
+        let mut binding = self.0.take().unwrap();
+        target
+            .add_event_listener(move |event: E| {
+                let value = js!(
+                    var target = @{event.as_ref()}.target;
+                    return target && target.value != null ? String(target.value) : "";
+                )
+                .into_string()
+                .unwrap_or_default();
+                if let Err(err) = binding.handle(&value) {
+                    Stdweb::handle_error(Box::new(err));
+                }
+            })
+            .into()
     }
 
     fn render(&self) -> Option<String> {
@@ -162,12 +673,38 @@ where
 }
 
 impl Stdweb {
-    pub fn install_handlers(target: &mut Element, handlers: &mut Events) {
-        for_events!(handler in handlers => {
-            handler.attach(target);
+    /// Set the function which gets the errors returned by event handlers
+    /// wrapped in [`fallible()`][fallible], and the values which
+    /// [`bind()`][bind] handlers without their own error handler couldn't
+    /// parse.
+    ///
+    /// Without one, errors are logged to the browser console.
+    ///
+    /// [fallible]: fn.fallible.html
+    /// [bind]: ../../events/fn.bind.html
+    pub fn set_error_handler<F>(handler: F)
+    where
+        F: Fn(Box<dyn error::Error>) + 'static,
+    {
+        ERROR_HANDLER.with(|cell| *cell.borrow_mut() = Some(Box::new(handler)));
+    }
+
+    fn handle_error(err: Box<dyn error::Error>) {
+        ERROR_HANDLER.with(|cell| match *cell.borrow() {
+            Some(ref handler) => handler(err),
+            None => {
+                let message = err.to_string();
+                js! { @(no_return)
+                    console.error(@{message});
+                }
+            }
         });
     }
 
+    pub fn install_handlers(target: &mut Element, handlers: &mut Events) {
+        handlers.attach(target);
+    }
+
     pub fn build(
         document: &web::Document,
         vnode: VNode<'_, Stdweb>,
@@ -175,8 +712,15 @@ impl Stdweb {
         match vnode {
             VNode::Text(text) => Ok(document.create_text_node(&text).into()),
             VNode::UnsafeText(text) => Ok(document.create_text_node(&text).into()),
+            VNode::Comment(text) => Ok(create_comment(document, text)),
+            VNode::Portal(element) => Ok(element.clone().into()),
             VNode::Element(element) => {
-                let mut node = document.create_element(element.name)?;
+                let is = element
+                    .attributes
+                    .iter()
+                    .find(|(key, _)| *key == "is")
+                    .map(|(_, value)| value.as_str());
+                let mut node = create_element(document, element.name, is)?;
                 for (key, value) in element.attributes {
                     node.set_attribute(&key, &value)?;
                 }
@@ -189,4 +733,722 @@ impl Stdweb {
             }
         }
     }
+
+    /// Apply a list of [`Patch`][Patch]es from [`diff()`][diff], usually
+    /// received from a server, to a tree in the live document.
+    ///
+    /// Returns the root of the patched tree, which is a new node if the
+    /// patches replaced the root. If the root has a parent, it's replaced
+    /// there too.
+    ///
+    /// Patching leaves the state the user has given the page alone where it
+    /// can: the value or checked state of the form control which has focus
+    /// isn't overwritten, only its attribute, and the focused element gets
+    /// focus back if patches moved it.
+    ///
+    /// Unescaped HTML becomes a text node, as it does in [`build()`][build].
+    ///
+    /// ```no_run
+    /// use stdweb::web::{document, IParentNode};
+    /// use typed_html::diff::Patch;
+    /// use typed_html::output::stdweb::Stdweb;
+    ///
+    /// # fn main() {
+    /// # let patches: Vec<Patch> = Vec::new();
+    /// // `patches` came from the server, sent as JSON for instance.
+    /// let root = document().query_selector("#app").unwrap().unwrap();
+    /// Stdweb::patch(&document(), root.into(), &patches).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [Patch]: ../../diff/enum.Patch.html
+    /// [diff]: ../../diff/fn.diff.html
+    /// [build]: #method.build
+    pub fn patch(
+        document: &web::Document,
+        root: web::Node,
+        patches: &[Patch],
+    ) -> Result<web::Node, PatchError> {
+        let focused = focused_element();
+        let mut root = root;
+        for patch in patches {
+            match patch {
+                Patch::Insert {
+                    parent,
+                    index,
+                    node,
+                } => {
+                    let parent = find_node(&root, parent)?;
+                    let node = create_node(document, node)?;
+                    match parent.child_nodes().item(*index as u32) {
+                        Some(before) => {
+                            parent.insert_before(&node, &before).map_err(insert_error)?;
+                        }
+                        None => parent.append_child(&node),
+                    }
+                }
+                Patch::Replace { path, node } => {
+                    let old = find_node(&root, path)?;
+                    let node = create_node(document, node)?;
+                    if let Some(parent) = old.parent_node() {
+                        parent.replace_child(&node, &old).map_err(insert_error)?;
+                    }
+                    if path.is_empty() {
+                        root = node;
+                    }
+                }
+                Patch::Remove { path } => {
+                    let node = find_node(&root, path)?;
+                    if let Some(parent) = node.parent_node() {
+                        parent.remove_child(&node).unwrap();
+                    }
+                }
+                Patch::Move {
+                    parent: parent_path,
+                    from,
+                    to,
+                } => {
+                    let parent = find_node(&root, parent_path)?;
+                    let children = parent.child_nodes();
+                    match (children.item(*from as u32), children.item(*to as u32)) {
+                        (Some(node), Some(before)) => {
+                            parent.insert_before(&node, &before).map_err(insert_error)?;
+                        }
+                        _ => return Err(PatchError::NotFound(parent_path.clone())),
+                    }
+                }
+                Patch::SetAttribute { path, name, value } => {
+                    let element = find_element(&root, path)?;
+                    element.set_attribute(name, value)?;
+                    if !is_focused(&focused, &element) {
+                        set_property(&element, name, Some(value));
+                    }
+                }
+                Patch::RemoveAttribute { path, name } => {
+                    let element = find_element(&root, path)?;
+                    element.remove_attribute(name);
+                    if !is_focused(&focused, &element) {
+                        set_property(&element, name, None);
+                    }
+                }
+                Patch::SetText { path, text } => {
+                    find_node(&root, path)?.set_text_content(text);
+                }
+            }
+        }
+        restore_focus(focused);
+        Ok(root)
+    }
+
+    /// Update a tree in the live document, which was built from `old`, to
+    /// match `new`, changing only what differs instead of building the whole
+    /// tree again.
+    ///
+    /// Children are matched up by position. Elements which have the same
+    /// name and `id` are kept and have their attributes and children
+    /// updated, and other nodes are replaced with ones built from `new`. As
+    /// with [`patch()`][patch], the state of the focused form control is left
+    /// alone.
+    ///
+    /// Event handlers can't be compared, so each handler in `new` replaces
+    /// the listener for that event on the element, and listeners for events
+    /// which `new` has no handler for are removed. Elements without handlers
+    /// in either tree are left as they are. Afterwards, `new` holds the
+    /// listeners, so it's the `old` tree for the next update.
+    ///
+    /// Returns the root of the updated tree, which is a new node if the root
+    /// element was replaced.
+    ///
+    /// ```no_run
+    /// # #![recursion_limit = "256"]
+    /// use stdweb::web::{document, INode};
+    /// use typed_html::dom::DOMTree;
+    /// use typed_html::{html, text};
+    /// use typed_html::output::stdweb::Stdweb;
+    ///
+    /// # fn main() {
+    /// let render = |count: usize| -> DOMTree<Stdweb> {
+    ///     html!(<p>{ text!("{} clicks", count) }</p> : Stdweb)
+    /// };
+    /// let mut old = render(0);
+    /// let root = Stdweb::build(&document(), old.vnode()).unwrap();
+    /// document().body().unwrap().append_child(&root);
+    ///
+    /// let mut new = render(1);
+    /// Stdweb::update(&document(), root, &mut *old, &mut *new).unwrap();
+    /// # }
+    /// ```
+    ///
+    /// [patch]: #method.patch
+    pub fn update(
+        document: &web::Document,
+        root: web::Node,
+        old: &mut dyn Node<Stdweb>,
+        new: &mut dyn Node<Stdweb>,
+    ) -> Result<web::Node, PatchError> {
+        let focused = focused_element();
+        let root = update_node(
+            document,
+            &mut Vec::new(),
+            root,
+            old.vnode(),
+            new.vnode(),
+            &focused,
+        )?;
+        restore_focus(focused);
+        Ok(root)
+    }
+
+    /// Build a tree and append it to an element in the page, given either
+    /// as an `Element` or as a CSS selector for one.
+    ///
+    /// The returned [`Mounted`][Mounted] handle owns the tree, along with
+    /// the listeners its event handlers were attached with, so it can be
+    /// [`update()`][update]d and [`unmount()`][unmount]ed again. Dropping
+    /// the handle unmounts the tree too, so keep it for as long as the tree
+    /// should stay in the page.
+    ///
+    /// ```no_run
+    /// # #![recursion_limit = "256"]
+    /// use stdweb::web::document;
+    /// use typed_html::{html, text};
+    /// use typed_html::output::stdweb::Stdweb;
+    ///
+    /// # fn main() {
+    /// let mut mounted = Stdweb::mount(
+    ///     &document(),
+    ///     "#app",
+    ///     html!(<p onclick={ |_| stdweb::web::alert("Hi") }>"Loading"</p> : Stdweb),
+    /// )
+    /// .unwrap();
+    /// mounted
+    ///     .update(html!(<p>{ text!("{} items", 3) }</p> : Stdweb))
+    ///     .unwrap();
+    /// mounted.unmount();
+    /// # }
+    /// ```
+    ///
+    /// [Mounted]: struct.Mounted.html
+    /// [update]: struct.Mounted.html#method.update
+    /// [unmount]: struct.Mounted.html#method.unmount
+    pub fn mount<M: MountPoint>(
+        document: &web::Document,
+        target: M,
+        mut tree: DOMTree<Stdweb>,
+    ) -> Result<Mounted, MountError> {
+        let parent = target.find(document)?;
+        let root = Stdweb::build(document, tree.vnode())?;
+        parent.append_child(&root);
+        Ok(Mounted {
+            document: document.clone(),
+            root,
+            tree,
+        })
+    }
+}
+
+fn focused_element() -> Option<Element> {
+    js!(
+        return document.activeElement;
+    )
+    .try_into()
+    .unwrap_or(None)
+}
+
+fn restore_focus(focused: Option<Element>) {
+    if let Some(focused) = focused {
+        js! { @(no_return)
+            var focused = @{focused};
+            if (document.activeElement !== focused && document.contains(focused)) {
+                focused.focus();
+            }
+        }
+    }
+}
+
+fn update_node(
+    document: &web::Document,
+    path: &mut Vec<usize>,
+    node: web::Node,
+    old: VNode<'_, Stdweb>,
+    new: VNode<'_, Stdweb>,
+    focused: &Option<Element>,
+) -> Result<web::Node, PatchError> {
+    match (old, new) {
+        (VNode::Text(old), VNode::Text(new))
+        | (VNode::Text(old), VNode::UnsafeText(new))
+        | (VNode::UnsafeText(old), VNode::Text(new))
+        | (VNode::UnsafeText(old), VNode::UnsafeText(new)) => {
+            if old != new {
+                node.set_text_content(new);
+            }
+            Ok(node)
+        }
+        (VNode::Comment(old), VNode::Comment(new)) => {
+            if old != new {
+                node.set_text_content(new);
+            }
+            Ok(node)
+        }
+        (VNode::Portal(old), VNode::Portal(new)) if old == new => Ok(node),
+        (VNode::Element(old), VNode::Element(new)) if same_element(&old, &new) => {
+            let mut element: Element = node
+                .clone()
+                .try_into()
+                .map_err(|_| PatchError::NotAnElement(path.clone()))?;
+            for (name, value) in attribute_changes(&old, &new) {
+                match value {
+                    Some(value) => element.set_attribute(name, value)?,
+                    None => element.remove_attribute(name),
+                }
+                if !is_focused(focused, &element) {
+                    set_property(&element, name, value);
+                }
+            }
+
+            if needs_new_listeners(&old, &new) {
+                for (_, listener) in old.events.listeners.drain(..) {
+                    listener.remove();
+                }
+                new.events.attach(&mut element);
+            }
+
+            let dom_children: Vec<web::Node> = {
+                let children = node.child_nodes();
+                (0..children.len()).filter_map(|index| children.item(index)).collect()
+            };
+            let new_len = new.children.len();
+            let mut old_children = old.children.into_iter();
+            for (index, new_child) in new.children.into_iter().enumerate() {
+                match old_children.next() {
+                    Some(old_child) => {
+                        path.push(index);
+                        let dom_child = dom_children
+                            .get(index)
+                            .cloned()
+                            .ok_or_else(|| PatchError::NotFound(path.clone()))?;
+                        update_node(document, path, dom_child, old_child, new_child, focused)?;
+                        path.pop();
+                    }
+                    None => {
+                        let child = Stdweb::build(document, new_child)?;
+                        node.append_child(&child);
+                    }
+                }
+            }
+            for (old_child, dom_child) in old_children.zip(dom_children.iter().skip(new_len)) {
+                remove_listeners(old_child);
+                node.remove_child(dom_child).unwrap();
+            }
+            Ok(node)
+        }
+        (old, new) => {
+            remove_listeners(old);
+            let replacement = Stdweb::build(document, new)?;
+            if let Some(parent) = node.parent_node() {
+                parent
+                    .replace_child(&replacement, &node)
+                    .map_err(insert_error)?;
+            }
+            Ok(replacement)
+        }
+    }
+}
+
+/// Whether an element being updated from `old` to `new` needs its listeners
+/// replaced.
+///
+/// Handlers can't be compared, so this is whenever either side has any.
+fn needs_new_listeners(old: &VElement<'_, Stdweb>, new: &VElement<'_, Stdweb>) -> bool {
+    !old.events.listeners.is_empty() || new.events.has_handlers()
+}
+
+/// Remove the listeners in a tree which is being taken out of the document.
+fn remove_listeners(vnode: VNode<'_, Stdweb>) {
+    if let VNode::Element(element) = vnode {
+        for (_, listener) in element.events.listeners.drain(..) {
+            listener.remove();
+        }
+        for child in element.children {
+            remove_listeners(child);
+        }
+    }
+}
+
+fn find_node(root: &web::Node, path: &[usize]) -> Result<web::Node, PatchError> {
+    let mut node = root.clone();
+    for index in path {
+        node = node
+            .child_nodes()
+            .item(*index as u32)
+            .ok_or_else(|| PatchError::NotFound(path.to_vec()))?;
+    }
+    Ok(node)
+}
+
+fn find_element(root: &web::Node, path: &[usize]) -> Result<Element, PatchError> {
+    find_node(root, path)?
+        .try_into()
+        .map_err(|_| PatchError::NotAnElement(path.to_vec()))
+}
+
+fn create_node(document: &web::Document, node: &PatchNode) -> Result<web::Node, PatchError> {
+    match node {
+        PatchNode::Text(text) | PatchNode::Html(text) => Ok(document.create_text_node(text).into()),
+        PatchNode::Comment(text) => Ok(create_comment(document, text)),
+        PatchNode::Element {
+            name,
+            attributes,
+            children,
+        } => {
+            let is = attributes
+                .iter()
+                .find(|(key, _)| key == "is")
+                .map(|(_, value)| value.as_str());
+            let element = create_element(document, name, is)?;
+            for (key, value) in attributes {
+                element.set_attribute(key, value)?;
+            }
+            for child in children {
+                element.append_child(&create_node(document, child)?);
+            }
+            Ok(element.into())
+        }
+    }
+}
+
+/// Create an element, as a customised built-in element if it has an `is`
+/// attribute.
+///
+/// The `is` value has to be given to `document.createElement()`, since
+/// setting the attribute on an element which already exists doesn't upgrade
+/// it.
+fn create_element(
+    document: &web::Document,
+    name: &str,
+    is: Option<&str>,
+) -> Result<Element, web::error::InvalidCharacterError> {
+    let element = document.create_element(name)?;
+    match is {
+        // The name was checked by creating the plain element above.
+        Some(is) => Ok(js!(
+            return @{document}.createElement(@{name}, { is: @{is} });
+        )
+        .try_into()
+        .unwrap()),
+        None => Ok(element),
+    }
+}
+
+fn create_comment(document: &web::Document, text: &str) -> web::Node {
+    js!(return @{document}.createComment(@{text});)
+        .try_into()
+        .unwrap()
+}
+
+fn is_focused(focused: &Option<Element>, element: &Element) -> bool {
+    focused.as_ref() == Some(element)
+}
+
+/// Keep the properties which reflect the state of a form control in step
+/// with their attributes, which only set the initial state once the user
+/// has changed it.
+fn set_property(element: &Element, name: &str, value: Option<&str>) {
+    match name {
+        "value" => {
+            let value = value.unwrap_or_default();
+            js! { @(no_return)
+                @{element}.value = @{value};
+            }
+        }
+        "checked" | "selected" => {
+            js! { @(no_return)
+                @{element}[@{name}] = @{value.is_some()};
+            }
+        }
+        _ => {}
+    }
+}
+
+/// An error from applying patches with [`Stdweb::patch()`][patch].
+///
+/// [patch]: struct.Stdweb.html#method.patch
+#[derive(Debug)]
+pub enum PatchError {
+    /// There's no node at this path.
+    NotFound(Vec<usize>),
+    /// The node at this path isn't an element, so it has no attributes.
+    NotAnElement(Vec<usize>),
+    /// An element or attribute name isn't valid.
+    InvalidCharacter(web::error::InvalidCharacterError),
+    /// A node can't be inserted where the patch puts it, with the browser's
+    /// explanation.
+    InsertNode(String),
+}
+
+impl Display for PatchError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            PatchError::NotFound(path) => write!(f, "no node at path {:?}", path),
+            PatchError::NotAnElement(path) => write!(f, "node at path {:?} isn't an element", path),
+            PatchError::InvalidCharacter(err) => Display::fmt(err, f),
+            PatchError::InsertNode(message) => f.write_str(message),
+        }
+    }
+}
+
+impl error::Error for PatchError {}
+
+impl From<web::error::InvalidCharacterError> for PatchError {
+    fn from(err: web::error::InvalidCharacterError) -> Self {
+        PatchError::InvalidCharacter(err)
+    }
+}
+
+fn insert_error<E: Display>(err: E) -> PatchError {
+    PatchError::InsertNode(err.to_string())
+}
+
+/// Where to mount a tree with [`Stdweb::mount()`][mount]: an `Element`, or
+/// a CSS selector for one.
+///
+/// [mount]: struct.Stdweb.html#method.mount
+pub trait MountPoint {
+    /// Find the element in the document.
+    fn find(self, document: &web::Document) -> Result<Element, MountError>;
+}
+
+impl MountPoint for Element {
+    fn find(self, _document: &web::Document) -> Result<Element, MountError> {
+        Ok(self)
+    }
+}
+
+impl MountPoint for &str {
+    fn find(self, document: &web::Document) -> Result<Element, MountError> {
+        document
+            .query_selector(self)
+            .map_err(|err| MountError::InvalidSelector(err.to_string()))?
+            .ok_or_else(|| MountError::NotFound(self.to_string()))
+    }
+}
+
+/// An error from [`Stdweb::mount()`][mount].
+///
+/// [mount]: struct.Stdweb.html#method.mount
+#[derive(Debug)]
+pub enum MountError {
+    /// No element matches this selector.
+    NotFound(String),
+    /// The selector isn't valid CSS, with the browser's explanation.
+    InvalidSelector(String),
+    /// An element or attribute name in the tree isn't valid.
+    InvalidCharacter(web::error::InvalidCharacterError),
+}
+
+impl Display for MountError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            MountError::NotFound(selector) => write!(f, "no element matches {:?}", selector),
+            MountError::InvalidSelector(message) => f.write_str(message),
+            MountError::InvalidCharacter(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl error::Error for MountError {}
+
+impl From<web::error::InvalidCharacterError> for MountError {
+    fn from(err: web::error::InvalidCharacterError) -> Self {
+        MountError::InvalidCharacter(err)
+    }
+}
+
+/// A tree which has been mounted in the page with
+/// [`Stdweb::mount()`][mount].
+///
+/// The tree is unmounted when the handle is dropped.
+///
+/// [mount]: struct.Stdweb.html#method.mount
+#[must_use = "the tree is unmounted when the handle is dropped"]
+pub struct Mounted {
+    document: web::Document,
+    root: web::Node,
+    tree: DOMTree<Stdweb>,
+}
+
+impl Mounted {
+    /// The root node of the tree in the page.
+    pub fn root(&self) -> &web::Node {
+        &self.root
+    }
+
+    /// Update the tree in the page to match `tree`, with
+    /// [`Stdweb::update()`][update], and keep it as the mounted tree.
+    ///
+    /// [update]: struct.Stdweb.html#method.update
+    pub fn update(&mut self, mut tree: DOMTree<Stdweb>) -> Result<(), PatchError> {
+        self.root = Stdweb::update(
+            &self.document,
+            self.root.clone(),
+            &mut *self.tree,
+            &mut *tree,
+        )?;
+        self.tree = tree;
+        Ok(())
+    }
+
+    /// Remove the tree from the page, and the listeners its event handlers
+    /// were attached with.
+    ///
+    /// This is the same as dropping the handle.
+    pub fn unmount(self) {}
+}
+
+impl Drop for Mounted {
+    fn drop(&mut self) {
+        remove_listeners(self.tree.vnode());
+        if let Some(parent) = self.root.parent_node() {
+            // Page script may have moved the node already.
+            let _ = parent.remove_child(&self.root);
+        }
+    }
+}
+
+impl Debug for Mounted {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        f.debug_struct("Mounted").field("tree", &self.tree).finish()
+    }
+}
+
+/// A node which puts an existing DOM element into a tree, like a `<canvas>`
+/// managed by a JavaScript library.
+///
+/// [`Stdweb::build()`][build] moves the element into its place in the new
+/// tree instead of creating a new one, so the element keeps its state and
+/// any listeners the library has attached to it. It can be a child of any
+/// element which accepts flow or phrasing content.
+///
+/// ```no_run
+/// # #![recursion_limit = "256"]
+/// use stdweb::web::{document, INode, IParentNode};
+/// use typed_html::dom::DOMTree;
+/// use typed_html::html;
+/// use typed_html::output::stdweb::{Portal, Stdweb};
+///
+/// # fn main() {
+/// let chart = document().query_selector("#chart").unwrap().unwrap();
+/// let mut doc: DOMTree<Stdweb> = html!(
+///     <section>
+///         <h2>"Sales"</h2>
+///         { Portal::new(chart) }
+///     </section>
+/// : Stdweb);
+/// let node = Stdweb::build(&document(), doc.vnode()).unwrap();
+/// document().body().unwrap().append_child(&node);
+/// # }
+/// ```
+///
+/// Rendered as a string, a portal is the element's current `outerHTML`.
+///
+/// [build]: struct.Stdweb.html#method.build
+pub struct Portal(Element);
+
+impl Portal {
+    /// Construct a portal to an existing element.
+    pub fn new(element: Element) -> Box<Self> {
+        Box::new(Portal(element))
+    }
+
+    /// The element this portal puts into the tree.
+    pub fn element(&self) -> &Element {
+        &self.0
+    }
+}
+
+impl Display for Portal {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        self.write_html(f, &Html)
+    }
+}
+
+impl Debug for Portal {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        f.debug_tuple("Portal").field(&self.0.node_name()).finish()
+    }
+}
+
+impl Node<Stdweb> for Portal {
+    fn vnode(&'_ mut self) -> VNode<'_, Stdweb> {
+        VNode::Portal(&self.0)
+    }
+
+    fn apply(&mut self, _transform: &mut dyn Transform) -> bool {
+        true
+    }
+
+    fn write_html(&self, f: &mut dyn fmt::Write, _escape: &dyn Escape) -> fmt::Result {
+        let html: String = js!(
+            return @{self.0.as_ref()}.outerHTML;
+        )
+        .try_into()
+        .unwrap();
+        f.write_str(&html)
+    }
+}
+
+impl IntoIterator for Box<Portal> {
+    type Item = Box<Portal>;
+    type IntoIter = std::vec::IntoIter<Box<Portal>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        vec![self].into_iter()
+    }
+}
+
+impl FlowContent<Stdweb> for Portal {}
+impl PhrasingContent<Stdweb> for Portal {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+
+    fn element<'a>(vnode: &'a VNode<'a, Stdweb>) -> &'a VElement<'a, Stdweb> {
+        match vnode {
+            VNode::Element(element) => element,
+            _ => panic!("expected an element"),
+        }
+    }
+
+    #[test]
+    fn test_update_matching() {
+        let mut old: DOMTree<Stdweb> = html!(<p id="a" class="x">"Hi"</p> : Stdweb);
+        let mut same: DOMTree<Stdweb> = html!(<p id="a" class="y">"Bye"</p> : Stdweb);
+        let mut moved: DOMTree<Stdweb> = html!(<p id="b" class="x">"Hi"</p> : Stdweb);
+        let mut renamed: DOMTree<Stdweb> = html!(<div id="a" class="x">"Hi"</div> : Stdweb);
+        let (old, same, moved, renamed) =
+            (old.vnode(), same.vnode(), moved.vnode(), renamed.vnode());
+        let matches = |new: &VNode<'_, Stdweb>| same_element(element(&old), element(new));
+        assert!(matches(&same));
+        assert!(!matches(&moved));
+        assert!(!matches(&renamed));
+        assert_eq!(
+            vec![("class", Some("y"))],
+            attribute_changes(element(&old), element(&same))
+        );
+    }
+
+    #[test]
+    fn test_update_listeners() {
+        let mut plain: DOMTree<Stdweb> = html!(<p>"Hi"</p> : Stdweb);
+        let mut clickable: DOMTree<Stdweb> = html!(<p onclick={ |_| () }>"Hi"</p> : Stdweb);
+        let (plain, clickable) = (plain.vnode(), clickable.vnode());
+        let (plain, clickable) = (element(&plain), element(&clickable));
+        assert!(!plain.events.has_handlers());
+        assert!(clickable.events.has_handlers());
+        assert!(!needs_new_listeners(plain, plain));
+        assert!(needs_new_listeners(plain, clickable));
+        assert!(needs_new_listeners(clickable, clickable));
+    }
 }
@@ -0,0 +1,287 @@
+//! Generators for property testing with [proptest].
+//!
+//! These strategies produce random DOM trees which follow the content model,
+//! as far as the types enforce it: paragraphs only contain phrasing content,
+//! lists only contain list items, and so on. Text and attribute values are
+//! arbitrary strings, so the trees are good for testing that anything which
+//! renders, parses or rewrites them copes with whatever it's given.
+//!
+//! Use them with the `proptest!` macro in your tests, or with a test runner:
+//!
+//! ```
+//! use proptest::test_runner::TestRunner;
+//! use typed_html::proptest::dom_tree;
+//!
+//! # fn main() {
+//! let mut runner = TestRunner::default();
+//! runner
+//!     .run(&dom_tree(), |tree| {
+//!         assert!(tree.to_string().starts_with("<div"));
+//!         Ok(())
+//!     })
+//!     .unwrap();
+//! # }
+//! ```
+//!
+//! This module is only available with the `proptest` feature flag.
+//!
+//! [proptest]: https://docs.rs/proptest/
+
+use ::proptest::collection::vec;
+use ::proptest::prelude::*;
+use ::proptest::strategy::BoxedStrategy;
+
+use crate::dom::{DOMTree, TextNode};
+use crate::elements::{self, FlowContent, PhrasingContent};
+use crate::types::Id;
+
+#[derive(Clone, Debug)]
+struct Attrs {
+    id: Option<String>,
+    class: Vec<String>,
+    title: Option<String>,
+    data: Option<(&'static str, String)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Inline {
+    B,
+    Code,
+    Em,
+    I,
+    Span,
+    Strong,
+}
+
+#[derive(Clone, Debug)]
+enum Phrasing {
+    Text(String),
+    Br,
+    Element(Inline, Attrs, Vec<Phrasing>),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Block {
+    Div,
+    Section,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Paragraph {
+    H1,
+    P,
+    Pre,
+}
+
+#[derive(Clone, Debug)]
+enum Flow {
+    Phrasing(Phrasing),
+    Block(Block, Attrs, Vec<Flow>),
+    Paragraph(Paragraph, Attrs, Vec<Phrasing>),
+    List(Attrs, Vec<Vec<Flow>>),
+}
+
+fn attrs() -> impl Strategy<Value = Attrs> {
+    let name = "[a-z][a-z0-9-]{0,8}";
+    (
+        prop::option::of(name),
+        vec(name, 0..3),
+        prop::option::of(any::<String>()),
+        prop::option::of((
+            prop::sample::select(vec!["id", "role", "x-y"]),
+            any::<String>(),
+        )),
+    )
+        .prop_map(|(id, class, title, data)| Attrs {
+            id,
+            class,
+            title,
+            data,
+        })
+}
+
+fn phrasing_spec() -> impl Strategy<Value = Phrasing> {
+    let leaf = prop_oneof![
+        4 => any::<String>().prop_map(Phrasing::Text),
+        1 => Just(Phrasing::Br),
+    ];
+    leaf.prop_recursive(4, 32, 4, |inner| {
+        let inline = prop::sample::select(vec![
+            Inline::B,
+            Inline::Code,
+            Inline::Em,
+            Inline::I,
+            Inline::Span,
+            Inline::Strong,
+        ]);
+        (inline, attrs(), vec(inner, 0..4))
+            .prop_map(|(inline, attrs, children)| Phrasing::Element(inline, attrs, children))
+    })
+}
+
+fn flow_spec() -> impl Strategy<Value = Flow> {
+    let paragraph = (
+        prop::sample::select(vec![Paragraph::H1, Paragraph::P, Paragraph::Pre]),
+        attrs(),
+        vec(phrasing_spec(), 0..4),
+    )
+        .prop_map(|(paragraph, attrs, children)| Flow::Paragraph(paragraph, attrs, children));
+    let leaf = prop_oneof![phrasing_spec().prop_map(Flow::Phrasing), paragraph];
+    leaf.prop_recursive(3, 32, 4, |inner| {
+        prop_oneof![
+            (
+                prop::sample::select(vec![Block::Div, Block::Section]),
+                attrs(),
+                vec(inner.clone(), 0..4),
+            )
+                .prop_map(|(block, attrs, children)| Flow::Block(block, attrs, children)),
+            (attrs(), vec(vec(inner, 0..3), 0..4))
+                .prop_map(|(attrs, items)| Flow::List(attrs, items)),
+        ]
+    })
+}
+
+macro_rules! element {
+    ($element:ident, $attrs:expr, $children:expr, $build:ident) => {{
+        let mut element = elements::$element::new();
+        let attrs = $attrs;
+        element.attrs.id = attrs.id.as_ref().map(|id| Id::new(id.as_str()));
+        if !attrs.class.is_empty() {
+            element.attrs.class = Some(attrs.class.join(" ").as_str().into());
+        }
+        element.attrs.title = attrs.title.clone();
+        element.data_attributes.extend(attrs.data.clone());
+        element.children.extend($children.iter().map($build));
+        Box::new(element)
+    }};
+}
+
+/// Build phrasing content as either a `PhrasingContent` or a `FlowContent`.
+macro_rules! phrasing {
+    ($spec:expr) => {
+        match $spec {
+            Phrasing::Text(text) => Box::new(TextNode::new(text.as_str())),
+            Phrasing::Br => Box::new(elements::br::new()),
+            Phrasing::Element(inline, attrs, children) => match inline {
+                Inline::B => element!(b, attrs, children, build_phrasing),
+                Inline::Code => element!(code, attrs, children, build_phrasing),
+                Inline::Em => element!(em, attrs, children, build_phrasing),
+                Inline::I => element!(i, attrs, children, build_phrasing),
+                Inline::Span => element!(span, attrs, children, build_phrasing),
+                Inline::Strong => element!(strong, attrs, children, build_phrasing),
+            },
+        }
+    };
+}
+
+fn build_phrasing(spec: &Phrasing) -> Box<dyn PhrasingContent<String>> {
+    phrasing!(spec)
+}
+
+fn build_flow(spec: &Flow) -> Box<dyn FlowContent<String>> {
+    match spec {
+        Flow::Phrasing(phrasing) => phrasing!(phrasing),
+        Flow::Block(Block::Div, attrs, children) => element!(div, attrs, children, build_flow),
+        Flow::Block(Block::Section, attrs, children) => {
+            element!(section, attrs, children, build_flow)
+        }
+        Flow::Paragraph(Paragraph::H1, attrs, children) => {
+            element!(h1, attrs, children, build_phrasing)
+        }
+        Flow::Paragraph(Paragraph::P, attrs, children) => {
+            element!(p, attrs, children, build_phrasing)
+        }
+        Flow::Paragraph(Paragraph::Pre, attrs, children) => {
+            element!(pre, attrs, children, build_phrasing)
+        }
+        Flow::List(attrs, items) => {
+            let build_item = |children: &Vec<Flow>| {
+                let mut item = elements::li::new();
+                item.children.extend(children.iter().map(build_flow));
+                Box::new(item)
+            };
+            element!(ul, attrs, items, build_item)
+        }
+    }
+}
+
+/// Generate phrasing content: text, `<br>`, and inline elements like `<em>`
+/// and `<span>`.
+pub fn phrasing_content() -> BoxedStrategy<Box<dyn PhrasingContent<String>>> {
+    phrasing_spec()
+        .prop_map(|spec| build_phrasing(&spec))
+        .boxed()
+}
+
+/// Generate flow content: phrasing content, headings, paragraphs, lists, and
+/// `<div>`s and `<section>`s containing more flow content.
+pub fn flow_content() -> BoxedStrategy<Box<dyn FlowContent<String>>> {
+    flow_spec().prop_map(|spec| build_flow(&spec)).boxed()
+}
+
+/// Generate a DOM tree, which is a `<div>` containing flow content.
+pub fn dom_tree() -> BoxedStrategy<DOMTree<String>> {
+    (attrs(), vec(flow_spec(), 0..4))
+        .prop_map(|(attrs, children)| -> DOMTree<String> {
+            element!(div, &attrs, children, build_flow)
+        })
+        .boxed()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::escape::{Html, Serializer};
+
+    /// Strip the tags from rendered HTML and decode what's left.
+    fn text_content(html: &str) -> String {
+        let mut text = String::new();
+        let mut in_tag = false;
+        for c in html.chars() {
+            match c {
+                '<' => in_tag = true,
+                '>' => in_tag = false,
+                c if !in_tag => text.push(c),
+                _ => (),
+            }
+        }
+        htmlescape::decode_html(&text).unwrap()
+    }
+
+    fn spec_text(spec: &Flow, out: &mut String) {
+        fn phrasing_text(spec: &Phrasing, out: &mut String) {
+            match spec {
+                Phrasing::Text(text) => out.push_str(text),
+                Phrasing::Br => (),
+                Phrasing::Element(_, _, children) => {
+                    children.iter().for_each(|child| phrasing_text(child, out))
+                }
+            }
+        }
+        match spec {
+            Flow::Phrasing(phrasing) => phrasing_text(phrasing, out),
+            Flow::Block(_, _, children) => children.iter().for_each(|child| spec_text(child, out)),
+            Flow::Paragraph(_, _, children) => {
+                children.iter().for_each(|child| phrasing_text(child, out))
+            }
+            Flow::List(_, items) => items
+                .iter()
+                .flatten()
+                .for_each(|child| spec_text(child, out)),
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_matches_serializer(tree in dom_tree()) {
+            prop_assert_eq!(tree.to_string(), Serializer::new(Html).render(&*tree));
+        }
+
+        #[test]
+        fn test_text_survives_escaping(spec in flow_spec()) {
+            let mut expected = String::new();
+            spec_text(&spec, &mut expected);
+            prop_assert_eq!(expected, text_content(&build_flow(&spec).to_string()));
+        }
+    }
+}
@@ -0,0 +1,572 @@
+//! A streaming parser for HTML documents.
+//!
+//! Rather than building a tree, the [`Parser`][Parser] emits an
+//! [`Event`][Event] for each start tag, attribute, end tag and piece of text
+//! as soon as it has seen enough of the input to know what it is. Its memory
+//! use depends on the size of the largest tag or text node rather than the
+//! size of the document, so it can work through very large documents, like
+//! sitemaps or exports, a chunk at a time.
+//!
+//! [`Reader`][Reader] wraps a parser around anything which implements
+//! [`Read`][Read], and iterates over its events.
+//!
+//! # Examples
+//!
+//! ```
+//! use typed_html::sax::{Event, Reader};
+//!
+//! let sitemap = r#"<?xml version="1.0" encoding="UTF-8"?>
+//! <urlset>
+//!     <url><loc>https://example.com/</loc></url>
+//!     <url><loc>https://example.com/about?lang=en&amp;page=1</loc></url>
+//! </urlset>"#;
+//!
+//! let mut in_loc = false;
+//! let mut urls = Vec::new();
+//! for event in Reader::new(sitemap.as_bytes()) {
+//!     match event.unwrap() {
+//!         Event::StartTag(name) => in_loc = name == "loc",
+//!         Event::EndTag(_) => in_loc = false,
+//!         Event::Text(text) if in_loc => urls.push(text),
+//!         _ => {}
+//!     }
+//! }
+//! assert_eq!(
+//!     vec![
+//!         "https://example.com/",
+//!         "https://example.com/about?lang=en&page=1",
+//!     ],
+//!     urls
+//! );
+//! ```
+//!
+//! # Parsing
+//!
+//! The parser follows the tokenising rules of HTML, not XML, but it doesn't
+//! try to repair the structure of the document: end tags are reported as
+//! they're written, and missing ones aren't filled in. The exceptions are
+//! void elements, like `<br>`, and elements closed with `/>`, which are
+//! followed by an [`EndTag`][EndTag] event straight after their attributes,
+//! so that every start tag has a matching end tag in a well formed document.
+//!
+//! Element and attribute names are converted to lower case, and character
+//! references in text and attribute values are decoded. The contents of
+//! `<script>` and `<style>` elements are reported as text without decoding
+//! them, and `<![CDATA[...]]>` sections are reported as text.
+//!
+//! [Parser]: struct.Parser.html
+//! [Event]: enum.Event.html
+//! [EndTag]: enum.Event.html#variant.EndTag
+//! [Reader]: struct.Reader.html
+//! [Read]: https://doc.rust-lang.org/std/io/trait.Read.html
+
+use std::collections::VecDeque;
+use std::error;
+use std::fmt::{Display, Error, Formatter};
+use std::io::{self, Read};
+use std::str;
+
+use htmlescape::decode_html;
+
+/// Elements which can't have children, and so have no end tag.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements whose contents are text up to their end tag.
+const RAW_TEXT_ELEMENTS: &[&str] = &["script", "style", "textarea", "title"];
+
+/// Elements whose contents aren't decoded.
+const UNESCAPED_ELEMENTS: &[&str] = &["script", "style"];
+
+/// Something found in a document by a [`Parser`][Parser].
+///
+/// [Parser]: struct.Parser.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Event {
+    /// The start of an element, with its name.
+    StartTag(String),
+    /// An attribute of the element most recently started, with its name and
+    /// value. Attributes without a value have an empty one.
+    Attribute(String, String),
+    /// The end of an element, with its name.
+    EndTag(String),
+    /// A piece of text.
+    Text(String),
+    /// A comment, without the `<!--` and `-->`.
+    Comment(String),
+    /// A doctype declaration, with the text after `<!DOCTYPE`.
+    Doctype(String),
+}
+
+/// An error from a [`Parser`][Parser] or a [`Reader`][Reader].
+///
+/// [Parser]: struct.Parser.html
+/// [Reader]: struct.Reader.html
+#[derive(Debug)]
+pub enum SaxError {
+    /// The input ended in the middle of a tag or a comment.
+    UnexpectedEof,
+    /// The input wasn't valid UTF-8.
+    InvalidUtf8,
+    /// The reader returned an error.
+    Io(io::Error),
+}
+
+impl Display for SaxError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            SaxError::UnexpectedEof => write!(f, "unexpected end of input"),
+            SaxError::InvalidUtf8 => write!(f, "input is not valid UTF-8"),
+            SaxError::Io(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl error::Error for SaxError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            SaxError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SaxError {
+    fn from(err: io::Error) -> Self {
+        SaxError::Io(err)
+    }
+}
+
+/// A streaming HTML parser, which is fed input a chunk at a time.
+///
+/// # Examples
+///
+/// ```
+/// use typed_html::sax::{Event, Parser};
+///
+/// let mut parser = Parser::new();
+/// parser.feed("<p class=intro>Hello, wo");
+/// assert_eq!(Some(Event::StartTag("p".to_string())), parser.next_event().unwrap());
+/// assert_eq!(
+///     Some(Event::Attribute("class".to_string(), "intro".to_string())),
+///     parser.next_event().unwrap()
+/// );
+/// // The text might not be finished yet.
+/// assert_eq!(None, parser.next_event().unwrap());
+///
+/// parser.feed("rld!</p>");
+/// parser.finish();
+/// assert_eq!(
+///     Some(Event::Text("Hello, world!".to_string())),
+///     parser.next_event().unwrap()
+/// );
+/// assert_eq!(Some(Event::EndTag("p".to_string())), parser.next_event().unwrap());
+/// assert_eq!(None, parser.next_event().unwrap());
+/// ```
+#[derive(Debug, Default)]
+pub struct Parser {
+    buffer: String,
+    pos: usize,
+    pending: VecDeque<Event>,
+    raw_text: Option<String>,
+    finished: bool,
+}
+
+impl Parser {
+    /// Construct a parser with no input.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a chunk of input.
+    pub fn feed(&mut self, input: &str) {
+        self.buffer.drain(..self.pos);
+        self.pos = 0;
+        self.buffer.push_str(input);
+    }
+
+    /// Mark the end of the input, so that anything left over is parsed.
+    pub fn finish(&mut self) {
+        self.finished = true;
+    }
+
+    /// Whether [`finish()`][finish] has been called.
+    ///
+    /// [finish]: #method.finish
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Parse the next event, if there's enough input for it.
+    ///
+    /// Returns `Ok(None)` when more input is needed, or at the end of the
+    /// input once [`finish()`][finish] has been called.
+    ///
+    /// [finish]: #method.finish
+    pub fn next_event(&mut self) -> Result<Option<Event>, SaxError> {
+        if let Some(event) = self.pending.pop_front() {
+            return Ok(Some(event));
+        }
+        let rest = &self.buffer[self.pos..];
+        if rest.is_empty() {
+            return Ok(None);
+        }
+
+        if let Some(element) = self.raw_text.clone() {
+            let end = match find_end_tag(rest, &element) {
+                Some(end) => end,
+                None if self.finished => rest.len(),
+                None => return Ok(None),
+            };
+            self.raw_text = None;
+            if end > 0 {
+                let text = if UNESCAPED_ELEMENTS.contains(&element.as_str()) {
+                    rest[..end].to_string()
+                } else {
+                    decode(&rest[..end])
+                };
+                self.pos += end;
+                return Ok(Some(Event::Text(text)));
+            }
+            return self.next_event();
+        }
+
+        let parsed = match markup_at(rest, 0) {
+            Some(true) => self.markup(),
+            Some(false) => match text_end(rest) {
+                Some(end) => Some((end, Event::Text(decode(&rest[..end])))),
+                None if self.finished => Some((rest.len(), Event::Text(decode(rest)))),
+                None => None,
+            },
+            None if self.finished => Some((rest.len(), Event::Text(rest.to_string()))),
+            None => None,
+        };
+        match parsed {
+            Some((length, event)) => {
+                self.pos += length;
+                Ok(Some(event))
+            }
+            None if self.finished => Err(SaxError::UnexpectedEof),
+            None => Ok(None),
+        }
+    }
+
+    /// Parse the tag, comment or declaration at the start of the input,
+    /// returning its length and its first event.
+    fn markup(&mut self) -> Option<(usize, Event)> {
+        let rest = &self.buffer[self.pos..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            let end = comment.find("-->")?;
+            return Some((end + 7, Event::Comment(comment[..end].to_string())));
+        }
+        if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+            let end = cdata.find("]]>")?;
+            return Some((end + 12, Event::Text(cdata[..end].to_string())));
+        }
+        if rest.starts_with("<!") || rest.starts_with("<?") {
+            let end = rest.find('>')?;
+            let contents = &rest[2..end];
+            let is_doctype = contents
+                .get(..7)
+                .is_some_and(|keyword| keyword.eq_ignore_ascii_case("doctype"));
+            let event = if is_doctype {
+                Event::Doctype(contents[7..].trim().to_string())
+            } else if rest.starts_with("<?") {
+                Event::Comment(rest[1..end].to_string())
+            } else {
+                Event::Comment(contents.to_string())
+            };
+            return Some((end + 1, event));
+        }
+        if rest.starts_with("</") {
+            let end = rest.find('>')?;
+            let name = rest[2..end].trim().to_ascii_lowercase();
+            return Some((end + 1, Event::EndTag(name)));
+        }
+
+        let tag = parse_tag(rest)?;
+        self.pending.extend(
+            tag.attributes
+                .into_iter()
+                .map(|(name, value)| Event::Attribute(name, value)),
+        );
+        if tag.self_closing || VOID_ELEMENTS.contains(&tag.name.as_str()) {
+            self.pending.push_back(Event::EndTag(tag.name.clone()));
+        } else if RAW_TEXT_ELEMENTS.contains(&tag.name.as_str()) {
+            self.raw_text = Some(tag.name.clone());
+        }
+        Some((tag.length, Event::StartTag(tag.name)))
+    }
+}
+
+/// Decode character references, leaving the text alone if it has any which
+/// aren't valid.
+fn decode(text: &str) -> String {
+    if text.contains('&') {
+        decode_html(text).unwrap_or_else(|_| text.to_string())
+    } else {
+        text.to_string()
+    }
+}
+
+/// Whether a `<` at `index` starts a tag, comment or declaration, or `None`
+/// if that depends on input which hasn't arrived yet.
+fn markup_at(input: &str, index: usize) -> Option<bool> {
+    let bytes = input.as_bytes();
+    if bytes[index] != b'<' {
+        return Some(false);
+    }
+    bytes
+        .get(index + 1)
+        .map(|next| next.is_ascii_alphabetic() || b"/!?".contains(next))
+}
+
+/// The length of the text at the start of the input, or `None` if it may
+/// continue past the end of the input so far.
+fn text_end(input: &str) -> Option<usize> {
+    input
+        .match_indices('<')
+        .map(|(index, _)| index)
+        .filter(|&index| index > 0)
+        .find_map(|index| match markup_at(input, index) {
+            Some(true) => Some(Some(index)),
+            Some(false) => None,
+            None => Some(None),
+        })
+        .flatten()
+}
+
+/// Find the end tag of a raw text element, ignoring case.
+fn find_end_tag(input: &str, name: &str) -> Option<usize> {
+    input
+        .match_indices("</")
+        .map(|(index, _)| index)
+        .find(|&index| {
+            let after = &input.as_bytes()[index + 2..];
+            after.len() > name.len()
+                && after[..name.len()].eq_ignore_ascii_case(name.as_bytes())
+                && (after[name.len()].is_ascii_whitespace() || b"/>".contains(&after[name.len()]))
+        })
+}
+
+struct Tag {
+    length: usize,
+    name: String,
+    attributes: Vec<(String, String)>,
+    self_closing: bool,
+}
+
+/// Parse a start tag, or return `None` if it isn't finished yet.
+fn parse_tag(input: &str) -> Option<Tag> {
+    let bytes = input.as_bytes();
+    let is_name_end = |b: u8| b.is_ascii_whitespace() || b == b'/' || b == b'>';
+    let name_end = 1 + bytes[1..].iter().position(|&b| is_name_end(b))?;
+    let mut tag = Tag {
+        length: 0,
+        name: input[1..name_end].to_ascii_lowercase(),
+        attributes: Vec::new(),
+        self_closing: false,
+    };
+
+    let mut pos = name_end;
+    loop {
+        while bytes.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        match bytes[pos] {
+            b'>' => {
+                tag.length = pos + 1;
+                return Some(tag);
+            }
+            b'/' => {
+                if *bytes.get(pos + 1)? == b'>' {
+                    tag.self_closing = true;
+                    tag.length = pos + 2;
+                    return Some(tag);
+                }
+                pos += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        let start = pos;
+        pos += 1;
+        while !is_name_end(*bytes.get(pos)?) && bytes[pos] != b'=' {
+            pos += 1;
+        }
+        let name = input[start..pos].to_ascii_lowercase();
+        while bytes.get(pos)?.is_ascii_whitespace() {
+            pos += 1;
+        }
+        let mut value = String::new();
+        if bytes[pos] == b'=' {
+            pos += 1;
+            while bytes.get(pos)?.is_ascii_whitespace() {
+                pos += 1;
+            }
+            let quote = bytes[pos];
+            if quote == b'"' || quote == b'\'' {
+                let end = pos + 1 + bytes[pos + 1..].iter().position(|&b| b == quote)?;
+                value = decode(&input[pos + 1..end]);
+                pos = end + 1;
+            } else {
+                let end = pos
+                    + bytes[pos..]
+                        .iter()
+                        .position(|&b| b.is_ascii_whitespace() || b == b'>')?;
+                value = decode(&input[pos..end]);
+                pos = end;
+            }
+        }
+        tag.attributes.push((name, value));
+    }
+}
+
+/// An iterator over the events in a document read from a
+/// [`Read`][Read]er, a chunk at a time.
+///
+/// [Read]: https://doc.rust-lang.org/std/io/trait.Read.html
+pub struct Reader<R> {
+    reader: R,
+    parser: Parser,
+    bytes: Vec<u8>,
+    failed: bool,
+}
+
+impl<R: Read> Reader<R> {
+    /// Construct a reader which parses the document in `reader`.
+    pub fn new(reader: R) -> Self {
+        Reader {
+            reader,
+            parser: Parser::new(),
+            bytes: Vec::new(),
+            failed: false,
+        }
+    }
+
+    /// Read the next chunk of input into the parser.
+    fn fill(&mut self) -> Result<(), SaxError> {
+        let mut chunk = [0; 8192];
+        let read = self.reader.read(&mut chunk)?;
+        if read == 0 {
+            if !self.bytes.is_empty() {
+                return Err(SaxError::InvalidUtf8);
+            }
+            self.parser.finish();
+            return Ok(());
+        }
+        self.bytes.extend_from_slice(&chunk[..read]);
+        // A chunk can end in the middle of a character, which is kept for
+        // the next one.
+        let valid = match str::from_utf8(&self.bytes) {
+            Ok(_) => self.bytes.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => return Err(SaxError::InvalidUtf8),
+        };
+        self.parser
+            .feed(str::from_utf8(&self.bytes[..valid]).unwrap());
+        self.bytes.drain(..valid);
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for Reader<R> {
+    type Item = Result<Event, SaxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        loop {
+            let result = match self.parser.next_event() {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) if self.parser.is_finished() => return None,
+                Ok(None) => self.fill(),
+                Err(err) => Err(err),
+            };
+            if let Err(err) = result {
+                self.failed = true;
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(chunks: &[&str]) -> Vec<Event> {
+        let mut parser = Parser::new();
+        let mut events = Vec::new();
+        for chunk in chunks {
+            parser.feed(chunk);
+            while let Some(event) = parser.next_event().unwrap() {
+                events.push(event);
+            }
+        }
+        parser.finish();
+        while let Some(event) = parser.next_event().unwrap() {
+            events.push(event);
+        }
+        events
+    }
+
+    #[test]
+    fn test_parse_in_chunks() {
+        use self::Event::*;
+        let s = |s: &str| s.to_string();
+        let document = "<!DOCTYPE html><P Class='a &amp; b' hidden>1 &lt; 2<br/><!-- hi --></p>\
+                        <script>if (a < b && c) {}</script><input value=x>";
+        let expected = vec![
+            Doctype(s("html")),
+            StartTag(s("p")),
+            Attribute(s("class"), s("a & b")),
+            Attribute(s("hidden"), s("")),
+            Text(s("1 < 2")),
+            StartTag(s("br")),
+            EndTag(s("br")),
+            Comment(s(" hi ")),
+            EndTag(s("p")),
+            StartTag(s("script")),
+            Text(s("if (a < b && c) {}")),
+            EndTag(s("script")),
+            StartTag(s("input")),
+            Attribute(s("value"), s("x")),
+            EndTag(s("input")),
+        ];
+        assert_eq!(expected, parse(&[document]));
+        // Split the document at every possible place.
+        for split in 1..document.len() {
+            assert_eq!(
+                expected,
+                parse(&[&document[..split], &document[split..]]),
+                "split at {}",
+                split
+            );
+        }
+    }
+
+    #[test]
+    fn test_reader_errors() {
+        let mut reader = Reader::new(&b"<p>caf\xc3"[..]);
+        assert_eq!(
+            Some(Event::StartTag("p".to_string())),
+            reader.next().map(Result::unwrap)
+        );
+        match reader.next() {
+            Some(Err(SaxError::InvalidUtf8)) => {}
+            other => panic!("expected invalid UTF-8, got {:?}", other),
+        }
+        assert!(reader.next().is_none());
+
+        let mut reader = Reader::new("<p title=\"unfinished".as_bytes());
+        match reader.next() {
+            Some(Err(SaxError::UnexpectedEof)) => {}
+            other => panic!("expected unexpected end of input, got {:?}", other),
+        }
+    }
+}
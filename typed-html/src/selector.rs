@@ -0,0 +1,601 @@
+//! CSS selectors for finding elements in a DOM tree.
+//!
+//! A [`Selector`][Selector] supports a subset of CSS selector syntax:
+//!
+//! * type selectors like `p`, and the universal selector `*`
+//! * class selectors like `.price` and id selectors like `#main`
+//! * attribute selectors like `[href]`, `[type=checkbox]` and
+//!   `[rel~=noopener]`
+//! * the descendant (`ul li`) and child (`ul > li`) combinators
+//! * lists of selectors separated by commas
+//!
+//! Pseudo-classes and the sibling combinators aren't supported.
+//!
+//! [`Node::select()`][select] finds the elements in a DOM tree which match a
+//! selector, which is handy for inspecting trees in tests and for pulling
+//! data out of a page.
+//!
+//! [Selector]: struct.Selector.html
+//! [select]: ../dom/trait.Node.html#method.select
+
+use std::error;
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+use crate::dom::{Node, VElement, VNode, Visit};
+use crate::OutputType;
+
+/// An element which a [`Selector`][Selector] can be matched against.
+///
+/// [Selector]: struct.Selector.html
+pub trait Selectable {
+    /// The element's name, like `p`.
+    fn name(&self) -> &str;
+
+    /// The value of one of the element's attributes, if it's set.
+    fn attribute(&self, name: &str) -> Option<&str>;
+}
+
+impl<'a, T: OutputType> Selectable for VElement<'a, T> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(&self.attributes, name)
+    }
+}
+
+/// The name and attributes of an element, for matching its ancestors while
+/// its children are borrowed.
+pub(crate) struct Ancestor<'a> {
+    pub name: &'a str,
+    pub attributes: &'a [(&'static str, String)],
+}
+
+impl<'a> Selectable for Ancestor<'a> {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(self.attributes, name)
+    }
+}
+
+fn find_attribute<'a>(attributes: &'a [(&'static str, String)], name: &str) -> Option<&'a str> {
+    attributes
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// A parsed CSS selector.
+///
+/// # Examples
+///
+/// ```
+/// use typed_html::selector::Selector;
+///
+/// let selector: Selector = "ul.menu > li a[href]".parse().unwrap();
+/// assert!("p..x".parse::<Selector>().is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selector {
+    alternatives: Vec<Complex>,
+}
+
+/// Compound selectors, from the subject of the selector back to the first,
+/// each with the combinator which joins it to the one on its left.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Complex(Vec<(Compound, Combinator)>);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Combinator {
+    Descendant,
+    Child,
+    None,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Compound {
+    name: Option<String>,
+    conditions: Vec<Condition>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Condition {
+    Class(String),
+    Id(String),
+    Has(String),
+    Equals(String, String),
+    Includes(String, String),
+}
+
+/// An error from parsing a [`Selector`][Selector].
+///
+/// [Selector]: struct.Selector.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectorError {
+    selector: String,
+}
+
+impl Display for SelectorError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "unsupported or invalid selector: {:?}", self.selector)
+    }
+}
+
+impl error::Error for SelectorError {}
+
+impl FromStr for Selector {
+    type Err = SelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || SelectorError {
+            selector: s.to_string(),
+        };
+        let alternatives = s
+            .split(',')
+            .map(|complex| parse_complex(complex).ok_or_else(error))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Selector { alternatives })
+    }
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_'
+}
+
+fn parse_complex(input: &str) -> Option<Complex> {
+    let mut compounds = Vec::new();
+    let mut combinator = Combinator::None;
+    let mut rest = input.trim_start();
+    while !rest.is_empty() {
+        let (compound, after) = parse_compound(rest)?;
+        compounds.push((compound, combinator));
+        let trimmed = after.trim_start();
+        if let Some(after_child) = trimmed.strip_prefix('>') {
+            combinator = Combinator::Child;
+            rest = after_child.trim_start();
+            if rest.is_empty() {
+                return None;
+            }
+        } else {
+            if trimmed.len() == after.len() && !trimmed.is_empty() {
+                return None;
+            }
+            combinator = Combinator::Descendant;
+            rest = trimmed;
+        }
+    }
+    if compounds.is_empty() {
+        return None;
+    }
+    compounds.reverse();
+    Some(Complex(compounds))
+}
+
+fn take_name(input: &str) -> (&str, &str) {
+    let end = input.find(|c| !is_name_char(c)).unwrap_or(input.len());
+    input.split_at(end)
+}
+
+fn parse_compound(input: &str) -> Option<(Compound, &str)> {
+    let mut compound = Compound::default();
+    let mut rest = input;
+    if let Some(after) = rest.strip_prefix('*') {
+        rest = after;
+    } else {
+        let (name, after) = take_name(rest);
+        if !name.is_empty() {
+            compound.name = Some(name.to_ascii_lowercase());
+            rest = after;
+        }
+    }
+    loop {
+        match rest.chars().next() {
+            Some('.') | Some('#') => {
+                let (name, after) = take_name(&rest[1..]);
+                if name.is_empty() {
+                    return None;
+                }
+                compound.conditions.push(if rest.starts_with('.') {
+                    Condition::Class(name.to_string())
+                } else {
+                    Condition::Id(name.to_string())
+                });
+                rest = after;
+            }
+            Some('[') => {
+                let end = rest.find(']')?;
+                compound.conditions.push(parse_attribute(&rest[1..end])?);
+                rest = &rest[end + 1..];
+            }
+            _ => break,
+        }
+    }
+    if rest.len() == input.len() {
+        return None;
+    }
+    Some((compound, rest))
+}
+
+fn parse_attribute(input: &str) -> Option<Condition> {
+    let (name, rest) = take_name(input.trim());
+    if name.is_empty() {
+        return None;
+    }
+    let name = name.to_ascii_lowercase();
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return Some(Condition::Has(name));
+    }
+    let (includes, value) = match rest.strip_prefix("~=") {
+        Some(value) => (true, value),
+        None => (false, rest.strip_prefix('=')?),
+    };
+    let value = value.trim();
+    let value = match value.chars().next() {
+        Some(quote @ '"') | Some(quote @ '\'') => {
+            value.strip_prefix(quote)?.strip_suffix(quote)?.to_string()
+        }
+        _ => value.to_string(),
+    };
+    Some(if includes {
+        Condition::Includes(name, value)
+    } else {
+        Condition::Equals(name, value)
+    })
+}
+
+impl Compound {
+    fn matches(&self, element: &dyn Selectable) -> bool {
+        if let Some(ref name) = self.name {
+            if !element.name().eq_ignore_ascii_case(name) {
+                return false;
+            }
+        }
+        self.conditions.iter().all(|condition| match condition {
+            Condition::Class(class) => includes(element.attribute("class"), class),
+            Condition::Id(id) => element.attribute("id") == Some(id.as_str()),
+            Condition::Has(name) => element.attribute(name).is_some(),
+            Condition::Equals(name, value) => element.attribute(name) == Some(value.as_str()),
+            Condition::Includes(name, value) => includes(element.attribute(name), value),
+        })
+    }
+}
+
+fn includes(list: Option<&str>, value: &str) -> bool {
+    list.is_some_and(|list| list.split_whitespace().any(|item| item == value))
+}
+
+impl Complex {
+    fn matches(&self, element: &dyn Selectable, ancestors: &[&dyn Selectable]) -> bool {
+        let (subject, combinator) = &self.0[0];
+        subject.matches(element) && Complex::matches_rest(&self.0[1..], *combinator, ancestors)
+    }
+
+    fn matches_rest(
+        compounds: &[(Compound, Combinator)],
+        combinator: Combinator,
+        ancestors: &[&dyn Selectable],
+    ) -> bool {
+        let (compound, next) = match compounds.first() {
+            Some(first) => first,
+            None => return true,
+        };
+        match combinator {
+            Combinator::Child => match ancestors.split_last() {
+                Some((parent, rest)) => {
+                    compound.matches(*parent) && Complex::matches_rest(&compounds[1..], *next, rest)
+                }
+                None => false,
+            },
+            _ => (0..ancestors.len()).rev().any(|index| {
+                compound.matches(ancestors[index])
+                    && Complex::matches_rest(&compounds[1..], *next, &ancestors[..index])
+            }),
+        }
+    }
+}
+
+impl Selector {
+    /// Test whether an element matches this selector, given its ancestors
+    /// in order from the root of the tree down to its parent.
+    pub fn matches(&self, element: &dyn Selectable, ancestors: &[&dyn Selectable]) -> bool {
+        self.alternatives
+            .iter()
+            .any(|complex| complex.matches(element, ancestors))
+    }
+}
+
+impl Selector {
+    /// Find the elements in a tree which match this selector, in document
+    /// order.
+    ///
+    /// This is usually called through [`Node::select()`][select].
+    ///
+    /// [select]: ../dom/trait.Node.html#method.select
+    pub fn select<T: OutputType>(&self, root: &VNode<'_, T>) -> Selection {
+        let mut found = Vec::new();
+        self.collect(root, &mut Vec::new(), &mut found);
+        Selection(found)
+    }
+
+    fn collect<'a, T: OutputType>(
+        &self,
+        node: &'a VNode<'_, T>,
+        ancestors: &mut Vec<&'a dyn Selectable>,
+        found: &mut Vec<Selected>,
+    ) {
+        if let VNode::Element(element) = node {
+            if self.matches(element, ancestors) {
+                let mut text = String::new();
+                collect_text(node, &mut text);
+                found.push(Selected {
+                    name: element.name,
+                    attributes: element.attributes.clone(),
+                    text,
+                });
+            }
+            ancestors.push(element);
+            for child in &element.children {
+                self.collect(child, ancestors, found);
+            }
+            ancestors.pop();
+        }
+    }
+}
+
+impl Selector {
+    /// Find the elements in a DOM tree which match this selector, in
+    /// document order, without rendering it to `VNode`s first.
+    ///
+    /// This is usually called through [`Node::select()`][select].
+    ///
+    /// [select]: ../dom/trait.Node.html#method.select
+    pub fn select_node<T: OutputType, N: Node<T> + ?Sized>(&self, root: &N) -> Selection {
+        let mut collector = Collector {
+            selector: self,
+            open: Vec::new(),
+            found: Vec::new(),
+        };
+        root.visit(&mut collector);
+        Selection(collector.found)
+    }
+}
+
+/// An element being visited, with the index of its entry in the elements
+/// found if it matched.
+struct Open {
+    name: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    found: Option<usize>,
+}
+
+impl Selectable for Open {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(&self.attributes, name)
+    }
+}
+
+struct Collector<'a> {
+    selector: &'a Selector,
+    open: Vec<Open>,
+    found: Vec<Selected>,
+}
+
+impl<'a> Visit for Collector<'a> {
+    fn enter_element(&mut self, name: &'static str, attributes: Vec<(&'static str, String)>) {
+        let mut element = Open {
+            name,
+            attributes,
+            found: None,
+        };
+        let ancestors: Vec<&dyn Selectable> = self
+            .open
+            .iter()
+            .map(|open| open as &dyn Selectable)
+            .collect();
+        if self.selector.matches(&element, &ancestors) {
+            element.found = Some(self.found.len());
+            self.found.push(Selected {
+                name,
+                attributes: element.attributes.clone(),
+                text: String::new(),
+            });
+        }
+        self.open.push(element);
+    }
+
+    fn leave_element(&mut self) {
+        self.open.pop();
+    }
+
+    fn text(&mut self, text: &str) {
+        for index in self.open.iter().filter_map(|open| open.found) {
+            self.found[index].text.push_str(text);
+        }
+    }
+}
+
+fn collect_text<T: OutputType>(node: &VNode<'_, T>, text: &mut String) {
+    match node {
+        VNode::Text(value) | VNode::UnsafeText(value) => text.push_str(value),
+        VNode::Element(element) => {
+            for child in &element.children {
+                collect_text(child, text);
+            }
+        }
+        VNode::Comment(_) | VNode::Portal(_) => {}
+    }
+}
+
+/// An element found by a [`Selector`][Selector], with its attributes and the
+/// text it contains.
+///
+/// [Selector]: struct.Selector.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selected {
+    name: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    text: String,
+}
+
+impl Selected {
+    /// The element's name.
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    /// The value of one of the element's attributes, if it's set.
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        find_attribute(&self.attributes, name)
+    }
+
+    /// Parse the value of one of the element's attributes, if it's set.
+    pub fn parse_attribute<A: FromStr>(&self, name: &str) -> Option<Result<A, A::Err>> {
+        self.attribute(name).map(str::parse)
+    }
+
+    /// All the text inside the element, with any markup removed.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Parse the text inside the element, ignoring whitespace around it.
+    pub fn parse_text<A: FromStr>(&self) -> Result<A, A::Err> {
+        self.text.trim().parse()
+    }
+}
+
+/// The elements found by a [`Selector`][Selector], in document order.
+///
+/// [Selector]: struct.Selector.html
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Selection(Vec<Selected>);
+
+impl Selection {
+    /// The number of elements found.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Test whether no elements were found.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The first element found.
+    pub fn first(&self) -> Option<&Selected> {
+        self.0.first()
+    }
+
+    /// Iterate over the elements found.
+    pub fn iter(&self) -> std::slice::Iter<'_, Selected> {
+        self.0.iter()
+    }
+
+    /// The text inside each element.
+    pub fn texts(&self) -> Vec<&str> {
+        self.0.iter().map(Selected::text).collect()
+    }
+
+    /// The value of an attribute on each element which has it set.
+    pub fn attributes(&self, name: &str) -> Vec<&str> {
+        self.0
+            .iter()
+            .filter_map(|selected| selected.attribute(name))
+            .collect()
+    }
+
+    /// Parse the text inside each element, stopping at the first error.
+    pub fn parse_texts<A: FromStr>(&self) -> Result<Vec<A>, A::Err> {
+        self.0.iter().map(Selected::parse_text).collect()
+    }
+}
+
+impl IntoIterator for Selection {
+    type Item = Selected;
+    type IntoIter = std::vec::IntoIter<Selected>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Selection {
+    type Item = &'a Selected;
+    type IntoIter = std::slice::Iter<'a, Selected>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Element(&'static str, Vec<(&'static str, String)>);
+
+    impl Selectable for Element {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn attribute(&self, name: &str) -> Option<&str> {
+            find_attribute(&self.1, name)
+        }
+    }
+
+    #[test]
+    fn test_selector_matching() {
+        let list = Element("ul", vec![("class", "menu main".to_string())]);
+        let item = Element("li", vec![]);
+        let link = Element(
+            "a",
+            vec![("href", "/".to_string()), ("rel", "noopener nofollow".to_string())],
+        );
+        let matches = |selector: &str, ancestors: &[&dyn Selectable]| {
+            selector.parse::<Selector>().unwrap().matches(&link, ancestors)
+        };
+        assert!(matches("a", &[]));
+        assert!(matches("A[href='/']", &[]));
+        assert!(matches("[rel~=nofollow]", &[]));
+        assert!(matches("ul.menu a", &[&list, &item]));
+        assert!(matches("ul.main > li > a", &[&list, &item]));
+        assert!(matches("p, li > *", &[&list, &item]));
+        assert!(!matches("ul > a", &[&list, &item]));
+        assert!(!matches("a#home", &[]));
+        assert!(!matches(".menu a", &[]));
+        assert!("ul >".parse::<Selector>().is_err());
+        assert!("a:hover".parse::<Selector>().is_err());
+    }
+
+    #[test]
+    fn test_select_node() {
+        use crate as typed_html;
+        use crate::dom::DOMTree;
+
+        let mut doc: DOMTree<String> = html!(
+            <div id="menu">
+                <p class="item" title="1.50">"Tea "<b>"hot"</b></p>
+                <p class="item">{ crate::unsafe_text!("<i>Cake</i>") }</p>
+                <section><p>"Not an item"</p></section>
+            </div>
+        );
+        for selector in &["p", ".item", "#menu > p", "div p", "[title]", "b, section"] {
+            let selector: Selector = selector.parse().unwrap();
+            assert_eq!(selector.select(&doc.vnode()), selector.select_node(&*doc));
+        }
+        let items = doc.select(".item").unwrap();
+        assert_eq!(vec!["Tea hot", "<i>Cake</i>"], items.texts());
+        assert_eq!(vec!["1.50"], items.attributes("title"));
+    }
+}
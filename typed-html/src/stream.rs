@@ -0,0 +1,222 @@
+//! Rendering a page in chunks, to send its `<head>` before its body is ready.
+//!
+//! A page whose body needs data from a database or another service would
+//! normally be sent all at once, after that data is in. A [`Page`][Page]
+//! instead renders the page's shell, the `<html>` element with its `<head>`
+//! and whatever's already known of the `<body>`, and sends that first, so
+//! the browser can start fetching stylesheets and scripts while the rest is
+//! being rendered. Each piece of the body after that is a closure, which
+//! isn't called until the chunks before it have been sent, and is sent as
+//! soon as it returns.
+//!
+//! # Examples
+//!
+//! ```
+//! # #![recursion_limit = "256"]
+//! # use typed_html::{html, text};
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::stream::Page;
+//!
+//! # fn load_orders() -> Vec<u32> { vec![1, 2] }
+//! # fn main() -> std::io::Result<()> {
+//! let page = Page::new(html!(
+//!     <html>
+//!         <head>
+//!             <title>"Orders"</title>
+//!             <link rel="stylesheet" href="/style.css"/>
+//!         </head>
+//!         <body><h1>"Orders"</h1></body>
+//!     </html>
+//! ))
+//! .chunk(|| {
+//!     let orders = load_orders();
+//!     html!(<ul>{ orders.iter().map(|order| html!(<li>{ text!("#{}", order) }</li>)) }</ul>)
+//! });
+//!
+//! let mut out = Vec::new();
+//! page.write_to(&mut out)?;
+//! assert_eq!(
+//!     "<!DOCTYPE html><html><head><title>Orders</title>\
+//!      <link href=\"&#x2F;style&#x2E;css\" rel=\"stylesheet\"/></head>\
+//!      <body><h1>Orders</h1><ul><li>#1</li><li>#2</li></ul></body></html>",
+//!     String::from_utf8(out).unwrap()
+//! );
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! To hand the chunks to a web framework's streaming response body instead,
+//! iterate over the page with [`chunks()`][chunks].
+//!
+//! [Page]: struct.Page.html
+//! [chunks]: struct.Page.html#method.chunks
+
+use std::collections::VecDeque;
+use std::fmt::{self, Debug, Formatter};
+use std::io::{self, Write};
+
+use crate::dom::DOMTree;
+use crate::escape::{Escape, Html, Serializer};
+
+/// A piece of a page's body, rendered when it's needed.
+type Chunk = Box<dyn FnOnce() -> DOMTree<String>>;
+
+/// A page which is rendered and sent in chunks.
+///
+/// The shell is written first, up to its closing `</body>` tag, followed by
+/// each chunk in order, and then the rest of the shell. If the shell has no
+/// `<body>`, the chunks are written after it.
+pub struct Page<E: Escape = Html> {
+    shell: DOMTree<String>,
+    chunks: VecDeque<Chunk>,
+    serializer: Serializer<E>,
+}
+
+impl Page<Html> {
+    /// Construct a page from its shell, usually an `<html>` element.
+    pub fn new(shell: DOMTree<String>) -> Self {
+        Page {
+            shell,
+            chunks: VecDeque::new(),
+            serializer: Serializer::new(Html),
+        }
+    }
+}
+
+impl<E: Escape> Page<E> {
+    /// Render the page with the given serializer.
+    pub fn serializer<F: Escape>(self, serializer: Serializer<F>) -> Page<F> {
+        Page {
+            shell: self.shell,
+            chunks: self.chunks,
+            serializer,
+        }
+    }
+
+    /// Add a chunk to the end of the body.
+    ///
+    /// The closure is called once the shell and the chunks before it have
+    /// been rendered, so it can wait for the data it needs without holding
+    /// them up.
+    pub fn chunk<F>(mut self, chunk: F) -> Self
+    where
+        F: FnOnce() -> DOMTree<String> + 'static,
+    {
+        self.chunks.push_back(Box::new(chunk));
+        self
+    }
+
+    /// Turn the page into an iterator over its rendered chunks.
+    ///
+    /// The first item is the shell up to its closing `</body>` tag, and the
+    /// last is the rest of it. Each chunk's closure is called as the iterator
+    /// reaches it.
+    pub fn chunks(self) -> Chunks<E> {
+        let mut shell = String::from("<!DOCTYPE html>");
+        self.serializer.write(&*self.shell, &mut shell).unwrap();
+        let tail = match shell.rfind("</body>") {
+            Some(index) => shell.split_off(index),
+            None => String::new(),
+        };
+        Chunks {
+            shell: Some(shell),
+            chunks: self.chunks,
+            tail: Some(tail),
+            serializer: self.serializer,
+        }
+    }
+
+    /// Write the page into a writer, flushing it after each chunk so the
+    /// chunks are sent as they're rendered.
+    pub fn write_to<W: Write>(self, out: &mut W) -> io::Result<()> {
+        for chunk in self.chunks() {
+            out.write_all(chunk.as_bytes())?;
+            out.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: Escape> Debug for Page<E> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Page")
+            .field("shell", &self.shell)
+            .field("chunks", &self.chunks.len())
+            .finish()
+    }
+}
+
+/// An iterator over the rendered chunks of a [`Page`][Page].
+///
+/// [Page]: struct.Page.html
+pub struct Chunks<E: Escape = Html> {
+    shell: Option<String>,
+    chunks: VecDeque<Chunk>,
+    tail: Option<String>,
+    serializer: Serializer<E>,
+}
+
+impl<E: Escape> Iterator for Chunks<E> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if let Some(shell) = self.shell.take() {
+            return Some(shell);
+        }
+        match self.chunks.pop_front() {
+            Some(chunk) => Some(self.serializer.render(&*chunk())),
+            None => self.tail.take().filter(|tail| !tail.is_empty()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_chunks_are_rendered_lazily() {
+        let calls = Rc::new(Cell::new(0));
+        let counter = calls.clone();
+        let mut chunks = Page::new(html!(
+            <html lang="en">
+                <head><title>"Lazy"</title></head>
+                <body class="page"><nav>"Menu"</nav></body>
+            </html>
+        ))
+        .chunk(move || {
+            counter.set(counter.get() + 1);
+            html!(<main>"Content"</main>)
+        })
+        .chunks();
+
+        assert_eq!(
+            Some(
+                "<!DOCTYPE html><html lang=\"en\"><head><title>Lazy</title></head>\
+                 <body class=\"page\"><nav>Menu</nav>"
+                    .to_string()
+            ),
+            chunks.next()
+        );
+        assert_eq!(0, calls.get());
+        assert_eq!(Some("<main>Content</main>".to_string()), chunks.next());
+        assert_eq!(1, calls.get());
+        assert_eq!(Some("</body></html>".to_string()), chunks.next());
+        assert_eq!(None, chunks.next());
+    }
+
+    #[test]
+    fn test_chunks_without_body() {
+        let chunks: Vec<String> = Page::new(html!(<div>"Shell"</div>))
+            .chunk(|| html!(<p>"After"</p>))
+            .chunks()
+            .collect();
+        assert_eq!(
+            vec!["<!DOCTYPE html><div>Shell</div>", "<p>After</p>"],
+            chunks
+        );
+    }
+}
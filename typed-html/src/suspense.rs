@@ -0,0 +1,264 @@
+//! Children which are futures, for fetching data next to the markup which
+//! displays it.
+//!
+//! A [`Suspense`][Suspense] holds the futures for a page's async children.
+//! Each one is given to [`child()`][child], which leaves a placeholder in the
+//! tree, and [`render()`][render] renders the tree and returns a future which
+//! polls all of the children together and fills in their placeholders as
+//! they resolve.
+//!
+//! This doesn't depend on any particular async runtime: the futures are
+//! polled by whichever one awaits the render, and a timeout is given as a
+//! future too, like a runtime's sleep. Children which haven't resolved when
+//! the timeout does are rendered with the fallback instead.
+//!
+//! Rendering is to strings with the standard [`Html`][Html] escaping policy,
+//! so this is for server side rendering only.
+//!
+//! # Examples
+//!
+//! ```
+//! # use std::future::Future;
+//! # use std::pin::pin;
+//! # use std::task::{Context, Poll, Waker};
+//! # use typed_html::{html, text};
+//! # use typed_html::dom::DOMTree;
+//! use typed_html::suspense::Suspense;
+//!
+//! # fn block_on<F: Future>(future: F) -> F::Output {
+//! #     let mut future = pin!(future);
+//! #     loop {
+//! #         if let Poll::Ready(output) = future.as_mut().poll(&mut Context::from_waker(Waker::noop())) {
+//! #             return output;
+//! #         }
+//! #     }
+//! # }
+//! async fn fetch_user_count() -> u32 {
+//!     42
+//! }
+//!
+//! # fn main() {
+//! let suspense = Suspense::new();
+//! let doc: DOMTree<String> = html!(
+//!     <p>
+//!         "Users: "
+//!         { suspense.child(async {
+//!             let count = fetch_user_count().await;
+//!             html!(<b>{ text!("{}", count) }</b>)
+//!         }) }
+//!     </p>
+//! );
+//! assert_eq!("<p>Users: <b>42</b></p>", block_on(suspense.render(&*doc)));
+//! # }
+//! ```
+//!
+//! [Suspense]: struct.Suspense.html
+//! [child]: struct.Suspense.html#method.child
+//! [render]: struct.Suspense.html#method.render
+//! [Html]: ../escape/struct.Html.html
+
+use std::cell::RefCell;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::task::{Context, Poll};
+
+use crate::dom::{DOMTree, Node, UnsafeTextNode};
+
+type Child = Pin<Box<dyn Future<Output = String>>>;
+
+/// Counts `Suspense`s, so each one's placeholders are different from any
+/// other's.
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// The async children of a tree.
+///
+/// The `Suspense` is shared by reference while the tree is built, so it can
+/// be used in several places in the same `html!` invocation. Children can't
+/// add async children of their own.
+pub struct Suspense {
+    id: usize,
+    children: RefCell<Vec<Child>>,
+    fallback: Box<dyn Fn() -> DOMTree<String>>,
+}
+
+impl Suspense {
+    /// Construct a `Suspense` with an empty fallback.
+    pub fn new() -> Self {
+        Suspense {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+            children: RefCell::new(Vec::new()),
+            fallback: Box::new(|| Box::new(UnsafeTextNode::new(""))),
+        }
+    }
+
+    /// Set the function which builds what's rendered in place of children
+    /// which time out.
+    pub fn fallback<F>(mut self, fallback: F) -> Self
+    where
+        F: Fn() -> DOMTree<String> + 'static,
+    {
+        self.fallback = Box::new(fallback);
+        self
+    }
+
+    /// Add an async child, leaving a placeholder for it in the tree.
+    pub fn child<F, N>(&self, child: F) -> Box<UnsafeTextNode<String>>
+    where
+        F: Future<Output = Box<N>> + 'static,
+        N: Node<String> + ?Sized,
+    {
+        let mut children = self.children.borrow_mut();
+        let placeholder = placeholder(self.id, children.len());
+        children.push(Box::pin(async move { child.await.to_string() }));
+        Box::new(UnsafeTextNode::new(placeholder))
+    }
+
+    /// Render a tree, waiting for all of its async children.
+    pub fn render(self, node: &dyn Node<String>) -> Render {
+        self.render_timeout(node, None)
+    }
+
+    /// Render a tree, waiting for its async children until `timeout`
+    /// resolves, and rendering the fallback for any which haven't by then.
+    pub fn render_with_timeout<F>(self, node: &dyn Node<String>, timeout: F) -> Render
+    where
+        F: Future<Output = ()> + 'static,
+    {
+        self.render_timeout(node, Some(Box::pin(timeout)))
+    }
+
+    fn render_timeout(
+        self,
+        node: &dyn Node<String>,
+        timeout: Option<Pin<Box<dyn Future<Output = ()>>>>,
+    ) -> Render {
+        let children = self.children.into_inner();
+        Render {
+            id: self.id,
+            html: node.to_string(),
+            rendered: vec![None; children.len()],
+            children: children.into_iter().map(Some).collect(),
+            timeout,
+            fallback: self.fallback,
+        }
+    }
+}
+
+impl Default for Suspense {
+    fn default() -> Self {
+        Suspense::new()
+    }
+}
+
+impl Debug for Suspense {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Suspense")
+            .field("children", &self.children.borrow().len())
+            .finish()
+    }
+}
+
+fn placeholder(id: usize, index: usize) -> String {
+    format!("<!--suspense:{}:{}-->", id, index)
+}
+
+/// A future which renders a tree with its async children, from
+/// [`Suspense::render()`][render].
+///
+/// [render]: struct.Suspense.html#method.render
+pub struct Render {
+    id: usize,
+    html: String,
+    children: Vec<Option<Child>>,
+    rendered: Vec<Option<String>>,
+    timeout: Option<Pin<Box<dyn Future<Output = ()>>>>,
+    fallback: Box<dyn Fn() -> DOMTree<String>>,
+}
+
+impl Render {
+    fn finish(&mut self) -> String {
+        let mut html = std::mem::take(&mut self.html);
+        for (index, rendered) in std::mem::take(&mut self.rendered).into_iter().enumerate() {
+            let rendered = rendered.unwrap_or_else(|| (self.fallback)().to_string());
+            html = html.replacen(&placeholder(self.id, index), &rendered, 1);
+        }
+        html
+    }
+}
+
+impl Future for Render {
+    type Output = String;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<String> {
+        let this = self.get_mut();
+        let mut pending = false;
+        for (slot, rendered) in this.children.iter_mut().zip(this.rendered.iter_mut()) {
+            if let Some(child) = slot {
+                match child.as_mut().poll(cx) {
+                    Poll::Ready(html) => {
+                        *rendered = Some(html);
+                        *slot = None;
+                    }
+                    Poll::Pending => pending = true,
+                }
+            }
+        }
+        let timed_out = pending
+            && this
+                .timeout
+                .as_mut()
+                .is_some_and(|timeout| timeout.as_mut().poll(cx).is_ready());
+        if pending && !timed_out {
+            return Poll::Pending;
+        }
+        Poll::Ready(this.finish())
+    }
+}
+
+impl Debug for Render {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("Render")
+            .field("html", &self.html)
+            .field("rendered", &self.rendered)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use std::future::{pending, ready};
+    use std::pin::pin;
+    use std::task::Waker;
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        loop {
+            let mut cx = Context::from_waker(Waker::noop());
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn test_suspense_timeout() {
+        let suspense = Suspense::new().fallback(|| html!(<p>"Timed out"</p>));
+        let doc: DOMTree<String> = html!(
+            <div>
+                { suspense.child(async { html!(<p>"Ready"</p>) }) }
+                { suspense.child(async {
+                    pending::<()>().await;
+                    html!(<p>"Never"</p>)
+                }) }
+            </div>
+        );
+        assert_eq!(
+            "<div><p>Ready</p><p>Timed out</p></div>",
+            block_on(suspense.render_with_timeout(&*doc, ready(())))
+        );
+    }
+}
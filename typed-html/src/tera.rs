@@ -0,0 +1,86 @@
+//! Interop with [Tera] templates.
+//!
+//! Tera can't tell that a value is already HTML, so [`insert()`][insert] adds
+//! a rendered DOM tree to a Tera context, and the template writes it with the
+//! `safe` filter to stop it being escaped a second time:
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! use tera::{Context, Tera};
+//! use typed_html::tera::insert;
+//!
+//! # fn main() {
+//! let mut tera = Tera::default();
+//! tera.add_raw_template("page.html", "<main>{{ content | safe }}</main>")
+//!     .unwrap();
+//!
+//! let content: DOMTree<String> = html!(<p>"Tom & Jerry"</p>);
+//! let mut context = Context::new();
+//! insert(&mut context, "content", &*content);
+//! assert_eq!(
+//!     "<main><p>Tom &amp; Jerry</p></main>",
+//!     tera.render("page.html", &context).unwrap()
+//! );
+//! # }
+//! ```
+//!
+//! Going the other way, [`fragment()`][fragment] renders a Tera template into
+//! a node you can put in a DOM tree.
+//!
+//! This module is only available with the `tera` feature flag enabled.
+//!
+//! [Tera]: https://docs.rs/tera/
+//! [insert]: fn.insert.html
+//! [fragment]: fn.fragment.html
+
+use ::tera::{Context, Tera};
+
+use crate::dom::{Node, UnsafeTextNode};
+use crate::OutputType;
+
+/// Render a DOM tree into a Tera context under the given key.
+///
+/// Write it in the template with `{{ key | safe }}`, or turn off
+/// autoescaping for the template.
+pub fn insert<T: OutputType>(context: &mut Context, key: &str, node: &dyn Node<T>) {
+    context.insert(key, &node.to_string());
+}
+
+/// Render a Tera template into a node which can be a child of any element
+/// that accepts text.
+///
+/// The template's output is inserted into the tree as it is, like
+/// [`unsafe_text!`][unsafe_text], so it should come from a template which
+/// has autoescaping turned on, which is the default for `.html` templates.
+///
+/// Returns `Err` if the template fails to render.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use tera::{Context, Tera};
+/// use typed_html::tera::fragment;
+///
+/// # fn main() {
+/// let mut tera = Tera::default();
+/// tera.add_raw_template("greeting.html", "<b>{{ name }}</b>").unwrap();
+/// let mut context = Context::new();
+/// context.insert("name", "<Kitty>");
+///
+/// let greeting = fragment(&tera, "greeting.html", &context).unwrap();
+/// let doc: DOMTree<String> = html!(<p>"Hello, " { greeting }</p>);
+/// assert_eq!("<p>Hello, <b>&lt;Kitty&gt;</b></p>", doc.to_string());
+/// # }
+/// ```
+///
+/// [unsafe_text]: ../macro.unsafe_text.html
+pub fn fragment<T: OutputType>(
+    tera: &Tera,
+    name: &str,
+    context: &Context,
+) -> ::tera::Result<Box<UnsafeTextNode<T>>> {
+    Ok(Box::new(UnsafeTextNode::new(tera.render(name, context)?)))
+}
@@ -0,0 +1,113 @@
+//! Returning DOM trees from [Tide] endpoints.
+//!
+//! A `DOMTree<String>` converts into a Tide [`Response`][Response], with a
+//! `200 OK` status and an HTML content type, so an endpoint can return one
+//! as it is:
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::DOMTree;
+//! # fn main() {
+//! let mut app = tide::new();
+//! app.at("/").get(|_| async {
+//!     let doc: DOMTree<String> = html!(<p>"Hello Joe!"</p>);
+//!     Ok(doc)
+//! });
+//! # }
+//! ```
+//!
+//! To send it with another status, like a `404 Not Found` page, use
+//! [`response()`][response]. A tree whose root is an `<html>` element is
+//! sent with a `<!DOCTYPE html>` in front of it, so browsers don't render
+//! it in quirks mode.
+//!
+//! This module is only available with the `tide` feature flag enabled.
+//!
+//! [Tide]: https://docs.rs/tide/
+//! [Response]: https://docs.rs/tide/latest/tide/struct.Response.html
+//! [response]: fn.response.html
+
+use ::tide::http::mime;
+use ::tide::{Body, Response, StatusCode};
+
+use crate::dom::DOMTree;
+
+/// Make a response with the given status from a DOM tree.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use tide::StatusCode;
+/// use typed_html::tide::response;
+///
+/// # fn main() {
+/// let doc: DOMTree<String> = html!(<h1>"There's no cat here"</h1>);
+/// let res = response(StatusCode::NotFound, doc);
+/// assert_eq!(StatusCode::NotFound, res.status());
+/// # }
+/// ```
+pub fn response(status: StatusCode, tree: DOMTree<String>) -> Response {
+    let mut res = Response::new(status);
+    res.set_body(tree);
+    res
+}
+
+impl From<DOMTree<String>> for Body {
+    fn from(tree: DOMTree<String>) -> Self {
+        let mut html = tree.to_string();
+        if html.starts_with("<html") {
+            html.insert_str(0, "<!DOCTYPE html>");
+        }
+        let mut body = Body::from_string(html);
+        body.set_mime(mime::HTML);
+        body
+    }
+}
+
+impl From<DOMTree<String>> for Response {
+    fn from(tree: DOMTree<String>) -> Self {
+        response(StatusCode::Ok, tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use ::tide::http::{Method, Request, Url};
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = pin!(future);
+        loop {
+            let mut cx = Context::from_waker(Waker::noop());
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+        }
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let mut app = ::tide::new();
+        app.at("/").get(|_| async {
+            let doc: DOMTree<String> = html!(
+                <html><head><title>"Cats"</title></head><body/></html>
+            );
+            Ok(doc)
+        });
+        let url = Url::parse("http://example.com/").unwrap();
+        let mut res: ::tide::http::Response =
+            block_on(app.respond(Request::new(Method::Get, url))).unwrap();
+        assert_eq!(StatusCode::Ok, res.status());
+        assert_eq!(Some(mime::HTML), res.content_type());
+        assert_eq!(
+            "<!DOCTYPE html><html><head><title>Cats</title></head><body></body></html>",
+            block_on(res.body_string()).unwrap()
+        );
+    }
+}
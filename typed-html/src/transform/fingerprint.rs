@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use super::Transform;
+use crate::dom::UntypedElement;
+
+/// A transform which rewrites asset URLs to their fingerprinted names.
+///
+/// Given a manifest mapping asset URLs to their cache busting names (eg.
+/// `app.css` to `app.3f9ab2.css`), this rewrites the `src`, `href` and
+/// `srcset` attributes of every element which refer to an asset in the
+/// manifest. URLs are looked up exactly as they're written in the template,
+/// ignoring any query string or fragment, which are preserved.
+///
+/// If a fingerprinted URL can't be set, because the attribute's type won't
+/// accept it, the attribute is left alone and the error is kept, to be
+/// checked with [`errors()`][errors] afterwards.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use typed_html::transform::Fingerprint;
+///
+/// # fn main() {
+/// let mut fingerprint = Fingerprint::new(vec![
+///     ("/app.css", "/app.3f9ab2.css"),
+///     ("/logo.png", "/logo.81cc0d.png"),
+/// ]);
+/// let mut doc: DOMTree<String> = html!(
+///     <html>
+///         <head>
+///             <title>"Hello Kitty"</title>
+///             <link rel="stylesheet" href="/app.css"/>
+///         </head>
+///         <body>
+///             <img src="/logo.png" alt="Logo"/>
+///         </body>
+///     </html>
+/// );
+/// doc.apply(&mut fingerprint);
+/// assert!(fingerprint.errors().is_empty());
+/// # }
+/// ```
+///
+/// [errors]: #method.errors
+pub struct Fingerprint {
+    manifest: HashMap<String, String>,
+    errors: Vec<String>,
+}
+
+impl Fingerprint {
+    /// Construct a fingerprinting transform from a list of pairs of original
+    /// and fingerprinted URLs.
+    pub fn new<I, K, V>(manifest: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Fingerprint {
+            manifest: manifest
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// The attributes which couldn't be set to their fingerprinted URLs so
+    /// far, with the reason why.
+    pub fn errors(&self) -> &[String] {
+        &self.errors
+    }
+
+    /// Look up the fingerprinted version of a URL.
+    ///
+    /// Returns `None` if the URL isn't in the manifest.
+    pub fn rewrite(&self, url: &str) -> Option<String> {
+        let split = url.find(['?', '#']).unwrap_or(url.len());
+        let (path, rest) = url.split_at(split);
+        self.manifest
+            .get(path)
+            .map(|fingerprinted| format!("{}{}", fingerprinted, rest))
+    }
+
+    fn rewrite_srcset(&self, srcset: &str) -> Option<String> {
+        let mut changed = false;
+        let candidates: Vec<String> = srcset
+            .split(',')
+            .map(|candidate| {
+                let candidate = candidate.trim();
+                let split = candidate
+                    .find(char::is_whitespace)
+                    .unwrap_or(candidate.len());
+                let (url, descriptor) = candidate.split_at(split);
+                match self.rewrite(url) {
+                    Some(url) => {
+                        changed = true;
+                        format!("{}{}", url, descriptor)
+                    }
+                    None => candidate.to_string(),
+                }
+            })
+            .collect();
+        if changed {
+            Some(candidates.join(", "))
+        } else {
+            None
+        }
+    }
+
+    fn set_attribute(&mut self, element: &mut dyn UntypedElement, name: &str, value: &str) {
+        if let Err(err) = element.set_attribute(name, value) {
+            self.errors.push(format!(
+                "<{}> {}={:?}: {}",
+                element.name(),
+                name,
+                value,
+                err
+            ));
+        }
+    }
+}
+
+impl Transform for Fingerprint {
+    fn element(&mut self, element: &mut dyn UntypedElement) -> bool {
+        for name in &["src", "href"] {
+            if let Some(url) = element.attribute(name).and_then(|url| self.rewrite(&url)) {
+                self.set_attribute(element, name, &url);
+            }
+        }
+        if let Some(srcset) = element
+            .attribute("srcset")
+            .and_then(|srcset| self.rewrite_srcset(&srcset))
+        {
+            self.set_attribute(element, "srcset", &srcset);
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    #[test]
+    fn test_fingerprint() {
+        let mut fingerprint = Fingerprint::new(vec![
+            ("app.css", "app.3f9ab2.css"),
+            ("a.png", "a.1234.png"),
+            ("b.png", "b.5678.png"),
+        ]);
+        let mut link: DOMTree<String> = html!(<link rel="stylesheet" href="app.css?v=1"/>);
+        link.apply(&mut fingerprint);
+        assert_eq!(
+            "link { href: \"app.3f9ab2.css?v=1\", rel: \"stylesheet\" }",
+            format!("{:?}", link)
+        );
+
+        let mut doc: DOMTree<String> = html!(
+            <div>
+                <img src="a.png" srcset="a.png 1x, b.png 2x, c.png 3x"/>
+                <a href="page.html#top">"Elsewhere"</a>
+            </div>
+        );
+        doc.apply(&mut fingerprint);
+        assert_eq!(
+            "div { children: [\
+             img { src: \"a.1234.png\", srcset: \"a.1234.png 1x, b.5678.png 2x, c.png 3x\" }, \
+             a { href: \"page.html#top\", children: [\"Elsewhere\"] }] }",
+            format!("{:?}", doc)
+        );
+        assert!(fingerprint.errors().is_empty());
+    }
+
+    #[test]
+    fn test_fingerprint_errors() {
+        use crate::elements::FlowContent;
+
+        crate::declare_elements! {
+            counter {
+                src: u32,
+            } in [FlowContent];
+        }
+
+        let mut fingerprint = Fingerprint::new(vec![("1", "one")]);
+        let mut doc: DOMTree<String> = html!(<div><counter src=1u32/></div>);
+        doc.apply(&mut fingerprint);
+        assert_eq!("<div><counter src=\"1\"></counter></div>", doc.to_string());
+        assert_eq!(1, fingerprint.errors().len());
+        assert!(fingerprint.errors()[0].starts_with("<counter> src=\"one\": "));
+    }
+}
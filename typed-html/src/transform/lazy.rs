@@ -0,0 +1,104 @@
+use super::Transform;
+use crate::dom::UntypedElement;
+
+/// A transform which makes images and iframes load lazily.
+///
+/// This sets `loading="lazy"` on every `<img>` and `<iframe>` element, and
+/// `decoding="async"` on every `<img>`, unless the attribute has already been
+/// set. Media near the top of the page is usually visible immediately and
+/// shouldn't be deferred, so you can ask for the first few elements to be
+/// left alone with [`skip_first()`][skip_first].
+///
+/// Elements are counted in document order, and the count carries over
+/// between calls to [`apply()`][apply], so use a new `LazyLoad` for each
+/// document.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use typed_html::transform::LazyLoad;
+///
+/// # fn main() {
+/// let mut doc: DOMTree<String> = html!(
+///     <div>
+///         <img src="hero.jpg" alt="Above the fold"/>
+///         <img src="footer.jpg" alt="Below the fold"/>
+///     </div>
+/// );
+/// doc.apply(&mut LazyLoad::skip_first(1));
+/// # }
+/// ```
+///
+/// [skip_first]: #method.skip_first
+/// [apply]: ../dom/trait.Node.html#tymethod.apply
+#[derive(Default)]
+pub struct LazyLoad {
+    skip: usize,
+    seen: usize,
+}
+
+impl LazyLoad {
+    /// Construct a transform which makes all images and iframes lazy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Construct a transform which leaves the first `count` images and
+    /// iframes alone.
+    pub fn skip_first(count: usize) -> Self {
+        LazyLoad {
+            skip: count,
+            seen: 0,
+        }
+    }
+}
+
+impl Transform for LazyLoad {
+    fn element(&mut self, element: &mut dyn UntypedElement) -> bool {
+        let name = element.name();
+        if name != "img" && name != "iframe" {
+            return true;
+        }
+        self.seen += 1;
+        if self.seen <= self.skip {
+            return true;
+        }
+        if element.attribute("loading").is_none() {
+            element.set_attribute("loading", "lazy").unwrap();
+        }
+        if name == "img" && element.attribute("decoding").is_none() {
+            element.set_attribute("decoding", "async").unwrap();
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    #[test]
+    fn test_lazy_load() {
+        let mut doc: DOMTree<String> = html!(
+            <div>
+                <img src="a.png"/>
+                <p><img src="b.png" decoding="sync"/></p>
+                <iframe src="c.html"></iframe>
+                <img src="d.png" loading="eager"/>
+            </div>
+        );
+        doc.apply(&mut LazyLoad::skip_first(1));
+        assert_eq!(
+            "div { children: [\
+             img { src: \"a.png\" }, \
+             p { children: [img { decoding: \"sync\", loading: \"lazy\", src: \"b.png\" }] }, \
+             iframe { loading: \"lazy\", src: \"c.html\" }, \
+             img { decoding: \"async\", loading: \"eager\", src: \"d.png\" }] }",
+            format!("{:?}", doc)
+        );
+    }
+}
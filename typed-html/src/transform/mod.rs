@@ -0,0 +1,261 @@
+//! Transforms for rewriting DOM trees.
+//!
+//! A [`Transform`][Transform] is handed every node of a tree in document
+//! order, and can rewrite its attributes, text or comments, or ask for it to
+//! be removed, like stripping comments from pages in production.
+//! This is useful for cross-cutting passes over a whole page, which would
+//! otherwise have to be threaded through every template.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::{DOMTree, UntypedElement};
+//! use typed_html::transform::Transform;
+//!
+//! /// Open external links in a new tab.
+//! struct ExternalLinks;
+//!
+//! impl Transform for ExternalLinks {
+//!     fn element(&mut self, element: &mut dyn UntypedElement) -> bool {
+//!         let is_external = element.name() == "a"
+//!             && element
+//!                 .attribute("href")
+//!                 .map_or(false, |href| href.starts_with("https://"));
+//!         if is_external {
+//!             element.set_attribute("target", "_blank").unwrap();
+//!             element.set_attribute("rel", "noopener").unwrap();
+//!         }
+//!         true
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let mut doc: DOMTree<String> = html!(
+//!     <p><a href="https://example.com/">"Elsewhere"</a></p>
+//! );
+//! doc.apply(&mut ExternalLinks);
+//! assert!(doc.to_string().contains("rel=\"noopener\""));
+//! # }
+//! ```
+
+use crate::dom::UntypedElement;
+
+mod fingerprint;
+pub use self::fingerprint::Fingerprint;
+
+mod lazy;
+pub use self::lazy::LazyLoad;
+
+/// Trait for transforms over DOM trees.
+///
+/// Each method is called for the corresponding kind of node, in document
+/// order, with elements being visited before their children. Returning
+/// `false` asks for the node to be removed from its parent, which is ignored
+/// for the root of the tree and for required children such as `<head>` and
+/// `<body>`.
+///
+/// All methods default to leaving the node as it is.
+pub trait Transform {
+    /// Rewrite an element.
+    fn element(&mut self, _element: &mut dyn UntypedElement) -> bool {
+        true
+    }
+
+    /// Rewrite a text node.
+    fn text(&mut self, _text: &mut String) -> bool {
+        true
+    }
+
+    /// Rewrite an unescaped text node.
+    fn unsafe_text(&mut self, _text: &mut String) -> bool {
+        true
+    }
+
+    /// Rewrite the text of a comment.
+    fn comment(&mut self, _text: &mut String) -> bool {
+        true
+    }
+
+    /// Decide whether to keep a conditional comment, given its condition,
+    /// like `mso`. The trees inside a conditional comment which is kept are
+    /// transformed as usual.
+    fn conditional_comment(&mut self, _condition: &str) -> bool {
+        true
+    }
+
+    /// Compose this transform with another.
+    ///
+    /// The resulting transform visits the tree once, running `self` and then
+    /// `next` on each node. If `self` removes a node, `next` won't see it.
+    fn then<U>(self, next: U) -> Chain<Self, U>
+    where
+        Self: Sized,
+        U: Transform,
+    {
+        Chain(self, next)
+    }
+}
+
+/// Two transforms run one after the other.
+///
+/// This is constructed by [`Transform::then()`][then].
+///
+/// [then]: trait.Transform.html#method.then
+pub struct Chain<A, B>(A, B);
+
+impl<A, B> Transform for Chain<A, B>
+where
+    A: Transform,
+    B: Transform,
+{
+    fn element(&mut self, element: &mut dyn UntypedElement) -> bool {
+        self.0.element(element) && self.1.element(element)
+    }
+
+    fn text(&mut self, text: &mut String) -> bool {
+        self.0.text(text) && self.1.text(text)
+    }
+
+    fn unsafe_text(&mut self, text: &mut String) -> bool {
+        self.0.unsafe_text(text) && self.1.unsafe_text(text)
+    }
+
+    fn comment(&mut self, text: &mut String) -> bool {
+        self.0.comment(text) && self.1.comment(text)
+    }
+
+    fn conditional_comment(&mut self, condition: &str) -> bool {
+        self.0.conditional_comment(condition) && self.1.conditional_comment(condition)
+    }
+}
+
+/// A list of transforms assembled at runtime, run in order on each node.
+impl Transform for Vec<Box<dyn Transform>> {
+    fn element(&mut self, element: &mut dyn UntypedElement) -> bool {
+        self.iter_mut().all(|transform| transform.element(element))
+    }
+
+    fn text(&mut self, text: &mut String) -> bool {
+        self.iter_mut().all(|transform| transform.text(text))
+    }
+
+    fn unsafe_text(&mut self, text: &mut String) -> bool {
+        self.iter_mut().all(|transform| transform.unsafe_text(text))
+    }
+
+    fn comment(&mut self, text: &mut String) -> bool {
+        self.iter_mut().all(|transform| transform.comment(text))
+    }
+
+    fn conditional_comment(&mut self, condition: &str) -> bool {
+        self.iter_mut()
+            .all(|transform| transform.conditional_comment(condition))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    struct StripHidden;
+
+    impl Transform for StripHidden {
+        fn element(&mut self, element: &mut dyn UntypedElement) -> bool {
+            element.attribute("hidden").is_none()
+        }
+    }
+
+    struct Shout;
+
+    impl Transform for Shout {
+        fn text(&mut self, text: &mut String) -> bool {
+            *text = text.to_uppercase();
+            true
+        }
+    }
+
+    #[test]
+    fn test_remove_and_rewrite() {
+        let mut doc: DOMTree<String> = html!(
+            <div>
+                <p>"hello"</p>
+                <p hidden=true>"secret"</p>
+            </div>
+        );
+        let mut transform = StripHidden.then(Shout);
+        assert!(doc.apply(&mut transform));
+        assert_eq!("<div><p>HELLO</p></div>", doc.to_string());
+    }
+
+    #[test]
+    fn test_strip_comments() {
+        struct StripComments;
+
+        impl Transform for StripComments {
+            fn comment(&mut self, _text: &mut String) -> bool {
+                false
+            }
+
+            fn conditional_comment(&mut self, condition: &str) -> bool {
+                condition == "mso"
+            }
+        }
+
+        let mut doc: DOMTree<String> = html!(
+            <div>
+                { crate::comment!("build 1234") }
+                <p>"Hello"</p>
+                { crate::conditional_comment!("mso", html!(<p hidden=true>"Outlook"</p>)) }
+                { crate::conditional_comment!("IE", html!(<p>"Old"</p>)) }
+            </div>
+        );
+        doc.apply(&mut StripComments.then(StripHidden));
+        assert_eq!(
+            "<div><p>Hello</p><!--[if mso]><![endif]--></div>",
+            doc.to_string()
+        );
+    }
+
+    #[test]
+    fn test_runtime_pipeline() {
+        let mut doc: DOMTree<String> = html!(
+            <div><span hidden=true>"no"</span>"yes"</div>
+        );
+        let mut pipeline: Vec<Box<dyn Transform>> = vec![Box::new(StripHidden), Box::new(Shout)];
+        doc.apply(&mut pipeline);
+        assert_eq!("<div>YES</div>", doc.to_string());
+    }
+
+    #[test]
+    fn test_set_attribute_errors() {
+        struct SetBogus(Vec<String>);
+
+        impl Transform for SetBogus {
+            fn element(&mut self, element: &mut dyn UntypedElement) -> bool {
+                if let Err(err) = element.set_attribute("href", "") {
+                    self.0.push(err);
+                }
+                if let Err(err) = element.set_attribute("id", "1nvalid") {
+                    self.0.push(err);
+                }
+                true
+            }
+        }
+
+        let mut doc: DOMTree<String> = html!(<p/>);
+        let mut transform = SetBogus(Vec::new());
+        doc.apply(&mut transform);
+        assert_eq!(
+            vec![
+                "<p> has no attribute \"href\"".to_string(),
+                "<p id=\"1nvalid\"> failed to parse attribute value: \
+                 ID must start with an alphabetic character"
+                    .to_string(),
+            ],
+            transform.0
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+use strum_macros::*;
+
+/// The value of an `autocomplete` attribute on a form control.
+///
+/// This is either `on` or `off`, or an autofill hint: a field name, like
+/// `email` or `cc-number`, optionally preceded by a `section-*` name to tell
+/// apart several forms on a page, `shipping` or `billing`, and for contact
+/// fields like `tel`, the kind of contact, and optionally followed by
+/// `webauthn`.
+///
+/// # Examples
+///
+/// ```
+/// use typed_html::types::{AddressType, Autocomplete, AutofillField, ContactType};
+///
+/// # fn main() {
+/// let hint: Autocomplete = "section-guest billing mobile tel".parse().unwrap();
+/// assert!(
+///     hint == Autocomplete::Hint {
+///         section: Some("guest".to_string()),
+///         address: Some(AddressType::Billing),
+///         contact: Some(ContactType::Mobile),
+///         field: AutofillField::Tel,
+///         webauthn: false,
+///     }
+/// );
+///
+/// // Only contact fields can have a contact type.
+/// assert!("work cc-number".parse::<Autocomplete>().is_err());
+/// # }
+/// ```
+#[derive(PartialEq, Eq)]
+pub enum Autocomplete {
+    On,
+    Off,
+    Hint {
+        section: Option<String>,
+        address: Option<AddressType>,
+        contact: Option<ContactType>,
+        field: AutofillField,
+        webauthn: bool,
+    },
+}
+
+impl From<AutofillField> for Autocomplete {
+    fn from(field: AutofillField) -> Self {
+        Autocomplete::Hint {
+            section: None,
+            address: None,
+            contact: None,
+            field,
+            webauthn: false,
+        }
+    }
+}
+
+impl FromStr for Autocomplete {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        match s.trim() {
+            "on" => return Ok(Autocomplete::On),
+            "off" => return Ok(Autocomplete::Off),
+            _ => (),
+        }
+        let mut tokens = s.split_whitespace().peekable();
+        let mut section = None;
+        if let Some(name) = tokens
+            .peek()
+            .and_then(|token| token.strip_prefix("section-"))
+        {
+            section = Some(name.to_string());
+            tokens.next();
+        }
+        let address = tokens.peek().and_then(|token| token.parse().ok());
+        if address.is_some() {
+            tokens.next();
+        }
+        let contact = tokens.peek().and_then(|token| token.parse().ok());
+        if contact.is_some() {
+            tokens.next();
+        }
+        let field: AutofillField = match tokens.next() {
+            None => return Err("autocomplete hint must include a field name"),
+            Some(token) => token
+                .parse()
+                .map_err(|_| "autocomplete hint has an unknown field name")?,
+        };
+        if contact.is_some() && !field.is_contact() {
+            return Err(
+                "only contact fields like tel and email can be home, work, mobile, fax or pager",
+            );
+        }
+        let webauthn = tokens.peek() == Some(&"webauthn");
+        if webauthn {
+            tokens.next();
+        }
+        if tokens.next().is_some() {
+            return Err("autocomplete hint has tokens after the field name");
+        }
+        Ok(Autocomplete::Hint {
+            section,
+            address,
+            contact,
+            field,
+            webauthn,
+        })
+    }
+}
+
+impl Display for Autocomplete {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            Autocomplete::On => f.write_str("on"),
+            Autocomplete::Off => f.write_str("off"),
+            Autocomplete::Hint {
+                section,
+                address,
+                contact,
+                field,
+                webauthn,
+            } => {
+                if let Some(section) = section {
+                    write!(f, "section-{} ", section)?;
+                }
+                if let Some(address) = address {
+                    write!(f, "{} ", address)?;
+                }
+                if let Some(contact) = contact {
+                    write!(f, "{} ", contact)?;
+                }
+                Display::fmt(field, f)?;
+                if *webauthn {
+                    f.write_str(" webauthn")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum AddressType {
+    #[strum(to_string = "shipping")]
+    Shipping,
+    #[strum(to_string = "billing")]
+    Billing,
+}
+
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum ContactType {
+    #[strum(to_string = "home")]
+    Home,
+    #[strum(to_string = "work")]
+    Work,
+    #[strum(to_string = "mobile")]
+    Mobile,
+    #[strum(to_string = "fax")]
+    Fax,
+    #[strum(to_string = "pager")]
+    Pager,
+}
+
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum AutofillField {
+    #[strum(to_string = "name")]
+    Name,
+    #[strum(to_string = "honorific-prefix")]
+    HonorificPrefix,
+    #[strum(to_string = "given-name")]
+    GivenName,
+    #[strum(to_string = "additional-name")]
+    AdditionalName,
+    #[strum(to_string = "family-name")]
+    FamilyName,
+    #[strum(to_string = "honorific-suffix")]
+    HonorificSuffix,
+    #[strum(to_string = "nickname")]
+    Nickname,
+    #[strum(to_string = "username")]
+    Username,
+    #[strum(to_string = "new-password")]
+    NewPassword,
+    #[strum(to_string = "current-password")]
+    CurrentPassword,
+    #[strum(to_string = "one-time-code")]
+    OneTimeCode,
+    #[strum(to_string = "organization-title")]
+    OrganizationTitle,
+    #[strum(to_string = "organization")]
+    Organization,
+    #[strum(to_string = "street-address")]
+    StreetAddress,
+    #[strum(to_string = "address-line1")]
+    AddressLine1,
+    #[strum(to_string = "address-line2")]
+    AddressLine2,
+    #[strum(to_string = "address-line3")]
+    AddressLine3,
+    #[strum(to_string = "address-level4")]
+    AddressLevel4,
+    #[strum(to_string = "address-level3")]
+    AddressLevel3,
+    #[strum(to_string = "address-level2")]
+    AddressLevel2,
+    #[strum(to_string = "address-level1")]
+    AddressLevel1,
+    #[strum(to_string = "country")]
+    Country,
+    #[strum(to_string = "country-name")]
+    CountryName,
+    #[strum(to_string = "postal-code")]
+    PostalCode,
+    #[strum(to_string = "cc-name")]
+    CcName,
+    #[strum(to_string = "cc-given-name")]
+    CcGivenName,
+    #[strum(to_string = "cc-additional-name")]
+    CcAdditionalName,
+    #[strum(to_string = "cc-family-name")]
+    CcFamilyName,
+    #[strum(to_string = "cc-number")]
+    CcNumber,
+    #[strum(to_string = "cc-exp")]
+    CcExp,
+    #[strum(to_string = "cc-exp-month")]
+    CcExpMonth,
+    #[strum(to_string = "cc-exp-year")]
+    CcExpYear,
+    #[strum(to_string = "cc-csc")]
+    CcCsc,
+    #[strum(to_string = "cc-type")]
+    CcType,
+    #[strum(to_string = "transaction-currency")]
+    TransactionCurrency,
+    #[strum(to_string = "transaction-amount")]
+    TransactionAmount,
+    #[strum(to_string = "language")]
+    Language,
+    #[strum(to_string = "bday")]
+    Bday,
+    #[strum(to_string = "bday-day")]
+    BdayDay,
+    #[strum(to_string = "bday-month")]
+    BdayMonth,
+    #[strum(to_string = "bday-year")]
+    BdayYear,
+    #[strum(to_string = "sex")]
+    Sex,
+    #[strum(to_string = "url")]
+    Url,
+    #[strum(to_string = "photo")]
+    Photo,
+    // Contact fields, which must stay last for `is_contact()`.
+    #[strum(to_string = "tel")]
+    Tel,
+    #[strum(to_string = "tel-country-code")]
+    TelCountryCode,
+    #[strum(to_string = "tel-national")]
+    TelNational,
+    #[strum(to_string = "tel-area-code")]
+    TelAreaCode,
+    #[strum(to_string = "tel-local")]
+    TelLocal,
+    #[strum(to_string = "tel-local-prefix")]
+    TelLocalPrefix,
+    #[strum(to_string = "tel-local-suffix")]
+    TelLocalSuffix,
+    #[strum(to_string = "tel-extension")]
+    TelExtension,
+    #[strum(to_string = "email")]
+    Email,
+    #[strum(to_string = "impp")]
+    Impp,
+}
+
+impl AutofillField {
+    /// Test whether this is a contact field, which can be qualified with a
+    /// [`ContactType`][ContactType].
+    ///
+    /// [ContactType]: enum.ContactType.html
+    pub fn is_contact(&self) -> bool {
+        *self >= AutofillField::Tel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_autocomplete_round_trip() {
+        for valid in &[
+            "on",
+            "off",
+            "email",
+            "shipping street-address",
+            "section-blue billing cc-number",
+            "work email",
+            "username webauthn",
+        ] {
+            assert_eq!(
+                *valid,
+                valid.parse::<Autocomplete>().unwrap().to_string(),
+                "{}",
+                valid
+            );
+        }
+        for invalid in &[
+            "",
+            "emial",
+            "billing",
+            "mobile postal-code",
+            "email shipping",
+            "on email",
+        ] {
+            assert!(invalid.parse::<Autocomplete>().is_err(), "{}", invalid);
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use std::fmt::{Display, Error, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// A valid CSS colour.
+///
+/// A colour is either a hex colour with three, four, six or eight digits, one
+/// of the functional notations `rgb()`, `rgba()`, `hsl()` and `hsla()`, or a
+/// named colour such as `rebeccapurple`, `transparent` or `currentcolor`.
+///
+/// String literals passed to colour attributes in the `html!` macro are
+/// checked at compile time.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Color(String);
+
+impl Color {
+    /// Construct a new colour from a string.
+    ///
+    /// Returns `Err` if the provided string is invalid.
+    pub fn try_new<S: Into<String>>(color: S) -> Result<Self, &'static str> {
+        let color = color.into();
+        Self::validate(&color)?;
+        Ok(Color(color))
+    }
+
+    /// Construct a new colour from a string.
+    ///
+    /// Panics if the provided string is invalid.
+    pub fn new<S: Into<String>>(color: S) -> Self {
+        let color = color.into();
+        Self::try_new(color.clone()).unwrap_or_else(|err| {
+            panic!(
+                "typed_html::types::Color: {:?} is not a valid colour: {}",
+                color, err
+            )
+        })
+    }
+
+    /// Construct a hex colour from its red, green and blue components.
+    pub fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Color(format!("#{:02x}{:02x}{:02x}", red, green, blue))
+    }
+
+    /// Check whether a string is a valid colour.
+    ///
+    /// This is a `const fn` so that the `html!` macro can check string
+    /// literals at compile time.
+    pub const fn validate(color: &str) -> Result<(), &'static str> {
+        let bytes = color.as_bytes();
+        if bytes.is_empty() {
+            return Err("colour cannot be empty");
+        }
+        if bytes[0] == b'#' {
+            return validate_hex(bytes);
+        }
+        if bytes[bytes.len() - 1] == b')' {
+            return validate_function(bytes);
+        }
+        let mut index = 0;
+        while index < NAMED_COLORS.len() {
+            if eq_ignore_case(bytes, NAMED_COLORS[index].as_bytes()) {
+                return Ok(());
+            }
+            index += 1;
+        }
+        Err("colour must be a hex colour, rgb(), rgba(), hsl(), hsla() or a named colour")
+    }
+}
+
+const fn eq_ignore_case(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < left.len() {
+        if !left[index].eq_ignore_ascii_case(&right[index]) {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+const fn validate_hex(bytes: &[u8]) -> Result<(), &'static str> {
+    let digits = bytes.len() - 1;
+    if digits != 3 && digits != 4 && digits != 6 && digits != 8 {
+        return Err("hex colour must have 3, 4, 6 or 8 digits");
+    }
+    let mut index = 1;
+    while index < bytes.len() {
+        if !bytes[index].is_ascii_hexdigit() {
+            return Err("hex colour can only contain hexadecimal digits");
+        }
+        index += 1;
+    }
+    Ok(())
+}
+
+const fn skip_whitespace(bytes: &[u8], mut index: usize) -> usize {
+    while index < bytes.len() && bytes[index].is_ascii_whitespace() {
+        index += 1;
+    }
+    index
+}
+
+const fn validate_function(bytes: &[u8]) -> Result<(), &'static str> {
+    let mut index = 0;
+    while index < bytes.len() && bytes[index] != b'(' {
+        index += 1;
+    }
+    let (name, _) = bytes.split_at(index);
+    if !eq_ignore_case(name, b"rgb")
+        && !eq_ignore_case(name, b"rgba")
+        && !eq_ignore_case(name, b"hsl")
+        && !eq_ignore_case(name, b"hsla")
+    {
+        return Err("colour function must be rgb(), rgba(), hsl() or hsla()");
+    }
+    let end = bytes.len() - 1;
+    index += 1;
+    let mut components = 0;
+    loop {
+        index = skip_whitespace(bytes, index);
+        if index == end {
+            break;
+        }
+        // A number, with an optional sign and fraction.
+        if bytes[index] == b'+' || bytes[index] == b'-' {
+            index += 1;
+        }
+        let mut digits = 0;
+        while index < end && (bytes[index].is_ascii_digit() || bytes[index] == b'.') {
+            if bytes[index] != b'.' {
+                digits += 1;
+            }
+            index += 1;
+        }
+        if digits == 0 {
+            return Err("colour function arguments must be numbers");
+        }
+        // An optional unit, like `%` or `deg`.
+        while index < end && (bytes[index] == b'%' || bytes[index].is_ascii_alphabetic()) {
+            index += 1;
+        }
+        components += 1;
+        index = skip_whitespace(bytes, index);
+        if index < end && (bytes[index] == b',' || bytes[index] == b'/') {
+            index += 1;
+        }
+    }
+    if components != 3 && components != 4 {
+        return Err("colour function must have 3 or 4 arguments");
+    }
+    Ok(())
+}
+
+impl FromStr for Color {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::try_new(s)
+    }
+}
+
+impl<'a> From<&'a str> for Color {
+    fn from(str: &'a str) -> Self {
+        Color::from_str(str).unwrap()
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((red, green, blue): (u8, u8, u8)) -> Self {
+        Color::rgb(red, green, blue)
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Deref for Color {
+    type Target = String;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+const NAMED_COLORS: &[&str] = &[
+    "aliceblue",
+    "antiquewhite",
+    "aqua",
+    "aquamarine",
+    "azure",
+    "beige",
+    "bisque",
+    "black",
+    "blanchedalmond",
+    "blue",
+    "blueviolet",
+    "brown",
+    "burlywood",
+    "cadetblue",
+    "chartreuse",
+    "chocolate",
+    "coral",
+    "cornflowerblue",
+    "cornsilk",
+    "crimson",
+    "currentcolor",
+    "cyan",
+    "darkblue",
+    "darkcyan",
+    "darkgoldenrod",
+    "darkgray",
+    "darkgreen",
+    "darkgrey",
+    "darkkhaki",
+    "darkmagenta",
+    "darkolivegreen",
+    "darkorange",
+    "darkorchid",
+    "darkred",
+    "darksalmon",
+    "darkseagreen",
+    "darkslateblue",
+    "darkslategray",
+    "darkslategrey",
+    "darkturquoise",
+    "darkviolet",
+    "deeppink",
+    "deepskyblue",
+    "dimgray",
+    "dimgrey",
+    "dodgerblue",
+    "firebrick",
+    "floralwhite",
+    "forestgreen",
+    "fuchsia",
+    "gainsboro",
+    "ghostwhite",
+    "gold",
+    "goldenrod",
+    "gray",
+    "green",
+    "greenyellow",
+    "grey",
+    "honeydew",
+    "hotpink",
+    "indianred",
+    "indigo",
+    "ivory",
+    "khaki",
+    "lavender",
+    "lavenderblush",
+    "lawngreen",
+    "lemonchiffon",
+    "lightblue",
+    "lightcoral",
+    "lightcyan",
+    "lightgoldenrodyellow",
+    "lightgray",
+    "lightgreen",
+    "lightgrey",
+    "lightpink",
+    "lightsalmon",
+    "lightseagreen",
+    "lightskyblue",
+    "lightslategray",
+    "lightslategrey",
+    "lightsteelblue",
+    "lightyellow",
+    "lime",
+    "limegreen",
+    "linen",
+    "magenta",
+    "maroon",
+    "mediumaquamarine",
+    "mediumblue",
+    "mediumorchid",
+    "mediumpurple",
+    "mediumseagreen",
+    "mediumslateblue",
+    "mediumspringgreen",
+    "mediumturquoise",
+    "mediumvioletred",
+    "midnightblue",
+    "mintcream",
+    "mistyrose",
+    "moccasin",
+    "navajowhite",
+    "navy",
+    "oldlace",
+    "olive",
+    "olivedrab",
+    "orange",
+    "orangered",
+    "orchid",
+    "palegoldenrod",
+    "palegreen",
+    "paleturquoise",
+    "palevioletred",
+    "papayawhip",
+    "peachpuff",
+    "peru",
+    "pink",
+    "plum",
+    "powderblue",
+    "purple",
+    "rebeccapurple",
+    "red",
+    "rosybrown",
+    "royalblue",
+    "saddlebrown",
+    "salmon",
+    "sandybrown",
+    "seagreen",
+    "seashell",
+    "sienna",
+    "silver",
+    "skyblue",
+    "slateblue",
+    "slategray",
+    "slategrey",
+    "snow",
+    "springgreen",
+    "steelblue",
+    "tan",
+    "teal",
+    "thistle",
+    "tomato",
+    "transparent",
+    "turquoise",
+    "violet",
+    "wheat",
+    "white",
+    "whitesmoke",
+    "yellow",
+    "yellowgreen",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_color() {
+        for valid in &[
+            "#fff",
+            "#FFF8",
+            "#663399",
+            "#66339980",
+            "rgb(255, 0, 0)",
+            "rgba(255,0,0,0.5)",
+            "rgb(100% 0% 0% / 50%)",
+            "hsl(270deg 50% 40%)",
+            "RebeccaPurple",
+            "currentcolor",
+        ] {
+            assert_eq!(Ok(()), Color::validate(valid), "{}", valid);
+        }
+        for invalid in &[
+            "",
+            "#ff",
+            "#ggg",
+            "rgb(255, 0)",
+            "rgb(red, 0, 0)",
+            "cmyk(0, 0, 0, 0)",
+            "blurple",
+        ] {
+            assert!(Color::validate(invalid).is_err(), "{}", invalid);
+        }
+        assert_eq!("#0a80ff", Color::rgb(10, 128, 255).to_string());
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt::{Display, Error, Formatter};
+use std::ops::Deref;
+use std::str::FromStr;
+
+/// A valid custom element name, like `fancy-button`, for the `is` attribute
+/// of a customised built-in element.
+///
+/// A custom element name starts with a lowercase ASCII letter, contains a
+/// `-`, and has no uppercase ASCII letters. ASCII characters other than
+/// letters and digits are limited to `-`, `.` and `_`. A few names which SVG
+/// and MathML already use, like `font-face`, are reserved.
+///
+/// String literals passed to the `is` attribute in the `html!` macro are
+/// checked at compile time.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct CustomElementName(String);
+
+/// Names which match the syntax of a custom element name but are taken.
+const RESERVED_NAMES: &[&str] = &[
+    "annotation-xml",
+    "color-profile",
+    "font-face",
+    "font-face-src",
+    "font-face-uri",
+    "font-face-format",
+    "font-face-name",
+    "missing-glyph",
+];
+
+impl CustomElementName {
+    /// Construct a new custom element name from a string.
+    ///
+    /// Returns `Err` if the provided string is invalid.
+    pub fn try_new<S: Into<String>>(name: S) -> Result<Self, &'static str> {
+        let name = name.into();
+        Self::validate(&name)?;
+        Ok(CustomElementName(name))
+    }
+
+    /// Construct a new custom element name from a string.
+    ///
+    /// Panics if the provided string is invalid.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        let name = name.into();
+        Self::try_new(name.clone()).unwrap_or_else(|err| {
+            panic!(
+                "typed_html::types::CustomElementName: {:?} is not a valid custom element name: {}",
+                name, err
+            )
+        })
+    }
+
+    /// Check whether a string is a valid custom element name.
+    ///
+    /// This is a `const fn` so that the `html!` macro can check string
+    /// literals at compile time.
+    pub const fn validate(name: &str) -> Result<(), &'static str> {
+        let bytes = name.as_bytes();
+        if bytes.is_empty() || !bytes[0].is_ascii_lowercase() {
+            return Err("custom element name must start with a lowercase ASCII letter");
+        }
+        let mut has_hyphen = false;
+        let mut index = 0;
+        while index < bytes.len() {
+            match bytes[index] {
+                b'-' => has_hyphen = true,
+                b'A'..=b'Z' => {
+                    return Err("custom element name cannot contain uppercase ASCII letters")
+                }
+                b'a'..=b'z' | b'0'..=b'9' | b'.' | b'_' | 0x80..=0xff => {}
+                _ => {
+                    return Err(
+                        "custom element name can only contain letters, digits, '-', '.' and '_'",
+                    )
+                }
+            }
+            index += 1;
+        }
+        if !has_hyphen {
+            return Err("custom element name must contain a '-'");
+        }
+        let mut index = 0;
+        while index < RESERVED_NAMES.len() {
+            if eq(bytes, RESERVED_NAMES[index].as_bytes()) {
+                return Err("custom element name is reserved");
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+}
+
+const fn eq(left: &[u8], right: &[u8]) -> bool {
+    if left.len() != right.len() {
+        return false;
+    }
+    let mut index = 0;
+    while index < left.len() {
+        if left[index] != right[index] {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+impl FromStr for CustomElementName {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CustomElementName::try_new(s)
+    }
+}
+
+impl<'a> From<&'a str> for CustomElementName {
+    fn from(str: &'a str) -> Self {
+        CustomElementName::from_str(str).unwrap()
+    }
+}
+
+impl Display for CustomElementName {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Deref for CustomElementName {
+    type Target = String;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_custom_element_name() {
+        for valid in &["fancy-button", "x-", "my-élément", "a.b-c_1"] {
+            assert_eq!(Ok(()), CustomElementName::validate(valid), "{:?}", valid);
+        }
+        for invalid in &[
+            "",
+            "button",
+            "Fancy-button",
+            "fancy-Button",
+            "-fancy",
+            "1-up",
+            "a b-c",
+            "font-face",
+        ] {
+            assert!(
+                CustomElementName::validate(invalid).is_err(),
+                "{:?}",
+                invalid
+            );
+        }
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+/// A calendar date, in the `YYYY-MM-DD` format used by the value of an
+/// `<input type="date">`.
+///
+/// # Examples
+///
+/// ```
+/// use typed_html::types::Date;
+///
+/// let date: Date = "2019-03-28".parse().unwrap();
+/// assert_eq!(Some(date), Date::new(2019, 3, 28));
+/// assert_eq!("2019-03-28", date.to_string());
+/// assert!("2019-02-29".parse::<Date>().is_err());
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct Date {
+    year: u32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Construct a date, or return `None` if there's no such day.
+    pub fn new(year: u32, month: u8, day: u8) -> Option<Self> {
+        if year == 0 || month == 0 || month > 12 || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// The year.
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    /// The month, from 1 to 12.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// The day of the month, from 1.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+}
+
+fn days_in_month(year: u32, month: u8) -> u8 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => {
+            29
+        }
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl FromStr for Date {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(3, '-');
+        let mut part = |min_len: usize| {
+            parts
+                .next()
+                .filter(|part| part.len() >= min_len && part.bytes().all(|b| b.is_ascii_digit()))
+                .ok_or("date must be written as YYYY-MM-DD")
+        };
+        let (year, month, day) = (part(4)?, part(2)?, part(2)?);
+        if month.len() != 2 || day.len() != 2 {
+            return Err("date must be written as YYYY-MM-DD");
+        }
+        let year = year.parse().map_err(|_| "year is too large")?;
+        Date::new(year, month.parse().unwrap(), day.parse().unwrap()).ok_or("date does not exist")
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(Date::new(2000, 2, 29), "2000-02-29".parse().ok());
+        assert_eq!(Date::new(12019, 12, 31), "12019-12-31".parse().ok());
+        for invalid in &[
+            "",
+            "2019",
+            "19-03-28",
+            "2019-3-28",
+            "2019-03-28T12:00",
+            "2019-13-01",
+            "1900-02-29",
+            "0000-01-01",
+            "+2019-03-28",
+        ] {
+            assert!(invalid.parse::<Date>().is_err(), "{:?}", invalid);
+        }
+    }
+}
@@ -0,0 +1,144 @@
+use std::convert::TryFrom;
+use std::fmt::{Display, Error, Formatter};
+use std::num::TryFromIntError;
+use std::str::FromStr;
+
+/// A length for attributes like `width` and `height`.
+///
+/// HTML dimensions are written without a unit: `width="100"` is 100 pixels,
+/// and `width="100px"` is an error. Some legacy attributes also accept a
+/// percentage, like `width="50%"`.
+///
+/// Unsigned integers convert into a `Dimension` in pixels: `u8`, `u16` and
+/// `u32` with `From`, and `u64` and `usize` with `TryFrom`, failing if the
+/// value doesn't fit in a `u32`. An unsuffixed integer like `width=100` in the
+/// `html!` macro is taken to be a `u32`. String literals are checked at
+/// compile time, including whether the attribute allows percentages.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Dimension {
+    Pixels(u32),
+    Percentage(u32),
+}
+
+impl Dimension {
+    /// Check whether a string is a valid dimension, in pixels or as a
+    /// percentage.
+    ///
+    /// This is a `const fn` so that the `html!` macro can check string
+    /// literals at compile time.
+    pub const fn validate(dimension: &str) -> Result<(), &'static str> {
+        let bytes = dimension.as_bytes();
+        if bytes.is_empty() {
+            return Err("dimension cannot be empty");
+        }
+        let mut end = bytes.len();
+        if bytes[end - 1] == b'%' {
+            end -= 1;
+        }
+        if end == 0 {
+            return Err("dimension must start with a number");
+        }
+        if end > 9 {
+            return Err("dimension is too large");
+        }
+        let mut index = 0;
+        while index < end {
+            if !bytes[index].is_ascii_digit() {
+                return Err(
+                    "dimension must be a whole number of pixels without a unit, or a percentage",
+                );
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+
+    /// Check whether a string is a valid dimension in pixels, for attributes
+    /// which don't accept percentages.
+    pub const fn validate_pixels(dimension: &str) -> Result<(), &'static str> {
+        if let Err(err) = Self::validate(dimension) {
+            return Err(err);
+        }
+        let bytes = dimension.as_bytes();
+        if bytes[bytes.len() - 1] == b'%' {
+            return Err("dimension must be in pixels, not a percentage");
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Dimension {
+    type Err = &'static str;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::validate(s)?;
+        match s.strip_suffix('%') {
+            Some(percentage) => Ok(Dimension::Percentage(percentage.parse().unwrap())),
+            None => Ok(Dimension::Pixels(s.parse().unwrap())),
+        }
+    }
+}
+
+impl From<u32> for Dimension {
+    fn from(pixels: u32) -> Self {
+        Dimension::Pixels(pixels)
+    }
+}
+
+impl From<u16> for Dimension {
+    fn from(pixels: u16) -> Self {
+        Dimension::Pixels(pixels.into())
+    }
+}
+
+impl From<u8> for Dimension {
+    fn from(pixels: u8) -> Self {
+        Dimension::Pixels(pixels.into())
+    }
+}
+
+impl TryFrom<u64> for Dimension {
+    type Error = TryFromIntError;
+    fn try_from(pixels: u64) -> Result<Self, Self::Error> {
+        u32::try_from(pixels).map(Dimension::Pixels)
+    }
+}
+
+impl TryFrom<usize> for Dimension {
+    type Error = TryFromIntError;
+    fn try_from(pixels: usize) -> Result<Self, Self::Error> {
+        u32::try_from(pixels).map(Dimension::Pixels)
+    }
+}
+
+impl Display for Dimension {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            Dimension::Pixels(pixels) => write!(f, "{}", pixels),
+            Dimension::Percentage(percentage) => write!(f, "{}%", percentage),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dimension() {
+        assert_eq!(Ok(Dimension::Pixels(100)), "100".parse());
+        assert_eq!(Ok(Dimension::Percentage(50)), "50%".parse());
+        assert!("100px".parse::<Dimension>().is_err());
+        assert!("%".parse::<Dimension>().is_err());
+        assert!("-1".parse::<Dimension>().is_err());
+        assert!(Dimension::validate_pixels("50%").is_err());
+        assert_eq!("50%", Dimension::Percentage(50).to_string());
+    }
+
+    #[test]
+    fn test_dimension_from_integers() {
+        assert_eq!(Dimension::Pixels(8), 8u8.into());
+        assert_eq!(Dimension::Pixels(640), 640u16.into());
+        assert_eq!(Ok(Dimension::Pixels(480)), Dimension::try_from(480usize));
+        assert!(Dimension::try_from(u64::from(u32::MAX) + 1).is_err());
+    }
+}
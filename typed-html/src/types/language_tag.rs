@@ -0,0 +1,181 @@
+/// Check whether a string is a syntactically valid [BCP 47] language tag,
+/// like `en`, `en-GB` or `zh-Hant-TW`.
+///
+/// This only checks the syntax of the tag, not whether its subtags are
+/// registered. It's a `const fn` so that the `html!` macro can check string
+/// literals for the [`LanguageTag`][LanguageTag] attributes at compile time,
+/// where the `language_tags` crate's parser isn't available.
+///
+/// [BCP 47]: https://tools.ietf.org/html/bcp47
+/// [LanguageTag]: struct.LanguageTag.html
+pub const fn validate_language_tag(tag: &str) -> Result<(), &'static str> {
+    let bytes = tag.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'_' {
+            return Err("language tag subtags are separated by '-', not '_'");
+        }
+        index += 1;
+    }
+
+    // The primary language, or a private use or irregular tag.
+    let end = subtag_end(bytes, 0);
+    if end == 1 && (is_singleton(bytes[0], b'x') || is_singleton(bytes[0], b'i')) {
+        return validate_private_use(bytes, end);
+    }
+    if end < 2 || end > 8 || !all(bytes, 0, end, Kind::Alpha) {
+        return Err("language tag must start with a language of 2 to 8 letters");
+    }
+    let language_len = end;
+
+    const EXTLANG: u8 = 1;
+    const SCRIPT: u8 = 2;
+    const REGION: u8 = 3;
+    const VARIANT: u8 = 4;
+    const EXTENSION: u8 = 5;
+    let mut stage = 0;
+    let mut extlangs = 0;
+    let mut needs_extension = false;
+    let mut start = end + 1;
+    while start <= bytes.len() {
+        let end = subtag_end(bytes, start);
+        let len = end - start;
+        if len == 0 {
+            return Err("language tag can't contain an empty subtag");
+        }
+        if len > 8 {
+            return Err("language subtags can't be longer than 8 characters");
+        }
+        if !all(bytes, start, end, Kind::AlphaNumeric) {
+            return Err("language subtags can only contain letters and digits");
+        }
+        if len == 1 {
+            if is_singleton(bytes[start], b'x') {
+                if needs_extension {
+                    return Err("language tag extensions can't be empty");
+                }
+                return validate_private_use(bytes, end);
+            }
+            if needs_extension {
+                return Err("language tag extensions can't be empty");
+            }
+            stage = EXTENSION;
+            needs_extension = true;
+        } else if stage == EXTENSION {
+            needs_extension = false;
+        } else if len == 3
+            && stage <= EXTLANG
+            && language_len <= 3
+            && extlangs < 3
+            && all(bytes, start, end, Kind::Alpha)
+        {
+            stage = EXTLANG;
+            extlangs += 1;
+        } else if len == 4 && stage < SCRIPT && all(bytes, start, end, Kind::Alpha) {
+            stage = SCRIPT;
+        } else if stage < REGION
+            && ((len == 2 && all(bytes, start, end, Kind::Alpha))
+                || (len == 3 && all(bytes, start, end, Kind::Digit)))
+        {
+            stage = REGION;
+        } else if len >= 5 || (len == 4 && bytes[start].is_ascii_digit()) {
+            stage = VARIANT;
+        } else {
+            return Err(
+                "language subtags must be a script, region, variant or extension, in that order",
+            );
+        }
+        start = end + 1;
+    }
+    if needs_extension {
+        return Err("language tag extensions can't be empty");
+    }
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Alpha,
+    Digit,
+    AlphaNumeric,
+}
+
+const fn subtag_end(bytes: &[u8], mut index: usize) -> usize {
+    while index < bytes.len() && bytes[index] != b'-' {
+        index += 1;
+    }
+    index
+}
+
+const fn all(bytes: &[u8], mut index: usize, end: usize, kind: Kind) -> bool {
+    while index < end {
+        let valid = match kind {
+            Kind::Alpha => bytes[index].is_ascii_alphabetic(),
+            Kind::Digit => bytes[index].is_ascii_digit(),
+            Kind::AlphaNumeric => bytes[index].is_ascii_alphanumeric(),
+        };
+        if !valid {
+            return false;
+        }
+        index += 1;
+    }
+    true
+}
+
+const fn is_singleton(byte: u8, singleton: u8) -> bool {
+    byte.to_ascii_lowercase() == singleton
+}
+
+/// Check the subtags after an `x` or `i` singleton, which can be any 1 to 8
+/// letters and digits.
+const fn validate_private_use(bytes: &[u8], singleton_end: usize) -> Result<(), &'static str> {
+    if singleton_end == bytes.len() {
+        return Err("language tag extensions can't be empty");
+    }
+    let mut start = singleton_end + 1;
+    while start <= bytes.len() {
+        let end = subtag_end(bytes, start);
+        if end == start || end - start > 8 || !all(bytes, start, end, Kind::AlphaNumeric) {
+            return Err("private use language subtags must be 1 to 8 letters and digits");
+        }
+        start = end + 1;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_language_tag() {
+        for valid in &[
+            "en",
+            "en-GB",
+            "zh-Hant-TW",
+            "es-419",
+            "zh-yue-HK",
+            "sl-rozaj-biske",
+            "de-CH-1901",
+            "en-US-u-ca-gregory",
+            "en-x-pirate",
+            "x-whatever",
+            "i-klingon",
+        ] {
+            assert_eq!(Ok(()), validate_language_tag(valid), "{}", valid);
+        }
+        for invalid in &[
+            "",
+            "en_US",
+            "e",
+            "en-",
+            "en--GB",
+            "en-GB-Latn",
+            "en-u",
+            "en-x",
+            "1en",
+        ] {
+            assert!(validate_language_tag(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
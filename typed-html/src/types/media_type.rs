@@ -0,0 +1,117 @@
+/// Check whether a string is a syntactically valid MIME type, like
+/// `text/html` or `text/plain; charset=utf-8`.
+///
+/// This is a `const fn` so that the `html!` macro can check string literals
+/// for the [`Mime`][Mime] attributes at compile time.
+///
+/// [Mime]: struct.Mime.html
+pub const fn validate_mime(mime: &str) -> Result<(), &'static str> {
+    let bytes = mime.as_bytes();
+    let mut index = token_end(bytes, 0);
+    if index == 0 || index == bytes.len() || bytes[index] != b'/' {
+        return Err("MIME type must look like type/subtype");
+    }
+    let start = index + 1;
+    index = token_end(bytes, start);
+    if index == start {
+        return Err("MIME type must look like type/subtype");
+    }
+    loop {
+        index = skip_whitespace(bytes, index);
+        if index == bytes.len() {
+            return Ok(());
+        }
+        if bytes[index] != b';' {
+            return Err("MIME type parameters must be separated by ';'");
+        }
+        index = skip_whitespace(bytes, index + 1);
+        let start = index;
+        index = token_end(bytes, start);
+        if index == start || index == bytes.len() || bytes[index] != b'=' {
+            return Err("MIME type parameters must look like name=value");
+        }
+        index += 1;
+        if index < bytes.len() && bytes[index] == b'"' {
+            index += 1;
+            while index < bytes.len() && bytes[index] != b'"' {
+                if bytes[index] == b'\\' {
+                    index += 1;
+                }
+                index += 1;
+            }
+            if index >= bytes.len() {
+                return Err("MIME type parameter has an unterminated quoted string");
+            }
+            index += 1;
+        } else {
+            let start = index;
+            index = token_end(bytes, start);
+            if index == start {
+                return Err("MIME type parameters must look like name=value");
+            }
+        }
+    }
+}
+
+const fn is_token(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric()
+        || matches!(
+            byte,
+            b'!' | b'#'
+                | b'$'
+                | b'%'
+                | b'&'
+                | b'\''
+                | b'*'
+                | b'+'
+                | b'-'
+                | b'.'
+                | b'^'
+                | b'_'
+                | b'`'
+                | b'|'
+                | b'~'
+        )
+}
+
+const fn token_end(bytes: &[u8], mut index: usize) -> usize {
+    while index < bytes.len() && is_token(bytes[index]) {
+        index += 1;
+    }
+    index
+}
+
+const fn skip_whitespace(bytes: &[u8], mut index: usize) -> usize {
+    while index < bytes.len() && (bytes[index] == b' ' || bytes[index] == b'\t') {
+        index += 1;
+    }
+    index
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_mime() {
+        for valid in &[
+            "text/html",
+            "image/svg+xml",
+            "text/plain; charset=utf-8",
+            "video/webm;codecs=\"vp8, vorbis\"",
+        ] {
+            assert_eq!(Ok(()), validate_mime(valid), "{}", valid);
+        }
+        for invalid in &[
+            "",
+            "text",
+            "text/",
+            "/html",
+            "text html",
+            "text/plain; charset",
+            "text/plain; charset=\"utf-8",
+        ] {
+            assert!(validate_mime(invalid).is_err(), "{}", invalid);
+        }
+    }
+}
@@ -2,18 +2,47 @@
 
 use strum_macros::*;
 
+mod autocomplete;
+pub use self::autocomplete::{AddressType, Autocomplete, AutofillField, ContactType};
+
 mod class;
 pub use self::class::Class;
 
+mod color;
+pub use self::color::Color;
+
+mod custom_element;
+pub use self::custom_element::CustomElementName;
+
+mod date;
+pub use self::date::Date;
+
+mod dimension;
+pub use self::dimension::Dimension;
+
 mod id;
 pub use self::id::Id;
 
+mod language_tag;
+pub use self::language_tag::validate_language_tag;
+
+mod media_type;
+pub use self::media_type::validate_mime;
+
+mod script_type;
+pub use self::script_type::ScriptType;
+
 mod spacedlist;
 pub use self::spacedlist::SpacedList;
 
 mod spacedset;
 pub use self::spacedset::SpacedSet;
 
+mod url;
+#[cfg(feature = "serde")]
+pub use self::url::QueryError;
+pub use self::url::Url;
+
 pub type ClassList = SpacedSet<Class>;
 
 pub use language_tags::LanguageTag;
@@ -28,6 +57,17 @@ pub type Integrity = String;
 pub type Nonce = String;
 pub type Target = String;
 
+/// Fail to compile if a string literal attribute value didn't validate.
+///
+/// The `html!` macro calls this in a `const` context with the result of a
+/// type's `const fn validate()`, so that a panic here is a compile error.
+#[doc(hidden)]
+pub const fn check_literal(result: Result<(), &'static str>) {
+    if let Err(err) = result {
+        panic!("{}", err)
+    }
+}
+
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum AreaShape {
     #[strum(to_string = "rect")]
@@ -40,6 +80,18 @@ pub enum AreaShape {
     Default,
 }
 
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum Autocapitalize {
+    #[strum(to_string = "none", serialize = "off")]
+    None,
+    #[strum(to_string = "sentences", serialize = "on")]
+    Sentences,
+    #[strum(to_string = "words")]
+    Words,
+    #[strum(to_string = "characters")]
+    Characters,
+}
+
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum BoolOrDefault {
     #[strum(to_string = "true")]
@@ -80,12 +132,31 @@ impl From<bool> for Bool {
 
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum CrossOrigin {
-    #[strum(to_string = "anonymous")]
+    // An empty value, as in `<img crossorigin>`, also means anonymous.
+    #[strum(to_string = "anonymous", serialize = "")]
     Anonymous,
     #[strum(to_string = "use-credentials")]
     UseCredentials,
 }
 
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum EnterKeyHint {
+    #[strum(to_string = "enter")]
+    Enter,
+    #[strum(to_string = "done")]
+    Done,
+    #[strum(to_string = "go")]
+    Go,
+    #[strum(to_string = "next")]
+    Next,
+    #[strum(to_string = "previous")]
+    Previous,
+    #[strum(to_string = "search")]
+    Search,
+    #[strum(to_string = "send")]
+    Send,
+}
+
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum FormEncodingType {
     #[strum(to_string = "application/x-www-form-urlencoded")]
@@ -132,6 +203,26 @@ pub enum ImageDecoding {
     Auto,
 }
 
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum InputMode {
+    #[strum(to_string = "none")]
+    None,
+    #[strum(to_string = "text")]
+    Text,
+    #[strum(to_string = "decimal")]
+    Decimal,
+    #[strum(to_string = "numeric")]
+    Numeric,
+    #[strum(to_string = "tel")]
+    Tel,
+    #[strum(to_string = "search")]
+    Search,
+    #[strum(to_string = "email")]
+    Email,
+    #[strum(to_string = "url")]
+    Url,
+}
+
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum InputType {
     #[strum(to_string = "button")]
@@ -228,6 +319,14 @@ pub enum LinkType {
     Tag,
 }
 
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum Loading {
+    #[strum(to_string = "eager")]
+    Eager,
+    #[strum(to_string = "lazy")]
+    Lazy,
+}
+
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum Metadata {
     #[strum(to_string = "application-name")]
@@ -250,6 +349,8 @@ pub enum Metadata {
     Publisher,
     #[strum(to_string = "robots")]
     Robots,
+    #[strum(to_string = "theme-color")]
+    ThemeColor,
     #[strum(to_string = "viewport")]
     Viewport,
 }
@@ -276,6 +377,27 @@ pub enum OrderedListType {
     Numbers,
 }
 
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum Popover {
+    // An empty value, as in `<div popover>`, also means auto.
+    #[strum(to_string = "auto", serialize = "")]
+    Auto,
+    #[strum(to_string = "manual")]
+    Manual,
+    #[strum(to_string = "hint")]
+    Hint,
+}
+
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum PopoverTargetAction {
+    #[strum(to_string = "toggle")]
+    Toggle,
+    #[strum(to_string = "show")]
+    Show,
+    #[strum(to_string = "hide")]
+    Hide,
+}
+
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum Preload {
     #[strum(to_string = "none")]
@@ -296,12 +418,186 @@ pub enum ReferrerPolicy {
     Origin,
     #[strum(to_string = "origin-when-cross-origin")]
     OriginWhenCrossOrigin,
+    #[strum(to_string = "same-origin")]
+    SameOrigin,
+    #[strum(to_string = "strict-origin")]
+    StrictOrigin,
+    #[strum(to_string = "strict-origin-when-cross-origin")]
+    StrictOriginWhenCrossOrigin,
     #[strum(to_string = "unsafe-url")]
     UnsafeUrl,
 }
 
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum Role {
+    #[strum(to_string = "alert")]
+    Alert,
+    #[strum(to_string = "alertdialog")]
+    AlertDialog,
+    #[strum(to_string = "application")]
+    Application,
+    #[strum(to_string = "article")]
+    Article,
+    #[strum(to_string = "banner")]
+    Banner,
+    #[strum(to_string = "blockquote")]
+    Blockquote,
+    #[strum(to_string = "button")]
+    Button,
+    #[strum(to_string = "caption")]
+    Caption,
+    #[strum(to_string = "cell")]
+    Cell,
+    #[strum(to_string = "checkbox")]
+    Checkbox,
+    #[strum(to_string = "code")]
+    Code,
+    #[strum(to_string = "columnheader")]
+    ColumnHeader,
+    #[strum(to_string = "combobox")]
+    ComboBox,
+    #[strum(to_string = "complementary")]
+    Complementary,
+    #[strum(to_string = "contentinfo")]
+    ContentInfo,
+    #[strum(to_string = "definition")]
+    Definition,
+    #[strum(to_string = "deletion")]
+    Deletion,
+    #[strum(to_string = "dialog")]
+    Dialog,
+    #[strum(to_string = "document")]
+    Document,
+    #[strum(to_string = "emphasis")]
+    Emphasis,
+    #[strum(to_string = "feed")]
+    Feed,
+    #[strum(to_string = "figure")]
+    Figure,
+    #[strum(to_string = "form")]
+    Form,
+    #[strum(to_string = "generic")]
+    Generic,
+    #[strum(to_string = "grid")]
+    Grid,
+    #[strum(to_string = "gridcell")]
+    GridCell,
+    #[strum(to_string = "group")]
+    Group,
+    #[strum(to_string = "heading")]
+    Heading,
+    #[strum(to_string = "img")]
+    Img,
+    #[strum(to_string = "insertion")]
+    Insertion,
+    #[strum(to_string = "link")]
+    Link,
+    #[strum(to_string = "list")]
+    List,
+    #[strum(to_string = "listbox")]
+    ListBox,
+    #[strum(to_string = "listitem")]
+    ListItem,
+    #[strum(to_string = "log")]
+    Log,
+    #[strum(to_string = "main")]
+    Main,
+    #[strum(to_string = "marquee")]
+    Marquee,
+    #[strum(to_string = "math")]
+    Math,
+    #[strum(to_string = "menu")]
+    Menu,
+    #[strum(to_string = "menubar")]
+    MenuBar,
+    #[strum(to_string = "menuitem")]
+    MenuItem,
+    #[strum(to_string = "menuitemcheckbox")]
+    MenuItemCheckbox,
+    #[strum(to_string = "menuitemradio")]
+    MenuItemRadio,
+    #[strum(to_string = "meter")]
+    Meter,
+    #[strum(to_string = "navigation")]
+    Navigation,
+    #[strum(to_string = "none")]
+    None,
+    #[strum(to_string = "note")]
+    Note,
+    #[strum(to_string = "option")]
+    Option,
+    #[strum(to_string = "paragraph")]
+    Paragraph,
+    #[strum(to_string = "presentation")]
+    Presentation,
+    #[strum(to_string = "progressbar")]
+    ProgressBar,
+    #[strum(to_string = "radio")]
+    Radio,
+    #[strum(to_string = "radiogroup")]
+    RadioGroup,
+    #[strum(to_string = "region")]
+    Region,
+    #[strum(to_string = "row")]
+    Row,
+    #[strum(to_string = "rowgroup")]
+    RowGroup,
+    #[strum(to_string = "rowheader")]
+    RowHeader,
+    #[strum(to_string = "scrollbar")]
+    ScrollBar,
+    #[strum(to_string = "search")]
+    Search,
+    #[strum(to_string = "searchbox")]
+    SearchBox,
+    #[strum(to_string = "separator")]
+    Separator,
+    #[strum(to_string = "slider")]
+    Slider,
+    #[strum(to_string = "spinbutton")]
+    SpinButton,
+    #[strum(to_string = "status")]
+    Status,
+    #[strum(to_string = "strong")]
+    Strong,
+    #[strum(to_string = "subscript")]
+    Subscript,
+    #[strum(to_string = "superscript")]
+    Superscript,
+    #[strum(to_string = "switch")]
+    Switch,
+    #[strum(to_string = "tab")]
+    Tab,
+    #[strum(to_string = "table")]
+    Table,
+    #[strum(to_string = "tablist")]
+    TabList,
+    #[strum(to_string = "tabpanel")]
+    TabPanel,
+    #[strum(to_string = "term")]
+    Term,
+    #[strum(to_string = "textbox")]
+    TextBox,
+    #[strum(to_string = "time")]
+    Time,
+    #[strum(to_string = "timer")]
+    Timer,
+    #[strum(to_string = "toolbar")]
+    ToolBar,
+    #[strum(to_string = "tooltip")]
+    Tooltip,
+    #[strum(to_string = "tree")]
+    Tree,
+    #[strum(to_string = "treegrid")]
+    TreeGrid,
+    #[strum(to_string = "treeitem")]
+    TreeItem,
+}
+
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum Sandbox {
+    #[strum(to_string = "allow-downloads")]
+    AllowDownloads,
     #[strum(to_string = "allow-forms")]
     AllowForms,
     #[strum(to_string = "allow-modals")]
@@ -324,6 +620,8 @@ pub enum Sandbox {
     AllowTopNavigation,
     #[strum(to_string = "allow-top-navigation-by-user-navigation")]
     AllowTopNavigationByUserNavigation,
+    #[strum(to_string = "allow-top-navigation-to-custom-protocols")]
+    AllowTopNavigationToCustomProtocols,
 }
 
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
@@ -348,6 +646,14 @@ pub enum TextDirection {
     RightToLeft,
 }
 
+#[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
+pub enum Translate {
+    #[strum(to_string = "yes")]
+    Yes,
+    #[strum(to_string = "no")]
+    No,
+}
+
 #[derive(EnumString, Display, PartialEq, Eq, PartialOrd, Ord, AsRefStr, AsStaticStr)]
 pub enum VideoKind {
     #[strum(to_string = "subtitles")]
@@ -0,0 +1,167 @@
+use std::fmt::{Display, Error, Formatter};
+use std::str::FromStr;
+
+use super::{validate_mime, Mime};
+
+/// The `type` of a `<script>` element.
+///
+/// This is either one of the special script types `module` and `importmap`,
+/// or a MIME type: a JavaScript MIME type like `text/javascript` for a
+/// classic script, or any other MIME type for a data block, like
+/// `application/ld+json`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum ScriptType {
+    Module,
+    ImportMap,
+    Mime(Mime),
+}
+
+/// Script types which browsers will run, and which a typo is most likely to
+/// have been aiming for.
+const KNOWN_SCRIPT_TYPES: &[&str] = &[
+    "module",
+    "importmap",
+    "text/javascript",
+    "application/javascript",
+    "text/ecmascript",
+    "application/ecmascript",
+];
+
+impl ScriptType {
+    /// Check whether a string is a valid script type.
+    ///
+    /// Any syntactically valid MIME type makes a valid data block, but a
+    /// browser will neither run nor complain about a script with a mistyped
+    /// JavaScript MIME type. This is stricter, and also rejects anything
+    /// within a couple of typos of a known script type, like
+    /// `text/javascrip`.
+    ///
+    /// This is a `const fn` so that the `html!` macro can check string
+    /// literals at compile time.
+    pub const fn validate(script_type: &str) -> Result<(), &'static str> {
+        let bytes = script_type.as_bytes();
+        let mut essence = 0;
+        while essence < bytes.len() && bytes[essence] != b';' && bytes[essence] != b' ' {
+            essence += 1;
+        }
+        let (essence, _) = bytes.split_at(essence);
+        let mut index = 0;
+        while index < KNOWN_SCRIPT_TYPES.len() {
+            if edit_distance(essence, KNOWN_SCRIPT_TYPES[index].as_bytes()) == 0 {
+                return Ok(());
+            }
+            index += 1;
+        }
+        index = 0;
+        while index < KNOWN_SCRIPT_TYPES.len() {
+            if edit_distance(essence, KNOWN_SCRIPT_TYPES[index].as_bytes()) <= 2 {
+                return Err("script type is a typo of a known script type, \
+                            like \"text/javascript\" or \"module\"");
+            }
+            index += 1;
+        }
+        validate_mime(script_type)
+    }
+}
+
+/// The Levenshtein distance between two ASCII strings, ignoring case, or
+/// `usize::MAX` if either is too long to bother comparing.
+const fn edit_distance(left: &[u8], right: &[u8]) -> usize {
+    const MAX_LEN: usize = 32;
+    if left.len() > MAX_LEN || right.len() > MAX_LEN {
+        return usize::MAX;
+    }
+    let mut row = [0; MAX_LEN + 1];
+    let mut j = 0;
+    while j <= right.len() {
+        row[j] = j;
+        j += 1;
+    }
+    let mut i = 1;
+    while i <= left.len() {
+        let mut diagonal = row[0];
+        row[0] = i;
+        let mut j = 1;
+        while j <= right.len() {
+            let above = row[j];
+            let mut best = if left[i - 1].eq_ignore_ascii_case(&right[j - 1]) {
+                diagonal
+            } else {
+                diagonal + 1
+            };
+            if above + 1 < best {
+                best = above + 1;
+            }
+            if row[j - 1] + 1 < best {
+                best = row[j - 1] + 1;
+            }
+            row[j] = best;
+            diagonal = above;
+            j += 1;
+        }
+        i += 1;
+    }
+    row[right.len()]
+}
+
+impl FromStr for ScriptType {
+    type Err = mime::FromStrError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("module") {
+            Ok(ScriptType::Module)
+        } else if s.eq_ignore_ascii_case("importmap") {
+            Ok(ScriptType::ImportMap)
+        } else {
+            s.parse().map(ScriptType::Mime)
+        }
+    }
+}
+
+impl From<Mime> for ScriptType {
+    fn from(mime: Mime) -> Self {
+        ScriptType::Mime(mime)
+    }
+}
+
+impl Display for ScriptType {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            ScriptType::Module => f.write_str("module"),
+            ScriptType::ImportMap => f.write_str("importmap"),
+            ScriptType::Mime(mime) => Display::fmt(mime, f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_type() {
+        for valid in &[
+            "module",
+            "text/javascript",
+            "application/ld+json",
+            "text/x-template",
+        ] {
+            assert_eq!(Ok(()), ScriptType::validate(valid), "{}", valid);
+        }
+        for invalid in &[
+            "text/javascrip",
+            "text/javscript",
+            "modul",
+            "text javascript",
+        ] {
+            assert!(ScriptType::validate(invalid).is_err(), "{}", invalid);
+        }
+        assert_eq!(ScriptType::Module, "module".parse().unwrap());
+        assert_eq!(
+            "application/ld+json",
+            "application/ld+json"
+                .parse::<ScriptType>()
+                .unwrap()
+                .to_string()
+        );
+    }
+}
@@ -44,6 +44,14 @@ impl<'a, A: 'a + Clone> FromIterator<&'a A> for SpacedList<A> {
     }
 }
 
+impl<A: FromStr> FromStr for SpacedList<A> {
+    type Err = <A as FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split_whitespace().map(FromStr::from_str).collect()
+    }
+}
+
 impl<'a, A: FromStr> From<&'a str> for SpacedList<A>
 where
     <A as FromStr>::Err: Debug,
@@ -218,7 +226,7 @@ macro_rules! spacedlist_from_array {
             <A as FromStr>::Err: Debug,
         {
             fn from(s: [&str; $num]) -> Self {
-                Self::from_iter(s.into_iter().map(|s| FromStr::from_str(*s).unwrap()))
+                Self::from_iter(s.iter().map(|s| FromStr::from_str(*s).unwrap()))
             }
         }
     };
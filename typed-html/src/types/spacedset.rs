@@ -251,7 +251,7 @@ macro_rules! spacedlist_from_array {
             <A as FromStr>::Err: Debug,
         {
             fn from(s: [&str; $num]) -> Self {
-                Self::from_iter(s.into_iter().map(|s| FromStr::from_str(*s).unwrap()))
+                Self::from_iter(s.iter().map(|s| FromStr::from_str(*s).unwrap()))
             }
         }
     };
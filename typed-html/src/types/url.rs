@@ -0,0 +1,215 @@
+use std::fmt::{Display, Error, Formatter, Write};
+
+#[cfg(feature = "serde")]
+mod query;
+#[cfg(feature = "serde")]
+pub use self::query::QueryError;
+
+/// A URL built from parts which are percent-encoded as they're added, for
+/// attributes like `href`, `src` and `action`.
+///
+/// The base URL is used as it is, and path segments, query parameters and
+/// the fragment are encoded, so there's no need to build query strings by
+/// hand and no way to get their encoding wrong.
+///
+/// A `Url` converts into a string, so it can be given to any URL attribute
+/// in the `html!` macro.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::html;
+/// # use typed_html::dom::DOMTree;
+/// use typed_html::types::Url;
+///
+/// # fn main() {
+/// let url = Url::new("https://example.com/users")
+///     .segment("Kitty Cat")
+///     .query("tab", "toys & games")
+///     .query("page", 2)
+///     .fragment("top");
+/// assert_eq!(
+///     "https://example.com/users/Kitty%20Cat?tab=toys%20%26%20games&page=2#top",
+///     url.to_string()
+/// );
+///
+/// let doc: DOMTree<String> = html!(<a href=url>"Toys"</a>);
+/// # }
+/// ```
+///
+/// With the `serde` feature flag, query parameters can also be added from
+/// any struct or map which implements `Serialize`, with
+/// [`queries()`][queries].
+///
+/// [queries]: #method.queries
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Url {
+    base: String,
+    query: Vec<(String, String)>,
+    fragment: Option<String>,
+}
+
+impl Url {
+    /// Construct a URL from a base URL, like `/search` or
+    /// `https://example.com/`, which is used as it is.
+    pub fn new<S: Into<String>>(base: S) -> Self {
+        Url {
+            base: base.into(),
+            query: Vec::new(),
+            fragment: None,
+        }
+    }
+
+    /// Add a path segment, separated from the path so far by a `/`.
+    ///
+    /// Characters which aren't allowed in a path segment, including `/`, are
+    /// percent-encoded.
+    pub fn segment<S: Display>(mut self, segment: S) -> Self {
+        if !self.base.ends_with('/') {
+            self.base.push('/');
+        }
+        encode(&mut self.base, &segment.to_string(), is_segment_char);
+        self
+    }
+
+    /// Add a query parameter.
+    pub fn query<K: Display, V: Display>(mut self, key: K, value: V) -> Self {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the fragment, which comes after the `#`.
+    pub fn fragment<S: Display>(mut self, fragment: S) -> Self {
+        self.fragment = Some(fragment.to_string());
+        self
+    }
+
+    /// Add query parameters from the fields of a struct or the entries of a
+    /// map.
+    ///
+    /// Fields which are `None` are left out, and sequences add a parameter
+    /// for each of their values. Fields which hold structs or maps
+    /// themselves can't be written as query parameters, and return an error.
+    ///
+    /// ```
+    /// use serde::Serialize;
+    /// use typed_html::types::Url;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Search<'a> {
+    ///     q: &'a str,
+    ///     tags: Vec<&'a str>,
+    ///     page: Option<u32>,
+    /// }
+    ///
+    /// let search = Search {
+    ///     q: "50% off",
+    ///     tags: vec!["toys", "cats"],
+    ///     page: None,
+    /// };
+    /// assert_eq!(
+    ///     "/search?q=50%25%20off&tags=toys&tags=cats",
+    ///     Url::new("/search").queries(&search).unwrap().to_string()
+    /// );
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn queries<T: serde::Serialize + ?Sized>(mut self, query: &T) -> Result<Self, QueryError> {
+        self.query.extend(query::to_pairs(query)?);
+        Ok(self)
+    }
+}
+
+/// Whether a character can be left as it is in a path segment.
+fn is_segment_char(c: char) -> bool {
+    is_unreserved(c) || "!$&'()*+,;=:@".contains(c)
+}
+
+/// Whether a character never needs encoding in a URL.
+fn is_unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-._~".contains(c)
+}
+
+/// Percent-encode the characters of `input` which `allowed` rejects.
+fn encode(out: &mut String, input: &str, allowed: fn(char) -> bool) {
+    for c in input.chars() {
+        if allowed(c) {
+            out.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                write!(out, "%{:02X}", byte).unwrap();
+            }
+        }
+    }
+}
+
+impl Display for Url {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        let mut url = self.base.clone();
+        for (index, (key, value)) in self.query.iter().enumerate() {
+            url.push(if index == 0 && !self.base.contains('?') {
+                '?'
+            } else {
+                '&'
+            });
+            encode(&mut url, key, is_unreserved);
+            url.push('=');
+            encode(&mut url, value, is_unreserved);
+        }
+        if let Some(ref fragment) = self.fragment {
+            url.push('#');
+            encode(&mut url, fragment, is_unreserved);
+        }
+        f.write_str(&url)
+    }
+}
+
+impl From<Url> for String {
+    fn from(url: Url) -> Self {
+        url.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_url_encoding() {
+        assert_eq!(
+            "/files/a%2Fb/caf%C3%A9?q=%3F%3D%23#a%20b",
+            Url::new("/files/")
+                .segment("a/b")
+                .segment("café")
+                .query("q", "?=#")
+                .fragment("a b")
+                .to_string()
+        );
+        assert_eq!(
+            "/search?lang=en&q=a%2Bb",
+            Url::new("/search?lang=en").query("q", "a+b").to_string()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_url_queries() {
+        use std::collections::BTreeMap;
+
+        let mut map = BTreeMap::new();
+        map.insert("b", 2);
+        map.insert("a", 1);
+        assert_eq!(
+            "/?a=1&b=2",
+            Url::new("/").queries(&map).unwrap().to_string()
+        );
+
+        let mut nested = BTreeMap::new();
+        nested.insert("outer", map);
+        assert_eq!(
+            "a nested map can't be written as a query parameter",
+            Url::new("/").queries(&nested).unwrap_err().to_string()
+        );
+        assert!(Url::new("/").queries(&5).is_err());
+    }
+}
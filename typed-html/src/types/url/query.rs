@@ -0,0 +1,421 @@
+//! A serde serializer which turns a struct or a map into query parameters.
+
+use std::error;
+use std::fmt::{Display, Error, Formatter};
+
+use serde::ser::{self, Impossible, Serialize, Serializer};
+
+/// An error from [`Url::queries()`][queries], for a value which can't be
+/// written as query parameters.
+///
+/// [queries]: struct.Url.html#method.queries
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QueryError(String);
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        f.write_str(&self.0)
+    }
+}
+
+impl error::Error for QueryError {}
+
+impl ser::Error for QueryError {
+    fn custom<T: Display>(msg: T) -> Self {
+        QueryError(msg.to_string())
+    }
+}
+
+fn unsupported<T>(what: &str) -> Result<T, QueryError> {
+    Err(QueryError(format!(
+        "{} can't be written as a query parameter",
+        what
+    )))
+}
+
+pub(super) fn to_pairs<T: Serialize + ?Sized>(
+    value: &T,
+) -> Result<Vec<(String, String)>, QueryError> {
+    let mut pairs = Vec::new();
+    value.serialize(Pairs { pairs: &mut pairs })?;
+    Ok(pairs)
+}
+
+/// Serializes the top level value, which has to be a struct or a map.
+struct Pairs<'a> {
+    pairs: &'a mut Vec<(String, String)>,
+}
+
+macro_rules! top_level_scalars {
+    ($($method:ident: $type:ty,)*) => {
+        $(
+            fn $method(self, _: $type) -> Result<(), QueryError> {
+                unsupported("a value outside of a struct or a map")
+            }
+        )*
+    };
+}
+
+impl<'a> Serializer for Pairs<'a> {
+    type Ok = ();
+    type Error = QueryError;
+    type SerializeSeq = Impossible<(), QueryError>;
+    type SerializeTuple = Impossible<(), QueryError>;
+    type SerializeTupleStruct = Impossible<(), QueryError>;
+    type SerializeTupleVariant = Impossible<(), QueryError>;
+    type SerializeMap = Entries<'a>;
+    type SerializeStruct = Entries<'a>;
+    type SerializeStructVariant = Impossible<(), QueryError>;
+
+    top_level_scalars! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+        serialize_bytes: &[u8],
+    }
+
+    fn serialize_none(self) -> Result<(), QueryError> {
+        Ok(())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<(), QueryError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), QueryError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<(), QueryError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+    ) -> Result<(), QueryError> {
+        unsupported("an enum")
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<(), QueryError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<(), QueryError> {
+        unsupported("an enum")
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, QueryError> {
+        unsupported("a sequence outside of a struct or a map")
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, QueryError> {
+        unsupported("a tuple outside of a struct or a map")
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleStruct, QueryError> {
+        unsupported("a tuple struct")
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, QueryError> {
+        unsupported("an enum")
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, QueryError> {
+        Ok(Entries {
+            pairs: self.pairs,
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, QueryError> {
+        Ok(Entries {
+            pairs: self.pairs,
+            key: None,
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, QueryError> {
+        unsupported("an enum")
+    }
+}
+
+/// Serializes the fields of a struct or the entries of a map.
+struct Entries<'a> {
+    pairs: &'a mut Vec<(String, String)>,
+    key: Option<String>,
+}
+
+impl<'a> Entries<'a> {
+    fn push<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), QueryError> {
+        for value in value.serialize(Values)? {
+            self.pairs.push((key.to_string(), value));
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStruct for Entries<'a> {
+    type Ok = ();
+    type Error = QueryError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), QueryError> {
+        self.push(key, value)
+    }
+
+    fn end(self) -> Result<(), QueryError> {
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeMap for Entries<'a> {
+    type Ok = ();
+    type Error = QueryError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), QueryError> {
+        let mut keys = key.serialize(Values)?;
+        if keys.len() != 1 {
+            return unsupported("a map key which isn't a single value");
+        }
+        self.key = keys.pop();
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), QueryError> {
+        let key = self.key.take().unwrap_or_default();
+        self.push(&key, value)
+    }
+
+    fn end(self) -> Result<(), QueryError> {
+        Ok(())
+    }
+}
+
+/// Serializes the value of a field into the values of the parameters it
+/// becomes: none for `None`, one for a scalar, and one for each item in a
+/// sequence.
+struct Values;
+
+macro_rules! value_scalars {
+    ($($method:ident: $type:ty,)*) => {
+        $(
+            fn $method(self, value: $type) -> Result<Vec<String>, QueryError> {
+                Ok(vec![value.to_string()])
+            }
+        )*
+    };
+}
+
+impl Serializer for Values {
+    type Ok = Vec<String>;
+    type Error = QueryError;
+    type SerializeSeq = Sequence;
+    type SerializeTuple = Sequence;
+    type SerializeTupleStruct = Sequence;
+    type SerializeTupleVariant = Impossible<Vec<String>, QueryError>;
+    type SerializeMap = Impossible<Vec<String>, QueryError>;
+    type SerializeStruct = Impossible<Vec<String>, QueryError>;
+    type SerializeStructVariant = Impossible<Vec<String>, QueryError>;
+
+    value_scalars! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_char: char,
+        serialize_str: &str,
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Vec<String>, QueryError> {
+        unsupported("a byte array")
+    }
+
+    fn serialize_none(self) -> Result<Vec<String>, QueryError> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Vec<String>, QueryError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Vec<String>, QueryError> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Vec<String>, QueryError> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        variant: &'static str,
+    ) -> Result<Vec<String>, QueryError> {
+        Ok(vec![variant.to_string()])
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        value: &T,
+    ) -> Result<Vec<String>, QueryError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: &T,
+    ) -> Result<Vec<String>, QueryError> {
+        unsupported("an enum variant with a value")
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Sequence, QueryError> {
+        Ok(Sequence(Vec::new()))
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Sequence, QueryError> {
+        Ok(Sequence(Vec::new()))
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Sequence, QueryError> {
+        Ok(Sequence(Vec::new()))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeTupleVariant, QueryError> {
+        unsupported("an enum variant with a value")
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, QueryError> {
+        unsupported("a nested map")
+    }
+
+    fn serialize_struct(
+        self,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStruct, QueryError> {
+        unsupported("a nested struct")
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _: &'static str,
+        _: u32,
+        _: &'static str,
+        _: usize,
+    ) -> Result<Self::SerializeStructVariant, QueryError> {
+        unsupported("an enum variant with a value")
+    }
+}
+
+/// Collects the values of a sequence.
+struct Sequence(Vec<String>);
+
+impl Sequence {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), QueryError> {
+        self.0.extend(value.serialize(Values)?);
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for Sequence {
+    type Ok = Vec<String>;
+    type Error = QueryError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), QueryError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Vec<String>, QueryError> {
+        Ok(self.0)
+    }
+}
+
+impl ser::SerializeTuple for Sequence {
+    type Ok = Vec<String>;
+    type Error = QueryError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), QueryError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Vec<String>, QueryError> {
+        Ok(self.0)
+    }
+}
+
+impl ser::SerializeTupleStruct for Sequence {
+    type Ok = Vec<String>;
+    type Error = QueryError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), QueryError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Vec<String>, QueryError> {
+        Ok(self.0)
+    }
+}
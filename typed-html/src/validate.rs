@@ -0,0 +1,433 @@
+//! Checking DOM trees against the content model at runtime.
+//!
+//! The `html!` macro checks that each element only contains what it's
+//! allowed to at compile time, using the content groups which the elements
+//! are declared with. Those rules are also available as data, as a
+//! [`ContentModel`][ContentModel] for each element, so trees which didn't
+//! come from the macro, like `VNode` trees built or edited by hand, parsed
+//! from a string or run through a sanitiser, can be checked with
+//! [`validate()`][validate].
+//!
+//! Text which is only whitespace is allowed anywhere, since documents parsed
+//! from strings have it between elements.
+//!
+//! # Examples
+//!
+//! ```
+//! # use typed_html::html;
+//! # use typed_html::dom::{DOMTree, VElement, VNode};
+//! use typed_html::validate::{validate, Violation};
+//!
+//! # fn main() {
+//! let mut doc: DOMTree<String> = html!(<ul><li>"One"</li></ul>);
+//! let mut events = Default::default();
+//! let mut tree = doc.vnode();
+//! assert!(validate(&tree).is_empty());
+//!
+//! // Put a `<p>` straight into the list, which the macro wouldn't allow.
+//! if let VNode::Element(list) = &mut tree {
+//!     list.children.push(VNode::Element(VElement {
+//!         name: "p",
+//!         attributes: Vec::new(),
+//!         events: &mut events,
+//!         children: vec![VNode::Text("Two")],
+//!     }));
+//! }
+//! assert_eq!(
+//!     vec![Violation::NotAllowed {
+//!         path: vec![1],
+//!         parent: "ul".to_string(),
+//!         child: "<p>".to_string(),
+//!     }],
+//!     validate(&tree)
+//! );
+//! # }
+//! ```
+//!
+//! [ContentModel]: struct.ContentModel.html
+//! [validate]: fn.validate.html
+
+use std::fmt::{Display, Error, Formatter};
+
+use crate::dom::{VElement, VNode};
+use crate::elements::CONTENT_MODELS;
+use crate::OutputType;
+
+/// The content groups which text nodes belong to.
+const TEXT_GROUPS: &[&str] = &["FlowContent", "PhrasingContent", "ScriptContent"];
+
+/// The content groups which unescaped text nodes belong to.
+const UNSAFE_TEXT_GROUPS: &[&str] = &[
+    "FlowContent",
+    "PhrasingContent",
+    "ScriptContent",
+    "SvgContent",
+];
+
+/// The content groups which portals belong to.
+const PORTAL_GROUPS: &[&str] = &["FlowContent", "PhrasingContent"];
+
+/// A content group, like `FlowContent`, and the group it's a subset of, if
+/// any.
+///
+/// These are generated by [`content_group!`][content_group], so an element
+/// in `PhrasingContent` is also accepted where `FlowContent` is.
+///
+/// [content_group]: ../macro.content_group.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentGroup {
+    /// The group's name.
+    pub name: &'static str,
+    /// The group this one is a subset of, like `PhrasingContent`'s
+    /// `FlowContent`.
+    pub parent: Option<&'static ContentGroup>,
+}
+
+impl ContentGroup {
+    /// Whether this group is `name` or a subset of it.
+    pub fn is_in(&self, name: &str) -> bool {
+        let mut group = Some(self);
+        while let Some(current) = group {
+            if current.name == name {
+                return true;
+            }
+            group = current.parent;
+        }
+        false
+    }
+}
+
+/// The `ContentGroup` of a content group's trait, implemented for its trait
+/// object by [`content_group!`][content_group].
+///
+/// [content_group]: ../macro.content_group.html
+#[doc(hidden)]
+pub trait Group {
+    const GROUP: ContentGroup;
+}
+
+/// The rules for what an element can contain, and where it can go.
+///
+/// These are generated from each element's declaration: the content groups
+/// it's declared `in`, and what it's declared `with`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentModel {
+    /// The element's name.
+    pub name: &'static str,
+    /// The content groups the element belongs to, like `FlowContent`.
+    pub groups: &'static [&'static ContentGroup],
+    /// The names of the elements which must come first in the element, in
+    /// order, like `<html>`'s `head` and `body`.
+    pub required_children: &'static [&'static str],
+    /// What the element can contain after its required children: a content
+    /// group, the name of an element, `TextNode` for text, `Node` for
+    /// anything, or `None` for nothing at all.
+    pub children: Option<&'static str>,
+}
+
+impl ContentModel {
+    /// Whether `child` is allowed after the element's required children.
+    fn allows<T: OutputType>(&self, validator: &Validator, child: &VNode<'_, T>) -> bool {
+        let children = match self.children {
+            Some(children) => children,
+            None => return false,
+        };
+        match child {
+            _ if children == "Node" => true,
+            VNode::Comment(_) => true,
+            VNode::Text(_) => children == "TextNode" || TEXT_GROUPS.contains(&children),
+            VNode::UnsafeText(_) => UNSAFE_TEXT_GROUPS.contains(&children),
+            VNode::Portal(_) => PORTAL_GROUPS.contains(&children),
+            VNode::Element(element) => {
+                element.name == children
+                    // Unknown elements are reported where they are.
+                    || validator
+                        .content_model(element.name)
+                        .is_none_or(|model| model.groups.iter().any(|group| group.is_in(children)))
+            }
+        }
+    }
+}
+
+/// A place where a tree breaks the content model.
+///
+/// Paths are given the same way as in [`diff`][diff] patches: the index of
+/// each node on the way down from the root among its parent's children.
+///
+/// [diff]: ../diff/index.html
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// The element at `path` isn't one the validator knows about.
+    UnknownElement { path: Vec<usize>, name: String },
+    /// The node at `path`, which is an element named `child` or `"text"`,
+    /// isn't allowed in its parent, `parent`.
+    NotAllowed {
+        path: Vec<usize>,
+        parent: String,
+        child: String,
+    },
+    /// The element at `path`, `parent`, is missing its required child
+    /// `child`.
+    MissingChild {
+        path: Vec<usize>,
+        parent: String,
+        child: String,
+    },
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), Error> {
+        match self {
+            Violation::UnknownElement { path, name } => {
+                write!(f, "unknown element <{}> at {:?}", name, path)
+            }
+            Violation::NotAllowed {
+                path,
+                parent,
+                child,
+            } => write!(f, "<{}> can't contain {} at {:?}", parent, child, path),
+            Violation::MissingChild {
+                path,
+                parent,
+                child,
+            } => write!(f, "<{}> at {:?} is missing <{}>", parent, path, child),
+        }
+    }
+}
+
+/// Check a tree against the content models of the standard elements.
+///
+/// Use a [`Validator`][Validator] to check trees with elements declared
+/// with [`declare_elements!`][declare_elements].
+///
+/// [Validator]: struct.Validator.html
+/// [declare_elements]: ../macro.declare_elements.html
+pub fn validate<T: OutputType>(tree: &VNode<'_, T>) -> Vec<Violation> {
+    Validator::new().validate(tree)
+}
+
+/// A content model validator, which knows about the standard elements and
+/// any others added to it.
+///
+/// # Examples
+///
+/// ```
+/// # use typed_html::{declare_elements, html};
+/// # use typed_html::dom::{DOMTree, Element};
+/// use typed_html::elements::*;
+/// use typed_html::validate::Validator;
+///
+/// declare_elements! {
+///     badge in [FlowContent, PhrasingContent] with PhrasingContent;
+/// }
+///
+/// # fn main() {
+/// let mut doc: DOMTree<String> = html!(<p><badge>"New"</badge></p>);
+/// let validator = Validator::new().element(badge::<String>::content_model());
+/// assert!(validator.validate(&doc.vnode()).is_empty());
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Validator {
+    models: Vec<&'static ContentModel>,
+}
+
+impl Validator {
+    /// Construct a validator for the standard elements.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add an element's content model, from
+    /// [`Element::content_model()`][content_model].
+    ///
+    /// [content_model]: ../dom/trait.Element.html#tymethod.content_model
+    pub fn element(mut self, model: &'static ContentModel) -> Self {
+        self.models.push(model);
+        self
+    }
+
+    /// Look up the content model for an element.
+    pub fn content_model(&self, name: &str) -> Option<&'static ContentModel> {
+        self.models
+            .iter()
+            .cloned()
+            .chain(CONTENT_MODELS)
+            .find(|model| model.name == name)
+    }
+
+    /// Check a tree, returning every place where it breaks the content
+    /// model, in document order.
+    pub fn validate<T: OutputType>(&self, tree: &VNode<'_, T>) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        if let VNode::Element(element) = tree {
+            self.validate_element(&mut violations, &mut Vec::new(), element);
+        }
+        violations
+    }
+
+    fn validate_element<T: OutputType>(
+        &self,
+        violations: &mut Vec<Violation>,
+        path: &mut Vec<usize>,
+        element: &VElement<'_, T>,
+    ) {
+        let model = self.content_model(element.name);
+        if model.is_none() {
+            violations.push(Violation::UnknownElement {
+                path: path.clone(),
+                name: element.name.to_string(),
+            });
+        }
+
+        let mut children = element
+            .children
+            .iter()
+            .enumerate()
+            .filter(|(_, child)| match child {
+                VNode::Text(text) => !text.trim().is_empty(),
+                VNode::Comment(_) => false,
+                _ => true,
+            })
+            .peekable();
+        if let Some(model) = model {
+            for required in model.required_children {
+                match children.peek() {
+                    Some(&(index, VNode::Element(child))) if child.name == *required => {
+                        path.push(index);
+                        self.validate_element(violations, path, child);
+                        path.pop();
+                        children.next();
+                    }
+                    _ => violations.push(Violation::MissingChild {
+                        path: path.clone(),
+                        parent: element.name.to_string(),
+                        child: required.to_string(),
+                    }),
+                }
+            }
+        }
+
+        for (index, child) in children {
+            path.push(index);
+            if let Some(model) = model {
+                if !model.allows(self, child) {
+                    violations.push(Violation::NotAllowed {
+                        path: path.clone(),
+                        parent: element.name.to_string(),
+                        child: match child {
+                            VNode::Element(child) => format!("<{}>", child.name),
+                            _ => "text".to_string(),
+                        },
+                    });
+                }
+            }
+            if let VNode::Element(child) = child {
+                self.validate_element(violations, path, child);
+            }
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as typed_html;
+    use crate::dom::DOMTree;
+
+    fn element(
+        name: &'static str,
+        children: Vec<VNode<'static, String>>,
+    ) -> VNode<'static, String> {
+        VNode::Element(VElement {
+            name,
+            attributes: Vec::new(),
+            events: Box::leak(Box::default()),
+            children,
+        })
+    }
+
+    #[test]
+    fn test_validate_macro_tree() {
+        let mut doc: DOMTree<String> = html!(
+            <div>
+                <ul><li>"One"</li></ul>
+                <p>"Two " <span>"Three"</span></p>
+                <select><option>"Four"</option></select>
+            </div>
+        );
+        assert!(validate(&doc.vnode()).is_empty());
+    }
+
+    #[test]
+    fn test_validate_violations() {
+        let tree = element(
+            "html",
+            vec![
+                VNode::Text("\n  "),
+                element(
+                    "body",
+                    vec![
+                        element("ul", vec![VNode::Text("One"), element("li", vec![])]),
+                        element("p", vec![element("div", vec![]), VNode::Text(" ")]),
+                        element("frob", vec![]),
+                    ],
+                ),
+            ],
+        );
+        let s = |s: &str| s.to_string();
+        assert_eq!(
+            vec![
+                Violation::MissingChild {
+                    path: vec![],
+                    parent: s("html"),
+                    child: s("head"),
+                },
+                Violation::NotAllowed {
+                    path: vec![1, 0, 0],
+                    parent: s("ul"),
+                    child: s("text"),
+                },
+                Violation::NotAllowed {
+                    path: vec![1, 1, 0],
+                    parent: s("p"),
+                    child: s("<div>"),
+                },
+                Violation::UnknownElement {
+                    path: vec![1, 2],
+                    name: s("frob"),
+                },
+            ],
+            validate(&tree)
+        );
+    }
+
+    #[test]
+    fn test_validate_group_parents() {
+        use crate::elements::PhrasingContent;
+
+        crate::content_group!(InlineWidgets: PhrasingContent);
+        static WIDGET: ContentModel = ContentModel {
+            name: "widget",
+            groups: &[&<dyn InlineWidgets<String> as Group>::GROUP],
+            required_children: &[],
+            children: None,
+        };
+        let group = <dyn InlineWidgets<String> as Group>::GROUP;
+        assert!(group.is_in("FlowContent"));
+        assert!(!group.is_in("TableContent"));
+
+        let tree = element("div", vec![element("p", vec![element("widget", vec![])])]);
+        assert!(Validator::new().element(&WIDGET).validate(&tree).is_empty());
+        let tree = element("ul", vec![element("widget", vec![])]);
+        assert_eq!(
+            vec![Violation::NotAllowed {
+                path: vec![0],
+                parent: "ul".to_string(),
+                child: "<widget>".to_string(),
+            }],
+            Validator::new().element(&WIDGET).validate(&tree)
+        );
+    }
+}
@@ -0,0 +1,75 @@
+//! Vendor attributes, which can be set on any element.
+//!
+//! JavaScript libraries often add their own attributes to every element,
+//! like Alpine's `x-data` or htmx's `hx-get`. The [`vendor!`][vendor] macro
+//! declares a namespace of these attributes and their types, which you can
+//! then use on any element in the [`html!`][html] macro by writing the
+//! namespace and the attribute name separated by a colon:
+//!
+//! ```
+//! # use typed_html::{html, vendor};
+//! # use typed_html::dom::DOMTree;
+//! vendor! {
+//!     /// Attributes for htmx.
+//!     pub hx {
+//!         get: String,
+//!         swap_oob: bool,
+//!     }
+//! }
+//!
+//! # fn main() {
+//! let doc: DOMTree<String> = html!(
+//!     <div hx:get="/news" hx:swap-oob="true">"Loading..."</div>
+//! );
+//! assert_eq!(
+//!     "<div hx-get=\"&#x2F;news\" hx-swap-oob=\"true\">Loading...</div>",
+//!     doc.to_string()
+//! );
+//! # }
+//! ```
+//!
+//! An attribute is rendered as the namespace and its name joined by a
+//! hyphen, and hyphens in the name are written as underscores in its
+//! declaration, so `hx:swap-oob` is declared as `swap_oob` in `hx` and
+//! rendered as `hx-swap-oob`.
+//!
+//! Values are type checked just like other attributes: string literals are
+//! parsed into the attribute's type, and checked at compile time if it's a
+//! type declared with [`attribute!`][attribute], and code blocks are
+//! converted with [`Into::into`][Into::into]. Using an attribute which isn't
+//! declared, or a namespace which isn't in scope, won't compile.
+//!
+//! A namespace is a module of type aliases, one for each attribute, so it
+//! follows the usual scoping rules. Vendor attributes are stored on the
+//! element in `extra_attributes`, and a [`Transform`][Transform] can get at
+//! them with [`UntypedElement::extra_attributes_mut()`][extra_attributes_mut].
+//!
+//! [vendor]: ../macro.vendor.html
+//! [attribute]: ../macro.attribute.html
+//! [html]: ../macro.html.html
+//! [Into::into]: https://doc.rust-lang.org/std/convert/trait.Into.html#method.into
+//! [Transform]: ../transform/trait.Transform.html
+//! [extra_attributes_mut]: ../dom/trait.UntypedElement.html#tymethod.extra_attributes_mut
+
+/// Declare a namespace of vendor attributes.
+///
+/// See the [`vendor`][vendor] module for details.
+///
+/// [vendor]: vendor/index.html
+#[macro_export]
+macro_rules! vendor {
+    ($($(#[$meta:meta])* $vis:vis $namespace:ident { $($key:ident : $type:ty),* $(,)* })*) => {
+        $(
+            $(#[$meta])*
+            #[allow(non_camel_case_types)]
+            $vis mod $namespace {
+                #[allow(unused_imports)]
+                use super::*;
+
+                $(
+                    pub type $key = $type;
+                )*
+            }
+        )*
+    };
+}
@@ -0,0 +1,17 @@
+#![feature(proc_macro_hygiene)]
+
+extern crate typed_html;
+
+use typed_html::html;
+use typed_html::dom::DOMTree;
+
+fn main() {
+    let _: DOMTree<String> = html!{ <div role="checkbox" aria-label="Agree"></div> };
+    let _: DOMTree<String> = html!{ <span role="button" aria-checked="true"></span> };
+    let _: DOMTree<String> = html!{ <button aria-sorted="ascending"></button> };
+    let _: DOMTree<String> = html!{ <h2 aria-valuenow="3">"Progress"</h2> };
+    let _: DOMTree<String> = html!{ <div role="checkbx"></div> };
+    let _: DOMTree<String> = html!{
+        <table role="grid"><tbody><tr><td aria-selected="true">"Cell"</td></tr></tbody></table>
+    };
+}
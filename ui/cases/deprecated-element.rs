@@ -0,0 +1,13 @@
+#![feature(proc_macro_hygiene)]
+#![deny(deprecated)]
+
+extern crate typed_html;
+
+use typed_html::html;
+use typed_html::dom::DOMTree;
+
+fn main() {
+    let _: DOMTree<String> = html!{
+        <marquee>"Hi"</marquee>
+    };
+}
@@ -0,0 +1,15 @@
+#![feature(proc_macro_hygiene)]
+
+extern crate typed_html;
+
+use typed_html::html;
+use typed_html::dom::DOMTree;
+
+fn main() {
+    let _: DOMTree<String> = html!{
+        <head>
+            <title>"Hello Kitty"</title>
+            <meta name="theme-color" content="#ff00zz"/>
+        </head>
+    };
+}
@@ -0,0 +1,12 @@
+#![feature(proc_macro_hygiene)]
+
+extern crate typed_html;
+
+use typed_html::html;
+use typed_html::dom::DOMTree;
+
+fn main() {
+    let _: DOMTree<String> = html!{
+        <button raw_attrs={vec![("@click", "open = true"), ("on click", "")]}>"Open"</button>
+    };
+}
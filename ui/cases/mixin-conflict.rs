@@ -0,0 +1,19 @@
+#![feature(proc_macro_hygiene)]
+
+extern crate typed_html;
+
+use typed_html::{html, mixin};
+use typed_html::dom::DOMTree;
+
+mixin! {
+    ExternalLink {
+        target: "_blank",
+        rel: "noopener noreferrer",
+    }
+}
+
+fn main() {
+    let _: DOMTree<String> = html!{
+        <a href="https://example.com/" target="_self" {..ExternalLink}>"Example"</a>
+    };
+}